@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::{
+    changes::{Change, MoveToBackup},
+    discovery::detect_project_plugins,
+};
+
+/// Directories generated by the engine, build tools and IDEs, next to a
+/// project or plugin descriptor.
+pub const GENERATED_DIR_NAMES: &[&str] = &["Binaries", "Intermediate", ".vs", ".idea"];
+
+/// Extensions of generated project files in the project root.
+const GENERATED_FILE_EXTENSIONS: &[&str] = &["sln", "xcworkspace"];
+
+/// Detect generated build artifacts of a project and its plugins. They
+/// reference module, target and project names, so they become stale when
+/// any of these are renamed, and can be regenerated from the sources.
+pub fn detect_artifacts(project_root: &Path) -> Vec<PathBuf> {
+    let mut artifacts = generated_dirs(project_root);
+    artifacts.extend(generated_files(project_root));
+    artifacts.sort();
+    artifacts
+}
+
+/// Detect generated build artifacts of a project and its plugins that are
+/// named after `name`, such as `Name.sln`, `Binaries/Win64/UnrealEditor-Name.dll`
+/// or `Intermediate/ProjectFiles/NameEditor.vcxproj`. Only these become stale
+/// when the project, plugin, module or target called `name` is renamed.
+pub fn detect_stale_artifacts(project_root: &Path, name: &str) -> Vec<PathBuf> {
+    let mut artifacts: Vec<PathBuf> = generated_files(project_root)
+        .into_iter()
+        .filter(|path| is_named_after(path, name))
+        .collect();
+    for dir in generated_dirs(project_root) {
+        let mut entries = WalkDir::new(dir).min_depth(1).into_iter();
+        while let Some(entry) = entries.next() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            if is_named_after(entry.path(), name) {
+                artifacts.push(entry.path().to_owned());
+                if entry.file_type().is_dir() {
+                    entries.skip_current_dir();
+                }
+            }
+        }
+    }
+    artifacts.sort();
+    artifacts
+}
+
+/// Generated directories of the project and each of its plugins.
+fn generated_dirs(project_root: &Path) -> Vec<PathBuf> {
+    let mut dirs = generated_dirs_of(project_root);
+    dirs.extend(
+        detect_project_plugins(project_root)
+            .iter()
+            .flat_map(|plugin| generated_dirs_of(&plugin.root)),
+    );
+    dirs
+}
+
+fn generated_dirs_of(root: &Path) -> Vec<PathBuf> {
+    GENERATED_DIR_NAMES
+        .iter()
+        .map(|name| root.join(name))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Generated project files in the project root.
+fn generated_files(project_root: &Path) -> Vec<PathBuf> {
+    project_root
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| GENERATED_FILE_EXTENSIONS.contains(&ext))
+        })
+        .collect()
+}
+
+/// Suffixes of target names derived from a project name.
+const TARGET_SUFFIXES: &[&str] = &["", "Editor", "Game", "Client", "Server"];
+
+/// Whether a file name refers to `name` or a target derived from it in one of
+/// its `-` or `.` separated parts.
+fn is_named_after(path: &Path, name: &str) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| {
+            file_name.split(['-', '.']).any(|part| {
+                part.strip_prefix(name)
+                    .is_some_and(|suffix| TARGET_SUFFIXES.contains(&suffix))
+            })
+        })
+}
+
+/// Generate a changeset that moves the build artifacts of a project into the
+/// backup directory of the run.
+pub fn generate_clean_changeset(project_root: &Path) -> Vec<Change> {
+    detect_artifacts(project_root)
+        .into_iter()
+        .map(|artifact| Change::MoveToBackup(MoveToBackup::new(artifact)))
+        .collect()
+}
+
+/// Generate a changeset that moves the build artifacts of a project that
+/// are named after `name` into the backup directory of the run.
+pub fn generate_stale_artifacts_changeset(project_root: &Path, name: &str) -> Vec<Change> {
+    detect_stale_artifacts(project_root, name)
+        .into_iter()
+        .map(|artifact| Change::MoveToBackup(MoveToBackup::new(artifact)))
+        .collect()
+}
@@ -0,0 +1,526 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+use walkdir::WalkDir;
+
+use crate::{
+    config::Config,
+    presentation::{log, reporter::Reporter},
+    snapshot::{self, Snapshot},
+};
+
+/// Name of the manifest file stored in each backup run directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.toml";
+
+/// Name of the directory within a backup run that holds moved files and
+/// directories.
+pub const MOVED_DIR_NAME: &str = "moved";
+
+/// Extension of compressed backup run archives.
+pub const ARCHIVE_EXTENSION: &str = "tar.gz";
+
+/// Path of the file, relative to the project root, that stores the id under
+/// which backups kept outside the project are grouped.
+pub const PROJECT_ID_FILE_PATH: &str = ".renom/id";
+
+/// Environment variable that overrides the backup location.
+pub const BACKUP_DIR_ENV_VAR: &str = "RENOM_BACKUP_DIR";
+
+/// Environment variable that enables compressed backup archives.
+pub const COMPRESS_BACKUPS_ENV_VAR: &str = "RENOM_COMPRESS_BACKUPS";
+
+/// Where and how backups are stored. Options that are not set fall back to
+/// the environment, then to the `[backup]` table of the project config file
+/// and finally to storing uncompressed backups in `.renom/backup` within the
+/// project.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackupOptions {
+    /// Directory under which backups of all projects are stored, grouped by
+    /// project id.
+    pub dir: Option<PathBuf>,
+    /// Whether each run should be stored as a single compressed archive.
+    pub compress: bool,
+    /// Whether the affected subtrees of the project should be captured in
+    /// full before each run.
+    pub snapshot: bool,
+}
+
+impl BackupOptions {
+    /// Fill in unset options for a project from the environment and the
+    /// project config file.
+    pub fn resolve(&self, project_root: &Path) -> Result<BackupOptions, String> {
+        let config = Config::load(project_root)?;
+        let dir = match self
+            .dir
+            .clone()
+            .or_else(|| env::var_os(BACKUP_DIR_ENV_VAR).map(PathBuf::from))
+        {
+            Some(dir) => Some(dir),
+            None => match config.backup.dir {
+                Some(dir) => Some(resolve_config_dir(project_root, &dir)?),
+                None => None,
+            },
+        };
+        let compress = self.compress
+            || env::var(COMPRESS_BACKUPS_ENV_VAR).is_ok_and(|value| is_truthy(&value))
+            || config.backup.compress;
+        let snapshot = self.snapshot || config.backup.snapshot;
+        Ok(BackupOptions {
+            dir,
+            compress,
+            snapshot,
+        })
+    }
+}
+
+/// Resolve a directory from the config file relative to the project root.
+/// The result does not depend on the project root path staying valid, since
+/// some workflows rename it.
+fn resolve_config_dir(project_root: &Path, dir: &Path) -> Result<PathBuf, String> {
+    let project_root = fs::canonicalize(project_root).map_err(|err| err.to_string())?;
+    let mut resolved = PathBuf::new();
+    for component in project_root.join(dir).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    Ok(resolved)
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+/// Description of a single backup run, stored alongside its backup files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Unique, time-ordered identifier of the run.
+    pub id: String,
+    /// Time at which the run was started, in RFC 3339 format.
+    pub created: String,
+    /// The workflow that produced the run.
+    pub workflow: String,
+    /// Human-readable description of the run.
+    pub description: String,
+    /// Absolute path to the project root at the time of the run.
+    pub project_root: PathBuf,
+    /// Files backed up during the run, in the order they were backed up.
+    #[serde(default)]
+    pub files: Vec<Entry>,
+    /// Files and directories moved into the run, such as stale build
+    /// artifacts. Their blob is their path within the run directory.
+    #[serde(default)]
+    pub moved: Vec<Entry>,
+    /// Snapshot of the affected subtrees, if one was taken.
+    #[serde(default)]
+    pub snapshot: Option<Snapshot>,
+}
+
+/// A single file backed up during a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Path of the original file, relative to the project root.
+    pub original: PathBuf,
+    /// Name of the backup file within the run directory.
+    pub blob: String,
+}
+
+impl Manifest {
+    /// Load the manifest of the backup run stored in `run_dir`.
+    pub fn load(run_dir: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(run_dir.join(MANIFEST_FILE_NAME))?;
+        toml::from_str(&content).map_err(io::Error::other)
+    }
+
+    /// Load the manifest of the backup run stored in the archive `archive`.
+    pub fn load_from_archive(archive: &Path) -> io::Result<Self> {
+        let content = read_from_archive(archive, MANIFEST_FILE_NAME)?;
+        let content = String::from_utf8(content).map_err(io::Error::other)?;
+        toml::from_str(&content).map_err(io::Error::other)
+    }
+
+    /// Save the manifest into `run_dir`.
+    pub fn save(&self, run_dir: &Path) -> io::Result<()> {
+        let content = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(run_dir.join(MANIFEST_FILE_NAME), content)
+    }
+
+    /// The time at which the run was started.
+    pub fn created_at(&self) -> Option<DateTime<Local>> {
+        DateTime::parse_from_rfc3339(&self.created)
+            .ok()
+            .map(|created| created.with_timezone(&Local))
+    }
+
+    /// Entries for each distinct original file, keeping only the earliest
+    /// backup of each file since it reflects the state before the run.
+    pub fn original_entries(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = vec![];
+        for entry in &self.files {
+            if entries.iter().all(|other| other.original != entry.original) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+}
+
+/// The directory that holds all backup runs for a project. Backups stored
+/// outside the project are grouped by the id of the project, which stays the
+/// same when the project is renamed or moved and differs between projects
+/// that share a name.
+pub fn backup_root(project_root: &Path, options: &BackupOptions) -> Result<PathBuf, String> {
+    match &options.dir {
+        None => Ok(project_root.join(".renom/backup")),
+        Some(dir) => Ok(dir.join(project_id(project_root)?)),
+    }
+}
+
+/// Read the id of a project from `.renom/id`, generating and storing a new
+/// one if the project does not have one yet.
+fn project_id(project_root: &Path) -> Result<String, String> {
+    let id_file = project_root.join(PROJECT_ID_FILE_PATH);
+    if let Ok(id) = fs::read_to_string(&id_file) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return Ok(id.into());
+        }
+    }
+    let id = Ulid::new().to_string();
+    fs::create_dir_all(id_file.parent().unwrap()).map_err(|err| err.to_string())?;
+    fs::write(&id_file, format!("{}\n", id)).map_err(|err| err.to_string())?;
+    Ok(id)
+}
+
+/// Create a directory for a new backup run and write its manifest.
+pub fn create_backup_dir(
+    project_root: &Path,
+    options: &BackupOptions,
+    workflow: &str,
+    description: &str,
+    reporter: &dyn Reporter,
+) -> Result<PathBuf, String> {
+    let id = Ulid::new().to_string();
+    let backup_dir = backup_root(project_root, options)?.join(&id);
+    log::verbose_with_category(
+        reporter,
+        "backup",
+        format!("Creating backup directory: {:?}", backup_dir),
+    );
+    fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
+    let manifest = Manifest {
+        id,
+        created: Local::now().to_rfc3339(),
+        workflow: workflow.into(),
+        description: description.into(),
+        project_root: fs::canonicalize(project_root).map_err(|err| err.to_string())?,
+        files: vec![],
+        moved: vec![],
+        snapshot: None,
+    };
+    manifest.save(&backup_dir).map_err(|err| err.to_string())?;
+    log::verbose_with_category(reporter, "backup", "Backup directory created successfully");
+    Ok(backup_dir)
+}
+
+/// Finish a backup run once it no longer needs to be read from during
+/// revert. If compression is enabled, the run directory is replaced by a
+/// single compressed archive.
+pub fn finalize_backup_dir(
+    backup_dir: &Path,
+    options: &BackupOptions,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    if !options.compress {
+        return Ok(());
+    }
+
+    let archive = backup_dir.with_extension(ARCHIVE_EXTENSION);
+    log::verbose_with_category(
+        reporter,
+        "backup",
+        format!("Compressing backup into {:?}", archive),
+    );
+    let file = File::create(&archive).map_err(|err| err.to_string())?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder
+        .append_dir_all(".", backup_dir)
+        .and_then(|_| builder.into_inner())
+        .and_then(|encoder| encoder.finish())
+        .map_err(|err| err.to_string())?;
+    fs::remove_dir_all(backup_dir).map_err(|err| err.to_string())?;
+    log::verbose_with_category(reporter, "backup", "Backup compressed successfully");
+    Ok(())
+}
+
+fn read_from_archive(archive: &Path, name: &str) -> io::Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.file_name() == Some(OsStr::new(name)) {
+            let mut content = vec![];
+            entry.read_to_end(&mut content)?;
+            return Ok(content);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} is not part of archive", name),
+    ))
+}
+
+/// Record that `original` was backed up as `blob` in the manifest of the
+/// run stored in `backup_dir`. Does nothing if the directory has no
+/// manifest.
+pub fn record(backup_dir: &Path, original: &Path, blob: &str) -> io::Result<()> {
+    if !backup_dir.join(MANIFEST_FILE_NAME).is_file() {
+        return Ok(());
+    }
+
+    let mut manifest = Manifest::load(backup_dir)?;
+    let original = fs::canonicalize(original)?;
+    let original = original
+        .strip_prefix(&manifest.project_root)
+        .map(Path::to_owned)
+        .unwrap_or(original);
+    manifest.files.push(Entry {
+        original,
+        blob: blob.into(),
+    });
+    manifest.save(backup_dir)
+}
+
+/// Record that `original` was moved to `stashed`, relative to `backup_dir`,
+/// in the manifest of the run stored in `backup_dir`. Does nothing if the
+/// directory has no manifest.
+pub fn record_moved(backup_dir: &Path, original: &Path, stashed: &Path) -> io::Result<()> {
+    if !backup_dir.join(MANIFEST_FILE_NAME).is_file() {
+        return Ok(());
+    }
+
+    let mut manifest = Manifest::load(backup_dir)?;
+    let original = fs::canonicalize(original)?;
+    let original = original
+        .strip_prefix(&manifest.project_root)
+        .map(Path::to_owned)
+        .unwrap_or(original);
+    manifest.moved.push(Entry {
+        original,
+        blob: stashed
+            .to_str()
+            .ok_or_else(|| io::Error::other("invalid Unicode path"))?
+            .into(),
+    });
+    manifest.save(backup_dir)
+}
+
+/// Copy a file or directory tree from `from` to `to`.
+pub fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(from) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(from).expect("entry should be in tree");
+        // Joining an empty path would add a trailing separator.
+        let target = match relative.as_os_str().is_empty() {
+            true => to.to_owned(),
+            false => to.join(relative),
+        };
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// List all backup runs of a project, oldest first. Directories without a
+/// manifest, such as backups made by older versions, are skipped.
+pub fn list_runs(project_root: &Path, options: &BackupOptions) -> Result<Vec<Manifest>, String> {
+    let backup_root = backup_root(project_root, options)?;
+    if !backup_root.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut runs: Vec<Manifest> = fs::read_dir(&backup_root)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter_map(|path| match is_archive(&path) {
+            true => Manifest::load_from_archive(&path).ok(),
+            false => Manifest::load(&path).ok(),
+        })
+        .collect();
+    runs.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(runs)
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .to_str()
+            .is_some_and(|path| path.ends_with(&format!(".{}", ARCHIVE_EXTENSION)))
+}
+
+/// Find a backup run of a project by its identifier.
+pub fn find_run(project_root: &Path, options: &BackupOptions, id: &str) -> Result<Manifest, String> {
+    list_runs(project_root, options)?
+        .into_iter()
+        .find(|run| run.id.eq_ignore_ascii_case(id))
+        .ok_or_else(|| format!("backup run {} does not exist", id))
+}
+
+/// Restore a single file of a backup run into the project. `original` is
+/// the path of the file relative to the project root.
+pub fn restore_file(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &Manifest,
+    original: &Path,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let entry = run
+        .original_entries()
+        .into_iter()
+        .find(|entry| entry.original == original)
+        .ok_or_else(|| format!("file {:?} is not part of backup run {}", original, run.id))?;
+    let run_dir = backup_root(project_root, options)?.join(&run.id);
+    let archive = run_dir.with_extension(ARCHIVE_EXTENSION);
+    let content = match archive.is_file() {
+        true => read_from_archive(&archive, &entry.blob),
+        false => fs::read(run_dir.join(&entry.blob)),
+    }
+    .map_err(|err| err.to_string())?;
+    let target = project_root.join(&entry.original);
+    log::verbose_with_category(
+        reporter,
+        "backup",
+        format!("Restoring {:?} from run {}", target, run.id),
+    );
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    fs::write(&target, content).map_err(|err| err.to_string())
+}
+
+/// Restore every file of a backup run into the project, along with moved
+/// files and directories that no longer exist in the project.
+pub fn restore_run(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &Manifest,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    for entry in run.original_entries() {
+        restore_file(project_root, options, run, &entry.original, reporter)?;
+    }
+    if run.moved.is_empty() {
+        return Ok(());
+    }
+
+    with_run_dir(project_root, options, run, reporter, |run_dir| {
+        for entry in &run.moved {
+            let target = project_root.join(&entry.original);
+            if target.exists() {
+                continue;
+            }
+            log::verbose_with_category(reporter, "backup", format!("Restoring {:?} from run {}", target, run.id));
+            copy_path(&run_dir.join(&entry.blob), &target).map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    })
+}
+
+/// Restore the snapshot of a backup run into the project.
+pub fn restore_run_snapshot(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &Manifest,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    with_run_dir(project_root, options, run, reporter, |run_dir| {
+        snapshot::restore_snapshot(project_root, run_dir, reporter)
+    })
+}
+
+/// Call `f` with the directory of a backup run. Compressed runs are extracted
+/// for the duration of the call.
+fn with_run_dir(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &Manifest,
+    reporter: &dyn Reporter,
+    f: impl FnOnce(&Path) -> Result<(), String>,
+) -> Result<(), String> {
+    let run_dir = backup_root(project_root, options)?.join(&run.id);
+    let archive = run_dir.with_extension(ARCHIVE_EXTENSION);
+    if !archive.is_file() {
+        return f(&run_dir);
+    }
+
+    log::verbose_with_category(reporter, "backup", format!("Extracting {:?}", archive));
+    let file = File::open(&archive).map_err(|err| err.to_string())?;
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(&run_dir)
+        .map_err(|err| err.to_string())?;
+    let result = f(&run_dir);
+    fs::remove_dir_all(&run_dir).map_err(|err| err.to_string())?;
+    result
+}
+
+/// Delete backup runs older than `older_than_days` and all but the newest
+/// `keep` runs. Returns the runs that were deleted.
+pub fn prune(
+    project_root: &Path,
+    options: &BackupOptions,
+    older_than_days: Option<u32>,
+    keep: Option<usize>,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Manifest>, String> {
+    let backup_root = backup_root(project_root, options)?;
+    let runs = list_runs(project_root, options)?;
+    let keep_from = keep.map_or(0, |keep| runs.len().saturating_sub(keep));
+    // A cutoff before the earliest representable date leaves every run too
+    // recent to prune.
+    let cutoff = older_than_days
+        .map(|days| Local::now().checked_sub_signed(Duration::days(days.into())));
+    let mut pruned = vec![];
+    for (idx, run) in runs.into_iter().enumerate() {
+        let too_many = idx < keep_from;
+        let too_old = match (cutoff, run.created_at()) {
+            (Some(Some(cutoff)), Some(created)) => created < cutoff,
+            _ => false,
+        };
+        if too_many || too_old {
+            let run_dir = backup_root.join(&run.id);
+            let archive = run_dir.with_extension(ARCHIVE_EXTENSION);
+            log::verbose_with_category(
+                reporter,
+                "backup",
+                format!("Deleting backup run {}", run.id),
+            );
+            match archive.is_file() {
+                true => fs::remove_file(&archive),
+                false => fs::remove_dir_all(&run_dir),
+            }
+            .map_err(|err| err.to_string())?;
+            pruned.push(run);
+        }
+    }
+    Ok(pruned)
+}
@@ -0,0 +1,49 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Add an entry to an array of a project or plugin descriptor, such as
+/// `Modules` or `Plugins`, keeping the rest of the descriptor as it is.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AddDescriptorEntry {
+    pub path: PathBuf,
+    /// The array to add the entry to.
+    pub array: String,
+    /// The name of the module or plugin that the entry describes.
+    pub name: String,
+    /// The entry as JSON text, such as returned by
+    /// [`descriptor::module_entry`](crate::descriptor::module_entry).
+    pub entry: String,
+}
+
+impl AddDescriptorEntry {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        array: impl Into<String>,
+        name: impl Into<String>,
+        entry: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            array: array.into(),
+            name: name.into(),
+            entry: entry.into(),
+        }
+    }
+}
+
+impl Display for AddDescriptorEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "add {} to {} of descriptor {}",
+            &self.name.dimmed(),
+            &self.array,
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
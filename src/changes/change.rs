@@ -4,66 +4,229 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use ini::{Ini, ParseOption};
+use ini::{ini::Properties, Ini, ParseOption};
 use regex::Regex;
 use sha2::{Digest, Sha256};
 
-use crate::presentation::log;
-use super::{rename_file::RenameFile, AppendIniEntry, ReplaceInFile, SetIniEntry};
+use crate::{
+    backup, cpp::ImplementMacro, csharp, descriptor,
+    presentation::{log, reporter::Reporter},
+    redirects,
+};
+use super::{
+    rename_file::RenameFile, AddDescriptorEntry, AppendIniEntry, CompactRedirects, CopyDir, CopyFile, CreateDir, CreateFile, DeleteFile,
+    MoveToBackup, RemoveCsListEntries, RemoveDescriptorEntry, RemoveIniEntry, RenameImplementMacro, ReplaceCsReferences,
+    ReplaceDescriptorListValues, ReplaceInFile, ReplaceIniValue, SetIniEntry,
+};
 
 #[derive(Debug, PartialEq)]
 pub enum Change {
     RenameFile(RenameFile),
     ReplaceInFile(ReplaceInFile),
+    ReplaceCsReferences(ReplaceCsReferences),
     SetIniEntry(SetIniEntry),
     AppendIniEntry(AppendIniEntry),
+    MoveToBackup(MoveToBackup),
+    CreateFile(CreateFile),
+    DeleteFile(DeleteFile),
+    CopyFile(CopyFile),
+    CopyDir(CopyDir),
+    CreateDir(CreateDir),
+    RemoveIniEntry(RemoveIniEntry),
+    ReplaceIniValue(ReplaceIniValue),
+    CompactRedirects(CompactRedirects),
+    AddDescriptorEntry(AddDescriptorEntry),
+    RemoveDescriptorEntry(RemoveDescriptorEntry),
+    ReplaceDescriptorListValues(ReplaceDescriptorListValues),
+    RemoveCsListEntries(RemoveCsListEntries),
+    RenameImplementMacro(RenameImplementMacro),
 }
 
 impl Change {
-    pub fn apply(&self, backup_dir: &Path) -> io::Result<Revert> {
-        match &*self {
-            Change::RenameFile(params) => Change::rename_file(&params),
-            Change::ReplaceInFile(params) => Change::replace_in_file(params, backup_dir),
-            Change::SetIniEntry(params) => Change::set_ini_entry(params, backup_dir),
-            Change::AppendIniEntry(params) => Change::append_ini_entry(params, backup_dir),
+    pub fn apply(&self, backup_dir: &Path, reporter: &dyn Reporter) -> io::Result<Revert> {
+        match self {
+            Change::RenameFile(params) => Change::rename_file(params, reporter),
+            Change::ReplaceInFile(params) => Change::replace_in_file(params, backup_dir, reporter),
+            Change::ReplaceCsReferences(params) => {
+                Change::replace_cs_references(params, backup_dir, reporter)
+            }
+            Change::SetIniEntry(params) => Change::set_ini_entry(params, backup_dir, reporter),
+            Change::AppendIniEntry(params) => {
+                Change::append_ini_entry(params, backup_dir, reporter)
+            }
+            Change::MoveToBackup(params) => Change::move_to_backup(params, backup_dir, reporter),
+            Change::CreateFile(params) => Change::create_file(params, reporter),
+            Change::DeleteFile(params) => Change::delete_file(params, backup_dir, reporter),
+            Change::CopyFile(params) => Change::copy_file(params, reporter),
+            Change::CopyDir(params) => Change::copy_dir(params, reporter),
+            Change::CreateDir(params) => Change::create_dir(params, reporter),
+            Change::RemoveIniEntry(params) => {
+                Change::remove_ini_entry(params, backup_dir, reporter)
+            }
+            Change::ReplaceIniValue(params) => {
+                Change::replace_ini_value(params, backup_dir, reporter)
+            }
+            Change::CompactRedirects(params) => {
+                Change::compact_redirects(params, backup_dir, reporter)
+            }
+            Change::AddDescriptorEntry(params) => {
+                Change::add_descriptor_entry(params, backup_dir, reporter)
+            }
+            Change::RemoveDescriptorEntry(params) => {
+                Change::remove_descriptor_entry(params, backup_dir, reporter)
+            }
+            Change::ReplaceDescriptorListValues(params) => {
+                Change::replace_descriptor_list_values(params, backup_dir, reporter)
+            }
+            Change::RemoveCsListEntries(params) => {
+                Change::remove_cs_list_entries(params, backup_dir, reporter)
+            }
+            Change::RenameImplementMacro(params) => {
+                Change::rename_implement_macro(params, backup_dir, reporter)
+            }
+        }
+    }
+
+    /// Short machine-readable name of the kind of change.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Change::RenameFile(_) => "rename_file",
+            Change::ReplaceInFile(_) => "replace_in_file",
+            Change::ReplaceCsReferences(_) => "replace_cs_references",
+            Change::SetIniEntry(_) => "set_ini_entry",
+            Change::AppendIniEntry(_) => "append_ini_entry",
+            Change::MoveToBackup(_) => "move_to_backup",
+            Change::CreateFile(_) => "create_file",
+            Change::DeleteFile(_) => "delete_file",
+            Change::CopyFile(_) => "copy_file",
+            Change::CopyDir(_) => "copy_dir",
+            Change::CreateDir(_) => "create_dir",
+            Change::RemoveIniEntry(_) => "remove_ini_entry",
+            Change::ReplaceIniValue(_) => "replace_ini_value",
+            Change::CompactRedirects(_) => "compact_redirects",
+            Change::AddDescriptorEntry(_) => "add_descriptor_entry",
+            Change::RemoveDescriptorEntry(_) => "remove_descriptor_entry",
+            Change::ReplaceDescriptorListValues(_) => "replace_descriptor_list_values",
+            Change::RemoveCsListEntries(_) => "remove_cs_list_entries",
+            Change::RenameImplementMacro(_) => "rename_implement_macro",
         }
     }
 
-    fn rename_file(params: &RenameFile) -> io::Result<Revert> {
+    /// Paths affected by the change.
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            Change::RenameFile(params) => vec![&params.from, &params.to],
+            Change::ReplaceInFile(params) => vec![&params.path],
+            Change::ReplaceCsReferences(params) => vec![&params.path],
+            Change::SetIniEntry(params) => vec![&params.path],
+            Change::AppendIniEntry(params) => vec![&params.path],
+            Change::MoveToBackup(params) => vec![&params.path],
+            Change::CreateFile(params) => vec![&params.path],
+            Change::DeleteFile(params) => vec![&params.path],
+            Change::CopyFile(params) => vec![&params.from, &params.to],
+            Change::CopyDir(params) => vec![&params.from, &params.to],
+            Change::CreateDir(params) => vec![&params.path],
+            Change::RemoveIniEntry(params) => vec![&params.path],
+            Change::ReplaceIniValue(params) => vec![&params.path],
+            Change::CompactRedirects(params) => vec![&params.path],
+            Change::AddDescriptorEntry(params) => vec![&params.path],
+            Change::RemoveDescriptorEntry(params) => vec![&params.path],
+            Change::ReplaceDescriptorListValues(params) => vec![&params.path],
+            Change::RemoveCsListEntries(params) => vec![&params.path],
+            Change::RenameImplementMacro(params) => vec![&params.path],
+        }
+    }
+
+    fn rename_file(params: &RenameFile, reporter: &dyn Reporter) -> io::Result<Revert> {
         let from = params.from.clone();
         let to = params.to.clone();
-        log::verbose_with_category("rename_file", format!("Renaming {:?} -> {:?}", from, to));
+        log::verbose_with_category(
+            reporter,
+            "rename_file",
+            format!("Renaming {:?} -> {:?}", from, to),
+        );
         std::fs::rename(&from, &to)?;
-        log::verbose_with_category("rename_file", "File renamed successfully");
+        log::verbose_with_category(reporter, "rename_file", "File renamed successfully");
 
         Ok(Box::new(move || std::fs::rename(&to, &from)))
     }
 
-    fn replace_in_file(params: &ReplaceInFile, backup_dir: &Path) -> io::Result<Revert> {
-        log::verbose_with_category("replace_in_file", format!("Processing file: {:?}", params.path));
-        log::verbose_with_category("replace_in_file", format!("Pattern: {}", params.from));
-        log::verbose_with_category("replace_in_file", format!("Replacement: {}", params.to));
-        let backup = Change::backup_file(&params.path, backup_dir)?;
+    fn replace_in_file(
+        params: &ReplaceInFile,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "replace_in_file", format!("Processing file: {:?}", params.path));
+        log::verbose_with_category(
+            reporter,
+            "replace_in_file",
+            format!("Pattern: {}", params.from),
+        );
+        log::verbose_with_category(
+            reporter,
+            "replace_in_file",
+            format!("Replacement: {}", params.to),
+        );
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
         let target = params.path.clone();
-        log::verbose_with_category("replace_in_file", "Reading file content");
+        log::verbose_with_category(reporter, "replace_in_file", "Reading file content");
         // Read as raw bytes and convert to string with lossy UTF-8 conversion to support any encoding
         let bytes = std::fs::read(&target)?;
         let content = String::from_utf8_lossy(&bytes);
-        log::verbose_with_category("replace_in_file", format!("File size: {} bytes", content.len()));
+        log::verbose_with_category(reporter, "replace_in_file", format!("File size: {} bytes", content.len()));
         let regex = Regex::new(&params.from).expect("regex should be valid");
         let content_after_replace = regex.replace_all(&content, params.to.as_str()).to_string();
         let num_replacements = content.len().saturating_sub(content_after_replace.len()).abs_diff(content_after_replace.len().saturating_sub(content.len()));
-        log::verbose_with_category("replace_in_file", format!("Replacements made, size changed by {} bytes", num_replacements));
-        log::verbose_with_category("replace_in_file", "Writing modified content");
+        log::verbose_with_category(reporter, "replace_in_file", format!("Replacements made, size changed by {} bytes", num_replacements));
+        log::verbose_with_category(reporter, "replace_in_file", "Writing modified content");
+        std::fs::write(&target, &content_after_replace)?;
+        log::verbose_with_category(reporter, "replace_in_file", "File replacement completed");
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn replace_cs_references(
+        params: &ReplaceCsReferences,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "replace_cs_references", format!("Processing file: {:?}", params.path));
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        let bytes = std::fs::read(&target)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let in_properties = !params.properties.is_empty();
+        let content = csharp::replace_references(
+            &content,
+            &params.from,
+            &params.to,
+            params.identifiers,
+            params.strings && !in_properties,
+        );
+        let content_after_replace = match params.strings && in_properties {
+            true => csharp::replace_property_references(
+                &content,
+                &params.from,
+                &params.to,
+                &params.properties,
+            ),
+            false => content,
+        };
+        log::verbose_with_category(reporter, "replace_cs_references", "Writing modified content");
         std::fs::write(&target, &content_after_replace)?;
-        log::verbose_with_category("replace_in_file", "File replacement completed");
 
         Ok(Box::new(move || {
             std::fs::copy(&backup, &target).map(|_| ())
         }))
     }
 
-    fn set_ini_entry(params: &SetIniEntry, backup_dir: &Path) -> io::Result<Revert> {
+    fn set_ini_entry(
+        params: &SetIniEntry,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
         let SetIniEntry {
             section,
             key,
@@ -71,35 +234,33 @@ impl Change {
             path,
         } = params;
 
-        log::verbose_with_category("set_ini_entry", format!("Processing INI file: {:?}", path));
-        log::verbose_with_category("set_ini_entry", format!("Section: [{}], Key: {}, Value: {}", section, key, value));
-        let backup = Change::backup_file(path, backup_dir)?;
+        log::verbose_with_category(
+            reporter,
+            "set_ini_entry",
+            format!("Processing INI file: {:?}", path),
+        );
+        log::verbose_with_category(reporter, "set_ini_entry", format!("Section: [{}], Key: {}, Value: {}", section, key, value));
+        let backup = Change::backup_file(path, backup_dir, reporter)?;
         let target = path.clone();
 
-        let read_opts = ParseOption {
-            enabled_escape: false,
-            enabled_quote: false,
-        };
-        log::verbose_with_category("set_ini_entry", "Loading INI file");
-        let mut ini = match Ini::load_from_file_opt(&target, read_opts) {
-            Ok(ini) => ini,
-            Err(err) => match err {
-                ini::ini::Error::Io(io) => return Err(io),
-                ini::ini::Error::Parse(p) => return Err(io::Error::new(io::ErrorKind::Other, p)),
-            },
-        };
-        log::verbose_with_category("set_ini_entry", "Setting INI entry");
+        log::verbose_with_category(reporter, "set_ini_entry", "Loading INI file");
+        let mut ini = load_ini(&target)?;
+        log::verbose_with_category(reporter, "set_ini_entry", "Setting INI entry");
         ini.with_section(Some(section)).set(key, value);
-        log::verbose_with_category("set_ini_entry", "Writing INI file");
+        log::verbose_with_category(reporter, "set_ini_entry", "Writing INI file");
         ini.write_to_file(&target)?;
-        log::verbose_with_category("set_ini_entry", "INI entry set successfully");
+        log::verbose_with_category(reporter, "set_ini_entry", "INI entry set successfully");
 
         Ok(Box::new(move || {
             std::fs::copy(&backup, &target).map(|_| ())
         }))
     }
 
-    fn append_ini_entry(params: &AppendIniEntry, backup_dir: &Path) -> io::Result<Revert> {
+    fn append_ini_entry(
+        params: &AppendIniEntry,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
         let AppendIniEntry {
             section,
             key,
@@ -107,59 +268,520 @@ impl Change {
             path,
         } = params;
 
-        log::verbose_with_category("append_ini_entry", format!("Processing INI file: {:?}", path));
-        log::verbose_with_category("append_ini_entry", format!("Section: [{}], Appending Key: {}, Value: {}", section, key, value));
-        let backup = Change::backup_file(path, backup_dir)?;
+        log::verbose_with_category(
+            reporter,
+            "append_ini_entry",
+            format!("Processing INI file: {:?}", path),
+        );
+        log::verbose_with_category(reporter, "append_ini_entry", format!("Section: [{}], Appending Key: {}, Value: {}", section, key, value));
+        let backup = Change::backup_file(path, backup_dir, reporter)?;
         let target = path.clone();
 
-        let read_opts = ParseOption {
-            enabled_escape: false,
-            enabled_quote: false,
-        };
-        log::verbose_with_category("append_ini_entry", "Loading INI file");
-        let mut ini = match Ini::load_from_file_opt(&target, read_opts) {
-            Ok(ini) => ini,
-            Err(err) => match err {
-                ini::ini::Error::Io(io) => return Err(io),
-                ini::ini::Error::Parse(p) => return Err(io::Error::new(io::ErrorKind::Other, p)),
-            },
-        };
-        log::verbose_with_category("append_ini_entry", "Appending INI entry");
+        log::verbose_with_category(reporter, "append_ini_entry", "Loading INI file");
+        let mut ini = load_ini(&target)?;
+        log::verbose_with_category(reporter, "append_ini_entry", "Appending INI entry");
         ini.with_section(Some(section)).set("dummy", "dummy"); // create if does not exist
         ini.section_mut(Some(section)).unwrap().append(key, value);
         ini.with_section(Some(section)).delete(&"dummy");
-        log::verbose_with_category("append_ini_entry", "Writing INI file");
+        log::verbose_with_category(reporter, "append_ini_entry", "Writing INI file");
         ini.write_to_file(&params.path)?;
-        log::verbose_with_category("append_ini_entry", "INI entry appended successfully");
+        log::verbose_with_category(reporter, "append_ini_entry", "INI entry appended successfully");
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn move_to_backup(
+        params: &MoveToBackup,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        let path = params.path.clone();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::other(format!("{:?} has no file name", path)))?;
+        // Files with the same name may be moved from different directories.
+        let hash = Sha256::digest(path.to_string_lossy().as_bytes());
+        let stashed = PathBuf::from(backup::MOVED_DIR_NAME)
+            .join(format!("{:x}", hash))
+            .join(file_name);
+        let target = backup_dir.join(&stashed);
+        log::verbose_with_category(
+            reporter,
+            "move_to_backup",
+            format!("Moving {:?} -> {:?}", path, target),
+        );
+        backup::record_moved(backup_dir, &path, &stashed)?;
+        std::fs::create_dir_all(target.parent().expect("stashed path should have a parent"))?;
+        move_path(&path, &target)?;
+        log::verbose_with_category(reporter, "move_to_backup", "Moved successfully");
+
+        Ok(Box::new(move || move_path(&target, &path)))
+    }
+
+    fn remove_ini_entry(
+        params: &RemoveIniEntry,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        let RemoveIniEntry {
+            section,
+            key,
+            value_pattern,
+            path,
+        } = params;
+
+        log::verbose_with_category(
+            reporter,
+            "remove_ini_entry",
+            format!("Processing INI file: {:?}", path),
+        );
+        log::verbose_with_category(reporter, "remove_ini_entry", format!("Section: [{}], Key: {}, Value pattern: {:?}", section, key, value_pattern));
+        let value_regex = value_pattern.as_deref().map(compile_regex).transpose()?;
+        let backup = Change::backup_file(path, backup_dir, reporter)?;
+        let target = path.clone();
+
+        log::verbose_with_category(reporter, "remove_ini_entry", "Loading INI file");
+        let mut ini = load_ini(&target)?;
+        let mut removed = 0;
+        for properties in ini.section_all_mut(Some(section.as_str())) {
+            let entries: Vec<(String, String)> = properties
+                .iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect();
+            let mut kept = Properties::new();
+            for (entry_key, value) in entries {
+                let matches = ini_key_matches(&entry_key, key)
+                    && value_regex.as_ref().map_or(true, |regex| regex.is_match(&value));
+                match matches {
+                    true => removed += 1,
+                    false => kept.append(entry_key, value),
+                }
+            }
+            *properties = kept;
+        }
+        log::verbose_with_category(
+            reporter,
+            "remove_ini_entry",
+            format!("Removed {} entries", removed),
+        );
+        log::verbose_with_category(reporter, "remove_ini_entry", "Writing INI file");
+        ini.write_to_file(&target)?;
+        log::verbose_with_category(
+            reporter,
+            "remove_ini_entry",
+            "INI entries removed successfully",
+        );
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn replace_ini_value(
+        params: &ReplaceIniValue,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        let ReplaceIniValue {
+            section,
+            key,
+            from,
+            to,
+            path,
+        } = params;
+
+        log::verbose_with_category(
+            reporter,
+            "replace_ini_value",
+            format!("Processing INI file: {:?}", path),
+        );
+        log::verbose_with_category(reporter, "replace_ini_value", format!("Section: [{}], Key: {}, Pattern: {}, Replacement: {}", section, key, from, to));
+        let regex = compile_regex(from)?;
+        let backup = Change::backup_file(path, backup_dir, reporter)?;
+        let target = path.clone();
+
+        log::verbose_with_category(reporter, "replace_ini_value", "Loading INI file");
+        let mut ini = load_ini(&target)?;
+        let mut replaced = 0;
+        for properties in ini.section_all_mut(Some(section.as_str())) {
+            let entries: Vec<(String, String)> = properties
+                .iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect();
+            let mut updated = Properties::new();
+            for (entry_key, value) in entries {
+                let value = match ini_key_matches(&entry_key, key) && regex.is_match(&value) {
+                    true => {
+                        replaced += 1;
+                        regex.replace_all(&value, to.as_str()).into_owned()
+                    }
+                    false => value,
+                };
+                updated.append(entry_key, value);
+            }
+            *properties = updated;
+        }
+        log::verbose_with_category(
+            reporter,
+            "replace_ini_value",
+            format!("Replaced {} values", replaced),
+        );
+        log::verbose_with_category(reporter, "replace_ini_value", "Writing INI file");
+        ini.write_to_file(&target)?;
+        log::verbose_with_category(
+            reporter,
+            "replace_ini_value",
+            "INI values replaced successfully",
+        );
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn compact_redirects(
+        params: &CompactRedirects,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "compact_redirects", format!("Processing INI file: {:?}", params.path));
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+
+        log::verbose_with_category(reporter, "compact_redirects", "Loading INI file");
+        let mut ini = load_ini(&target)?;
+        let compaction = redirects::compact(&mut ini);
+        for cycle in &compaction.cycles {
+            log::step(reporter, "cycle", format!("{} (left unchanged)", cycle.join(" -> ")));
+        }
+        log::verbose_with_category(
+            reporter,
+            "compact_redirects",
+            format!("Collapsed {} redirects, removed {}", compaction.collapsed, compaction.removed),
+        );
+        if compaction.collapsed + compaction.removed > 0 {
+            log::verbose_with_category(reporter, "compact_redirects", "Writing INI file");
+            ini.write_to_file(&target)?;
+        }
+        log::verbose_with_category(
+            reporter,
+            "compact_redirects",
+            "Redirects compacted successfully",
+        );
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn add_descriptor_entry(
+        params: &AddDescriptorEntry,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "add_descriptor_entry", format!("Processing descriptor: {:?}", params.path));
+        let content = std::fs::read_to_string(&params.path)?;
+        let content_after_add = descriptor::add_entry(&content, &params.array, &params.name, &params.entry)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        std::fs::write(&target, content_after_add)?;
+        log::verbose_with_category(reporter, "add_descriptor_entry", "Entry added successfully");
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn remove_descriptor_entry(
+        params: &RemoveDescriptorEntry,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "remove_descriptor_entry", format!("Processing descriptor: {:?}", params.path));
+        let content = std::fs::read_to_string(&params.path)?;
+        let content_after_remove = descriptor::remove_entry(&content, &params.array, &params.name)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        std::fs::write(&target, content_after_remove)?;
+        log::verbose_with_category(
+            reporter,
+            "remove_descriptor_entry",
+            "Entry removed successfully",
+        );
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn replace_descriptor_list_values(
+        params: &ReplaceDescriptorListValues,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(
+            reporter,
+            "replace_descriptor_list_values",
+            format!("Processing descriptor: {:?}", params.path),
+        );
+        let content = std::fs::read_to_string(&params.path)?;
+        let content_after_replace =
+            descriptor::replace_list_values(&content, &params.lists, &params.from, &params.to)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        std::fs::write(&target, content_after_replace)?;
+        log::verbose_with_category(reporter, "replace_descriptor_list_values", "Values replaced successfully");
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn rename_implement_macro(
+        params: &RenameImplementMacro,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(
+            reporter,
+            "rename_implement_macro",
+            format!("Processing file: {:?}", params.path),
+        );
+        let mut content = std::fs::read_to_string(&params.path)?;
+        let implement_macro = ImplementMacro::find(&content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        let renamed = implement_macro.renamed(&params.new_name);
+        content.replace_range(implement_macro.range, &renamed);
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        std::fs::write(&target, content)?;
+        log::verbose_with_category(reporter, "rename_implement_macro", "Macro renamed successfully");
+
+        Ok(Box::new(move || {
+            std::fs::copy(&backup, &target).map(|_| ())
+        }))
+    }
+
+    fn remove_cs_list_entries(
+        params: &RemoveCsListEntries,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        log::verbose_with_category(reporter, "remove_cs_list_entries", format!("Processing file: {:?}", params.path));
+        let backup = Change::backup_file(&params.path, backup_dir, reporter)?;
+        let target = params.path.clone();
+        let bytes = std::fs::read(&target)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let content_after_remove = csharp::remove_from_lists(&content, &params.value, &params.properties);
+        log::verbose_with_category(reporter, "remove_cs_list_entries", "Writing modified content");
+        std::fs::write(&target, &content_after_remove)?;
 
         Ok(Box::new(move || {
             std::fs::copy(&backup, &target).map(|_| ())
         }))
     }
 
-    fn backup_file(file: &Path, backup_dir: &Path) -> io::Result<PathBuf> {
-        log::verbose_with_category("backup", format!("Creating backup of {:?}", file));
+    fn create_file(params: &CreateFile, reporter: &dyn Reporter) -> io::Result<Revert> {
+        let path = params.path.clone();
+        log::verbose_with_category(reporter, "create_file", format!("Creating {:?}", path));
+        ensure_vacant(&path)?;
+        let created_dir = create_parent_dirs(&path)?;
+        std::fs::write(&path, &params.content)?;
+        log::verbose_with_category(reporter, "create_file", "File created successfully");
+
+        Ok(Box::new(move || {
+            std::fs::remove_file(&path)?;
+            remove_created_dir(created_dir.as_deref())
+        }))
+    }
+
+    fn delete_file(
+        params: &DeleteFile,
+        backup_dir: &Path,
+        reporter: &dyn Reporter,
+    ) -> io::Result<Revert> {
+        let path = params.path.clone();
+        log::verbose_with_category(reporter, "delete_file", format!("Deleting {:?}", path));
+        let backup = Change::backup_file(&path, backup_dir, reporter)?;
+        std::fs::remove_file(&path)?;
+        log::verbose_with_category(reporter, "delete_file", "File deleted successfully");
+
+        Ok(Box::new(move || std::fs::copy(&backup, &path).map(|_| ())))
+    }
+
+    fn copy_file(params: &CopyFile, reporter: &dyn Reporter) -> io::Result<Revert> {
+        let from = params.from.clone();
+        let to = params.to.clone();
+        log::verbose_with_category(
+            reporter,
+            "copy_file",
+            format!("Copying {:?} -> {:?}", from, to),
+        );
+        ensure_vacant(&to)?;
+        let created_dir = create_parent_dirs(&to)?;
+        std::fs::copy(&from, &to)?;
+        log::verbose_with_category(reporter, "copy_file", "File copied successfully");
+
+        Ok(Box::new(move || {
+            std::fs::remove_file(&to)?;
+            remove_created_dir(created_dir.as_deref())
+        }))
+    }
+
+    fn copy_dir(params: &CopyDir, reporter: &dyn Reporter) -> io::Result<Revert> {
+        let from = params.from.clone();
+        let to = params.to.clone();
+        log::verbose_with_category(reporter, "copy_dir", format!("Copying {:?} -> {:?}", from, to));
+        if !from.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{:?} is not a directory", from),
+            ));
+        }
+        ensure_vacant(&to)?;
+        let created_dir = create_parent_dirs(&to)?;
+        backup::copy_path(&from, &to)?;
+        log::verbose_with_category(reporter, "copy_dir", "Directory copied successfully");
+
+        Ok(Box::new(move || {
+            std::fs::remove_dir_all(&to)?;
+            remove_created_dir(created_dir.as_deref())
+        }))
+    }
+
+    fn create_dir(params: &CreateDir, reporter: &dyn Reporter) -> io::Result<Revert> {
+        let path = params.path.clone();
+        log::verbose_with_category(reporter, "create_dir", format!("Creating {:?}", path));
+        ensure_vacant(&path)?;
+        let created_dir = create_parent_dirs(&path)?;
+        std::fs::create_dir(&path)?;
+        log::verbose_with_category(reporter, "create_dir", "Directory created successfully");
+
+        Ok(Box::new(move || {
+            std::fs::remove_dir_all(&path)?;
+            remove_created_dir(created_dir.as_deref())
+        }))
+    }
+
+    fn backup_file(file: &Path, backup_dir: &Path, reporter: &dyn Reporter) -> io::Result<PathBuf> {
+        log::verbose_with_category(reporter, "backup", format!("Creating backup of {:?}", file));
         // Read as raw bytes to support any encoding
         let content = std::fs::read(file)?;
         let hash = Sha256::digest(&content);
         let path = backup_dir.join(format!("{:x}", hash));
-        log::verbose_with_category("backup", format!("Backup file hash: {:x}", hash));
-        log::verbose_with_category("backup", format!("Backup path: {:?}", path));
+        log::verbose_with_category(reporter, "backup", format!("Backup file hash: {:x}", hash));
+        log::verbose_with_category(reporter, "backup", format!("Backup path: {:?}", path));
         std::fs::write(&path, &content)?;
-        log::verbose_with_category("backup", "Backup created successfully");
+        backup::record(backup_dir, file, &format!("{:x}", hash))?;
+        log::verbose_with_category(reporter, "backup", "Backup created successfully");
         Ok(path)
     }
 }
 
 impl Display for Change {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &*self {
+        match self {
             Change::RenameFile(params) => write!(f, "{}", &params),
             Change::ReplaceInFile(params) => write!(f, "{}", &params),
+            Change::ReplaceCsReferences(params) => write!(f, "{}", &params),
             Change::SetIniEntry(params) => write!(f, "{}", &params),
             Change::AppendIniEntry(params) => write!(f, "{}", &params),
+            Change::MoveToBackup(params) => write!(f, "{}", &params),
+            Change::CreateFile(params) => write!(f, "{}", &params),
+            Change::DeleteFile(params) => write!(f, "{}", &params),
+            Change::CopyFile(params) => write!(f, "{}", &params),
+            Change::CopyDir(params) => write!(f, "{}", &params),
+            Change::CreateDir(params) => write!(f, "{}", &params),
+            Change::RemoveIniEntry(params) => write!(f, "{}", &params),
+            Change::ReplaceIniValue(params) => write!(f, "{}", &params),
+            Change::CompactRedirects(params) => write!(f, "{}", &params),
+            Change::AddDescriptorEntry(params) => write!(f, "{}", &params),
+            Change::RemoveDescriptorEntry(params) => write!(f, "{}", &params),
+            Change::ReplaceDescriptorListValues(params) => write!(f, "{}", &params),
+            Change::RemoveCsListEntries(params) => write!(f, "{}", &params),
+            Change::RenameImplementMacro(params) => write!(f, "{}", &params),
         }
     }
 }
 
 pub type Revert = Box<dyn Fn() -> io::Result<()>>;
+
+/// Move a file or directory, copying it if it cannot be renamed, for instance
+/// because the destination is on another device.
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    backup::copy_path(from, to)?;
+    match from.is_dir() {
+        true => std::fs::remove_dir_all(from),
+        false => std::fs::remove_file(from),
+    }
+}
+
+/// Fail if something already exists at `path`, so that it is never
+/// overwritten by a change that cannot restore it.
+fn ensure_vacant(path: &Path) -> io::Result<()> {
+    match path.exists() {
+        true => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{:?} already exists", path),
+        )),
+        false => Ok(()),
+    }
+}
+
+/// Create the missing parent directories of `path`, returning the outermost
+/// directory that was created, if any.
+fn create_parent_dirs(path: &Path) -> io::Result<Option<PathBuf>> {
+    let outermost = path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .last()
+        .map(Path::to_owned);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(outermost)
+}
+
+/// Remove a directory created by [`create_parent_dirs`].
+fn remove_created_dir(dir: Option<&Path>) -> io::Result<()> {
+    match dir {
+        Some(dir) => std::fs::remove_dir_all(dir),
+        None => Ok(()),
+    }
+}
+
+/// Load a config file, keeping quotes and backslashes in values as they are.
+fn load_ini(path: &Path) -> io::Result<Ini> {
+    let read_opts = ParseOption {
+        enabled_escape: false,
+        enabled_quote: false,
+    };
+    Ini::load_from_file_opt(path, read_opts).map_err(|err| match err {
+        ini::ini::Error::Io(io) => io,
+        ini::ini::Error::Parse(p) => io::Error::other(p),
+    })
+}
+
+/// Check if the key of a config entry matches `key`. Array prefixes of the
+/// entry key are ignored unless `key` has one itself.
+fn ini_key_matches(entry_key: &str, key: &str) -> bool {
+    let strip = |key: &str| key.trim_start_matches(['+', '-', '.', '!']).to_owned();
+    match strip(key) == key {
+        true => strip(entry_key) == key,
+        false => entry_key == key,
+    }
+}
+
+fn compile_regex(pattern: &str) -> io::Result<Regex> {
+    Regex::new(pattern).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
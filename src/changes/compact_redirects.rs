@@ -0,0 +1,30 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Compact the redirects of an engine config file, collapsing chains and
+/// removing self-redirects and duplicates.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CompactRedirects {
+    pub path: PathBuf,
+}
+
+impl CompactRedirects {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Display for CompactRedirects {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "compact redirects in config file {}",
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
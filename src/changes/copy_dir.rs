@@ -0,0 +1,35 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Copy a directory tree to a new location, creating any missing parent
+/// directories. Fails if the destination already exists.
+#[derive(Debug, PartialEq)]
+pub struct CopyDir {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl CopyDir {
+    pub fn new(from: impl Into<PathBuf>, to: impl Into<PathBuf>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Display for CopyDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "copy directory {} to {}",
+            &self
+                .from
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed(),
+            &self.to.to_str().unwrap_or("invalid Unicode path").dimmed()
+        )
+    }
+}
@@ -0,0 +1,30 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Create a directory, along with any missing parent directories. Fails if
+/// the directory already exists.
+#[derive(Debug, PartialEq)]
+pub struct CreateDir {
+    pub path: PathBuf,
+}
+
+impl CreateDir {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Display for CreateDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "create directory {}",
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,34 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Create a new file with the given content, along with any missing parent
+/// directories. Fails if the file already exists.
+#[derive(Debug, PartialEq)]
+pub struct CreateFile {
+    pub path: PathBuf,
+    pub content: Vec<u8>,
+}
+
+impl CreateFile {
+    pub fn new(path: impl Into<PathBuf>, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: path.into(),
+            content: content.into(),
+        }
+    }
+}
+
+impl Display for CreateFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "create file {}",
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,29 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Delete a file after backing it up, so that it can be restored on revert.
+#[derive(Debug, PartialEq)]
+pub struct DeleteFile {
+    pub path: PathBuf,
+}
+
+impl DeleteFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Display for DeleteFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "delete file {}",
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -1,11 +1,41 @@
+mod add_descriptor_entry;
 mod append_ini_entry;
 mod change;
+mod compact_redirects;
+mod copy_dir;
+mod copy_file;
+mod create_dir;
+mod create_file;
+mod delete_file;
+mod move_to_backup;
+mod remove_cs_list_entries;
+mod remove_descriptor_entry;
+mod remove_ini_entry;
+mod rename_implement_macro;
 mod rename_file;
+mod replace_cs_references;
+mod replace_descriptor_list_values;
 mod replace_in_file;
+mod replace_ini_value;
 mod set_ini_entry;
 
+pub use add_descriptor_entry::*;
 pub use append_ini_entry::*;
 pub use change::*;
+pub use compact_redirects::*;
+pub use copy_dir::*;
+pub use copy_file::*;
+pub use create_dir::*;
+pub use create_file::*;
+pub use delete_file::*;
+pub use move_to_backup::*;
+pub use remove_cs_list_entries::*;
+pub use remove_descriptor_entry::*;
+pub use remove_ini_entry::*;
+pub use rename_implement_macro::*;
 pub use rename_file::*;
+pub use replace_cs_references::*;
+pub use replace_descriptor_list_values::*;
 pub use replace_in_file::*;
+pub use replace_ini_value::*;
 pub use set_ini_entry::*;
@@ -0,0 +1,30 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Move a file or directory into the backup directory of the run instead of
+/// deleting it, so that it can be moved back on revert.
+#[derive(Debug, PartialEq)]
+pub struct MoveToBackup {
+    pub path: PathBuf,
+}
+
+impl MoveToBackup {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Display for MoveToBackup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} to backup",
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
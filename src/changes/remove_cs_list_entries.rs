@@ -0,0 +1,40 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Remove string literals that are exactly `value` from list properties of a
+/// C# build or target file, such as `ExtraModuleNames` or
+/// `PublicDependencyModuleNames`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RemoveCsListEntries {
+    pub path: PathBuf,
+    pub value: String,
+    /// The list properties to remove the value from.
+    pub properties: Vec<String>,
+}
+
+impl RemoveCsListEntries {
+    pub fn new(path: impl Into<PathBuf>, value: impl Into<String>, properties: &[&str]) -> Self {
+        Self {
+            path: path.into(),
+            value: value.into(),
+            properties: properties.iter().map(|property| property.to_string()).collect(),
+        }
+    }
+}
+
+impl Display for RemoveCsListEntries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "remove {} from {} in file {}",
+            &self.value.dimmed(),
+            &self.properties.join(", "),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Remove an entry from an array of a project or plugin descriptor, such as
+/// `Modules` or `Plugins`, keeping the rest of the descriptor as it is.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RemoveDescriptorEntry {
+    pub path: PathBuf,
+    /// The array to remove the entry from.
+    pub array: String,
+    /// The name of the module or plugin that the entry describes.
+    pub name: String,
+}
+
+impl RemoveDescriptorEntry {
+    pub fn new(path: impl Into<PathBuf>, array: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            array: array.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl Display for RemoveDescriptorEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "remove {} from {} of descriptor {}",
+            &self.name.dimmed(),
+            &self.array,
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Remove entries from a section of a config file. The key matches entries
+/// with any of the `+`, `-`, `.` and `!` array prefixes unless it has a prefix
+/// itself, in which case only entries with that prefix match. If a value
+/// pattern is given, only entries whose value matches the regular expression
+/// are removed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RemoveIniEntry {
+    pub path: PathBuf,
+    pub section: String,
+    pub key: String,
+    pub value_pattern: Option<String>,
+}
+
+impl RemoveIniEntry {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        section: impl Into<String>,
+        key: impl Into<String>,
+        value_pattern: Option<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            section: section.into(),
+            key: key.into(),
+            value_pattern,
+        }
+    }
+}
+
+impl Display for RemoveIniEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remove {}", &self.key.dimmed())?;
+        if let Some(pattern) = &self.value_pattern {
+            write!(f, " matching {}", pattern.dimmed())?;
+        }
+        write!(
+            f,
+            " from [{}] in config file {}",
+            &self.section.dimmed(),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed(),
+        )
+    }
+}
@@ -0,0 +1,36 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Rename the module passed to the module implementation macro of a source
+/// file, such as `IMPLEMENT_MODULE(FDefaultModuleImpl, Module)`. Only the
+/// invocation itself is changed, not copies of it in comments or strings.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenameImplementMacro {
+    pub path: PathBuf,
+    pub new_name: String,
+}
+
+impl RenameImplementMacro {
+    pub fn new(path: impl Into<PathBuf>, new_name: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            new_name: new_name.into(),
+        }
+    }
+}
+
+impl Display for RenameImplementMacro {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rename module to {} in implement macro of file {}",
+            &self.new_name.dimmed(),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
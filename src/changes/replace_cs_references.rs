@@ -0,0 +1,68 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Replace references in a C# build or target file that are exactly `from`,
+/// either as an identifier, such as a class name, or as a string literal,
+/// such as a module name. Comments and partial matches are left as they are.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplaceCsReferences {
+    pub path: PathBuf,
+    pub from: String,
+    pub to: String,
+    /// Replace identifiers, such as class and constructor names.
+    pub identifiers: bool,
+    /// Replace string literals, such as module names.
+    pub strings: bool,
+    /// Only replace string literals used with these properties, such as
+    /// `ExtraModuleNames`. Empty to replace string literals anywhere.
+    pub properties: Vec<String>,
+}
+
+impl ReplaceCsReferences {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        identifiers: bool,
+        strings: bool,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            from: from.into(),
+            to: to.into(),
+            identifiers,
+            strings,
+            properties: vec![],
+        }
+    }
+
+    /// Only replace string literals used with the given properties.
+    pub fn in_properties(mut self, properties: &[&str]) -> Self {
+        self.properties = properties.iter().map(|property| property.to_string()).collect();
+        self
+    }
+}
+
+impl Display for ReplaceCsReferences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let references = match (self.identifiers, self.strings) {
+            (true, true) => "identifiers and strings",
+            (true, false) => "identifiers",
+            _ if !self.properties.is_empty() => "strings used with properties",
+            _ => "strings",
+        };
+        write!(
+            f,
+            "replace {} {} with {} in file {}",
+            references,
+            &self.from.dimmed(),
+            &self.to.dimmed(),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,48 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Replace a value in lists of the `Modules` and `Plugins` entries of a
+/// project or plugin descriptor, such as `TargetAllowList`, keeping the rest
+/// of the descriptor as it is.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplaceDescriptorListValues {
+    pub path: PathBuf,
+    /// The lists of the entries to replace the value in.
+    pub lists: Vec<String>,
+    pub from: String,
+    pub to: String,
+}
+
+impl ReplaceDescriptorListValues {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        lists: &[&str],
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            lists: lists.iter().map(|list| list.to_string()).collect(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Display for ReplaceDescriptorListValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "replace {} with {} in {} of descriptor {}",
+            &self.from.dimmed(),
+            &self.to.dimmed(),
+            &self.lists.join(", "),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed()
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use std::{fmt::Display, path::PathBuf};
+
+use colored::Colorize;
+
+/// Replace matches of a regular expression in the values of entries in a
+/// section of a config file. Keys match as in
+/// [`RemoveIniEntry`](super::RemoveIniEntry), so that array entries are
+/// updated regardless of their prefix.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplaceIniValue {
+    pub path: PathBuf,
+    pub section: String,
+    pub key: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl ReplaceIniValue {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        section: impl Into<String>,
+        key: impl Into<String>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            section: section.into(),
+            key: key.into(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Display for ReplaceIniValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "replace {} with {} in {} values of [{}] in config file {}",
+            &self.from.dimmed(),
+            &self.to.dimmed(),
+            &self.key.dimmed(),
+            &self.section.dimmed(),
+            &self
+                .path
+                .to_str()
+                .unwrap_or("invalid Unicode path")
+                .dimmed(),
+        )
+    }
+}
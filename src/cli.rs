@@ -1,8 +1,22 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use clap::{Parser, Subcommand};
 
-use crate::workflows::{rename_module, rename_plugin, rename_project, rename_target};
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    presentation::{
+        log::OutputFormat,
+        reporter::{FileReporter, JsonReporter, Reporter, TeeReporter, TerminalReporter},
+    },
+    workflows::{
+        add_module, add_plugin, add_target, clean, manage_backups, manage_redirects, move_module,
+        remove_module, rename_module, rename_plugin, rename_project, rename_target,
+    },
+};
 
 #[derive(Parser)]
 #[command(author, version, about, arg_required_else_help(true))]
@@ -12,6 +26,58 @@ pub struct Cli {
     /// Enable verbose logging to see detailed operation information
     #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
+    /// Store backups under this directory instead of inside the project
+    #[arg(long, global = true, value_name = "DIR")]
+    pub backup_dir: Option<PathBuf>,
+    /// Store the backups of each run as a single compressed archive
+    #[arg(long, global = true)]
+    pub compress_backups: bool,
+    /// Capture the affected parts of the project in full before making changes
+    #[arg(long, global = true)]
+    pub snapshot: bool,
+    /// Format of the output: colored text or newline-delimited JSON events
+    #[arg(long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+    /// Also write all output, including verbose messages, to this file
+    #[arg(long, global = true, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Reporter for the output format and log file provided on the command
+    /// line. The output format falls back to the one in the config file of
+    /// the project the command operates on.
+    pub fn reporter(&self) -> Result<Rc<dyn Reporter>, String> {
+        let output = self.output.unwrap_or_else(|| {
+            self.command
+                .as_ref()
+                .and_then(Command::project_root)
+                .and_then(|project_root| Config::load(project_root).ok())
+                .and_then(|config| config.output)
+                .unwrap_or_default()
+        });
+        let reporter: Rc<dyn Reporter> = match output {
+            OutputFormat::Text => Rc::new(TerminalReporter::new(self.verbose)),
+            OutputFormat::Json => Rc::new(JsonReporter::new(self.verbose)),
+        };
+        match &self.log_file {
+            Some(path) => {
+                let file = FileReporter::create(path)
+                    .map_err(|err| format!("could not create log file {:?}: {}", path, err))?;
+                Ok(Rc::new(TeeReporter::new(vec![reporter, Rc::new(file)])))
+            }
+            None => Ok(reporter),
+        }
+    }
+
+    /// Backup options provided on the command line.
+    pub fn backup_options(&self) -> BackupOptions {
+        BackupOptions {
+            dir: self.backup_dir.clone(),
+            compress: self.compress_backups,
+            snapshot: self.snapshot,
+        }
+    }
 }
 
 #[derive(PartialEq, Debug, Subcommand)]
@@ -24,10 +90,47 @@ pub enum Command {
     RenameTarget(RenameTarget),
     /// Rename an Unreal Engine project module
     RenameModule(RenameModule),
+    /// Add a module to an Unreal Engine project or one of its plugins
+    AddModule(AddModule),
+    /// Add a plugin with an initial module to an Unreal Engine project
+    AddPlugin(AddPlugin),
+    /// Add a target to an Unreal Engine project
+    AddTarget(AddTarget),
+    /// Remove a module from an Unreal Engine project or one of its plugins
+    RemoveModule(RemoveModule),
+    /// Move an Unreal Engine module between the project and its plugins
+    MoveModule(MoveModule),
+    /// Move stale build artifacts of an Unreal Engine project into the backup
+    Clean(Clean),
+    /// Manage the backups created for an Unreal Engine project
+    Backup(Backup),
+    /// Maintain the redirects of an Unreal Engine project
+    Redirects(Redirects),
     /// Start an interactive session
     Wizard,
 }
 
+impl Command {
+    /// Root of the project that the command operates on, if any.
+    pub fn project_root(&self) -> Option<&Path> {
+        match self {
+            Command::RenameProject(command) => Some(&command.project),
+            Command::RenamePlugin(command) => Some(&command.project),
+            Command::RenameTarget(command) => Some(&command.project),
+            Command::RenameModule(command) => Some(&command.project),
+            Command::AddModule(command) => Some(&command.project),
+            Command::AddPlugin(command) => Some(&command.project),
+            Command::AddTarget(command) => Some(&command.project),
+            Command::RemoveModule(command) => Some(&command.project),
+            Command::MoveModule(command) => Some(&command.project),
+            Command::Clean(command) => Some(&command.project),
+            Command::Backup(command) => Some(&command.project),
+            Command::Redirects(command) => Some(&command.project),
+            Command::Wizard => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Parser)]
 pub struct RenameProject {
     /// Path to the project to rename
@@ -36,14 +139,19 @@ pub struct RenameProject {
     /// New name for the project
     #[arg(long)]
     new_name: String,
+    /// Move build artifacts named after the old name into the backup after
+    /// renaming
+    #[arg(long)]
+    clean: bool,
 }
 
 impl RenameProject {
-    pub fn into_params(self, verbose: bool) -> rename_project::Params {
+    pub fn into_params(self, backup: BackupOptions) -> rename_project::Params {
         rename_project::Params {
             project_root: self.project,
             new_name: self.new_name,
-            verbose,
+            clean: self.clean,
+            backup,
         }
     }
 }
@@ -59,15 +167,20 @@ pub struct RenamePlugin {
     /// New name for the plugin
     #[arg(long)]
     new_name: String,
+    /// Move build artifacts named after the old name into the backup after
+    /// renaming
+    #[arg(long)]
+    clean: bool,
 }
 
 impl RenamePlugin {
-    pub fn into_params(self, verbose: bool) -> rename_plugin::Params {
+    pub fn into_params(self, backup: BackupOptions) -> rename_plugin::Params {
         rename_plugin::Params {
             project_root: self.project,
             plugin: self.plugin,
             new_name: self.new_name,
-            verbose,
+            clean: self.clean,
+            backup,
         }
     }
 }
@@ -83,15 +196,20 @@ pub struct RenameTarget {
     /// New name for the target
     #[arg(long)]
     new_name: String,
+    /// Move build artifacts named after the old name into the backup after
+    /// renaming
+    #[arg(long)]
+    clean: bool,
 }
 
 impl RenameTarget {
-    pub fn into_params(self, verbose: bool) -> rename_target::Params {
+    pub fn into_params(self, backup: BackupOptions) -> rename_target::Params {
         rename_target::Params {
             project_root: self.project,
             target: self.target,
             new_name: self.new_name,
-            verbose,
+            clean: self.clean,
+            backup,
         }
     }
 }
@@ -107,15 +225,295 @@ pub struct RenameModule {
     /// New name for the module
     #[arg(long)]
     new_name: String,
+    /// Also rename the main header and source files, the implementation class
+    /// and the log categories of the module
+    #[arg(long)]
+    rename_sources: bool,
+    /// Move build artifacts named after the old name into the backup after
+    /// renaming
+    #[arg(long)]
+    clean: bool,
 }
 
 impl RenameModule {
-    pub fn into_params(self, verbose: bool) -> rename_module::Params {
+    pub fn into_params(self, backup: BackupOptions) -> rename_module::Params {
         rename_module::Params {
             project_root: self.project,
             module: self.module,
             new_name: self.new_name,
-            verbose,
+            rename_sources: self.rename_sources,
+            clean: self.clean,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct AddModule {
+    /// Path to the project to add the module to
+    #[arg(long)]
+    project: PathBuf,
+    /// Name of the new module
+    #[arg(long)]
+    name: String,
+    /// Plugin to add the module to instead of the project
+    #[arg(long)]
+    plugin: Option<String>,
+    /// Type of the module, such as Runtime or Editor
+    #[arg(long = "type", default_value = "Runtime")]
+    module_type: String,
+    /// Loading phase of the module
+    #[arg(long, default_value = "Default")]
+    loading_phase: String,
+    /// Add the module to the ExtraModuleNames of the project targets
+    #[arg(long)]
+    add_to_targets: bool,
+}
+
+impl AddModule {
+    pub fn into_params(self, backup: BackupOptions) -> add_module::Params {
+        add_module::Params {
+            project_root: self.project,
+            name: self.name,
+            plugin: self.plugin,
+            module_type: self.module_type,
+            loading_phase: self.loading_phase,
+            add_to_targets: self.add_to_targets,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct AddPlugin {
+    /// Path to the project to add the plugin to
+    #[arg(long)]
+    project: PathBuf,
+    /// Name of the new plugin
+    #[arg(long)]
+    name: String,
+    /// Name of the initial module, defaults to the name of the plugin
+    #[arg(long)]
+    module: Option<String>,
+    /// Type of the initial module, such as Runtime or Editor
+    #[arg(long = "type", default_value = "Runtime")]
+    module_type: String,
+    /// Create a Content folder and allow the plugin to contain content
+    #[arg(long)]
+    content: bool,
+    /// Create a placeholder Resources/Icon128.png
+    #[arg(long)]
+    icon: bool,
+    /// Enable the plugin in the project descriptor
+    #[arg(long)]
+    enable: bool,
+}
+
+impl AddPlugin {
+    pub fn into_params(self, backup: BackupOptions) -> add_plugin::Params {
+        add_plugin::Params {
+            project_root: self.project,
+            name: self.name,
+            module: self.module,
+            module_type: self.module_type,
+            content: self.content,
+            icon: self.icon,
+            enable: self.enable,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct AddTarget {
+    /// Path to the project to add the target to
+    #[arg(long)]
+    project: PathBuf,
+    /// Name of the new target
+    #[arg(long)]
+    name: String,
+    /// Type of the target: Game, Editor, Client, Server or Program
+    #[arg(long = "type")]
+    target_type: Option<String>,
+    /// Existing target to clone instead of generating one
+    #[arg(long)]
+    from: Option<String>,
+}
+
+impl AddTarget {
+    pub fn into_params(self, backup: BackupOptions) -> add_target::Params {
+        add_target::Params {
+            project_root: self.project,
+            name: self.name,
+            target_type: self.target_type,
+            from: self.from,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct RemoveModule {
+    /// Path to the project to remove the module from
+    #[arg(long)]
+    project: PathBuf,
+    /// Name of the module to remove
+    #[arg(long)]
+    module: String,
+    /// Remove the module from the build files of modules that depend on it
+    #[arg(long)]
+    force: bool,
+}
+
+impl RemoveModule {
+    pub fn into_params(self, backup: BackupOptions) -> remove_module::Params {
+        remove_module::Params {
+            project_root: self.project,
+            module: self.module,
+            force: self.force,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct MoveModule {
+    /// Path to the project that the module belongs to
+    #[arg(long)]
+    project: PathBuf,
+    /// Name of the module to move
+    #[arg(long)]
+    module: String,
+    /// Plugin to move the module to
+    #[arg(long, conflicts_with = "to_project", required_unless_present = "to_project")]
+    to_plugin: Option<String>,
+    /// Move the module out of its plugin into the project
+    #[arg(long)]
+    to_project: bool,
+}
+
+impl MoveModule {
+    pub fn into_params(self, backup: BackupOptions) -> move_module::Params {
+        move_module::Params {
+            project_root: self.project,
+            module: self.module,
+            to_plugin: self.to_plugin,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct Clean {
+    /// Path to the project to clean
+    #[arg(long)]
+    project: PathBuf,
+}
+
+impl Clean {
+    pub fn into_params(self, backup: BackupOptions) -> clean::Params {
+        clean::Params {
+            project_root: self.project,
+            backup,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct Backup {
+    /// Path to the project that the backups belong to
+    #[arg(long)]
+    project: PathBuf,
+    #[command(subcommand)]
+    command: BackupCommand,
+}
+
+#[derive(PartialEq, Debug, Subcommand)]
+pub enum BackupCommand {
+    /// List all backups with their timestamps
+    List,
+    /// Show which files were backed up in a backup
+    Show {
+        /// Identifier of the backup
+        #[arg(long)]
+        run: String,
+    },
+    /// Restore a single file or all files of a backup
+    Restore {
+        /// Identifier of the backup
+        #[arg(long)]
+        run: String,
+        /// Path of the file to restore, relative to the project root
+        #[arg(long, conflicts_with = "snapshot")]
+        file: Option<PathBuf>,
+        /// Restore the snapshot of the backup instead of the backed up files
+        #[arg(long)]
+        snapshot: bool,
+    },
+    /// Delete backups by age or count
+    Prune {
+        /// Delete backups older than this many days
+        #[arg(long)]
+        older_than: Option<u32>,
+        /// Keep only this many of the most recent backups
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+}
+
+impl Backup {
+    pub fn into_params(self, backup: BackupOptions) -> manage_backups::Params {
+        manage_backups::Params {
+            project_root: self.project,
+            backup,
+            action: match self.command {
+                BackupCommand::List => manage_backups::Action::List,
+                BackupCommand::Show { run } => manage_backups::Action::Show { run },
+                BackupCommand::Restore {
+                    run,
+                    file,
+                    snapshot,
+                } => manage_backups::Action::Restore {
+                    run,
+                    file,
+                    snapshot,
+                },
+                BackupCommand::Prune { older_than, keep } => manage_backups::Action::Prune {
+                    older_than_days: older_than,
+                    keep,
+                },
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Parser)]
+pub struct Redirects {
+    /// Path to the project that the redirects belong to
+    #[arg(long)]
+    project: PathBuf,
+    #[command(subcommand)]
+    command: RedirectsCommand,
+}
+
+#[derive(PartialEq, Debug, Subcommand)]
+pub enum RedirectsCommand {
+    /// Collapse redirect chains and remove self-redirects and duplicates
+    Compact,
+    /// Replace legacy ActiveGameNameRedirects and ActiveClassRedirects with
+    /// equivalent [CoreRedirects] entries
+    Migrate,
+}
+
+impl Redirects {
+    pub fn into_params(self, backup: BackupOptions) -> manage_redirects::Params {
+        manage_redirects::Params {
+            project_root: self.project,
+            action: match self.command {
+                RedirectsCommand::Compact => manage_redirects::Action::Compact,
+                RedirectsCommand::Migrate => manage_redirects::Action::Migrate,
+            },
+            backup,
         }
     }
 }
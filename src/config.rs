@@ -0,0 +1,270 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+
+use crate::{
+    changes::Change,
+    hooks::HooksConfig,
+    presentation::{
+        log::{self, OutputFormat},
+        reporter::Reporter,
+    },
+    rules::ReplacementRule,
+    validation::ValidationOverrides,
+};
+
+/// Location of the config file, relative to the project root.
+pub const CONFIG_FILE_PATH: &str = ".renom/config.toml";
+
+/// Project-level configuration, read from `.renom/config.toml` in the project
+/// root. Every setting is optional.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Default output format, used when none is provided on the command line.
+    pub output: Option<OutputFormat>,
+    /// Glob patterns, relative to the project root, of files that must never
+    /// be searched or edited. Files and directories are still renamed.
+    pub ignore: Vec<String>,
+    /// Default backup location and mode.
+    pub backup: BackupConfig,
+    /// Interaction with version control.
+    pub vcs: VcsConfig,
+    /// Adjustments to the validation rules for names and paths.
+    pub validation: ValidationOverrides,
+    /// Commands run before and after changes are applied or reverted.
+    pub hooks: HooksConfig,
+    /// Settings specific to each workflow.
+    pub workflows: WorkflowsConfig,
+}
+
+/// The `[backup]` table of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Directory under which backups are stored, relative to the project root.
+    pub dir: Option<PathBuf>,
+    /// Whether each run should be stored as a single compressed archive.
+    pub compress: bool,
+    /// Whether the affected subtrees should be captured in full before each run.
+    pub snapshot: bool,
+}
+
+/// The `[vcs]` table of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct VcsConfig {
+    pub mode: VcsMode,
+}
+
+/// How workflows interact with version control.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VcsMode {
+    /// Ignore version control.
+    #[default]
+    None,
+    /// Refuse to run unless the project is part of a Git working tree without
+    /// uncommitted changes, so that every change can be reviewed and undone
+    /// with Git.
+    Git,
+}
+
+/// The `[workflows]` table of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WorkflowsConfig {
+    pub rename_project: WorkflowConfig,
+    pub rename_plugin: WorkflowConfig,
+    pub rename_target: WorkflowConfig,
+    pub rename_module: WorkflowConfig,
+    pub add_module: WorkflowConfig,
+    pub add_plugin: WorkflowConfig,
+    pub add_target: WorkflowConfig,
+    pub remove_module: WorkflowConfig,
+    pub move_module: WorkflowConfig,
+    pub clean: WorkflowConfig,
+    pub redirects: WorkflowConfig,
+}
+
+/// Settings for a single workflow.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    /// Glob patterns, relative to the project root, of additional files in
+    /// which the old name is replaced with the new name.
+    pub files: Vec<String>,
+    /// Additional replacements in files matching glob patterns.
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl WorkflowsConfig {
+    fn all(&self) -> [&WorkflowConfig; 11] {
+        [
+            &self.rename_project,
+            &self.rename_plugin,
+            &self.rename_target,
+            &self.rename_module,
+            &self.add_module,
+            &self.add_plugin,
+            &self.add_target,
+            &self.remove_module,
+            &self.move_module,
+            &self.clean,
+            &self.redirects,
+        ]
+    }
+}
+
+impl Config {
+    /// Load the config file of a project. A missing config file results in
+    /// the default config.
+    pub fn load(project_root: &Path) -> Result<Config, String> {
+        let config_file = project_root.join(CONFIG_FILE_PATH);
+        if !config_file.is_file() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(&config_file).map_err(|err| err.to_string())?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|err| format!("invalid config file {:?}: {}", config_file, err))?;
+        for rule in config.workflows.all().iter().flat_map(|workflow| &workflow.rules) {
+            rule.validate()
+                .map_err(|err| format!("invalid config file {:?}: {}", config_file, err))?;
+        }
+        Ok(config)
+    }
+
+    /// Check if a path within the project matches any ignore pattern.
+    pub fn is_ignored(&self, project_root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(project_root).unwrap_or(path);
+        self.ignore.iter().any(|pattern| {
+            Pattern::new(pattern)
+                .is_ok_and(|pattern| pattern.matches_path_with(relative, match_options()))
+        })
+    }
+
+    /// Drop changes that edit ignored files. Renames are always kept, as
+    /// later changes depend on them.
+    pub fn filter_changeset(
+        &self,
+        project_root: &Path,
+        changeset: Vec<Change>,
+        reporter: &dyn Reporter,
+    ) -> Vec<Change> {
+        changeset
+            .into_iter()
+            .filter(|change| {
+                let ignored = !matches!(change, Change::RenameFile(_))
+                    && change
+                        .paths()
+                        .iter()
+                        .any(|path| self.is_ignored(project_root, path));
+                if ignored {
+                    log::verbose_with_category(
+                        reporter,
+                        "config",
+                        format!("Ignoring change: {}", change),
+                    );
+                }
+                !ignored
+            })
+            .collect()
+    }
+
+    /// Files matching the additional file patterns of a workflow, excluding
+    /// ignored files.
+    pub fn extra_files(
+        &self,
+        project_root: &Path,
+        workflow: &WorkflowConfig,
+    ) -> Result<Vec<PathBuf>, String> {
+        self.matching_files(project_root, &workflow.files)
+    }
+
+    /// Files matching any of the glob patterns, relative to the project root,
+    /// excluding ignored files.
+    pub fn matching_files(
+        &self,
+        project_root: &Path,
+        patterns: &[String],
+    ) -> Result<Vec<PathBuf>, String> {
+        let mut files = vec![];
+        for pattern in patterns {
+            let root = project_root.to_str().ok_or("project root is not valid Unicode")?;
+            let pattern = format!("{}/{}", Pattern::escape(root), pattern);
+            let paths = glob::glob_with(&pattern, match_options())
+                .map_err(|err| format!("invalid file pattern {}: {}", pattern, err))?;
+            files.extend(
+                paths
+                    .filter_map(Result::ok)
+                    .filter(|path| path.is_file() && !self.is_ignored(project_root, path)),
+            );
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Changes that replace the old name with the new name in the additional
+    /// files of a workflow, followed by the changes of its replacement rules.
+    /// Additional files are left alone by workflows that do not replace a
+    /// name, which pass an empty old name or the same old and new name.
+    pub fn extra_changes(
+        &self,
+        project_root: &Path,
+        workflow: &WorkflowConfig,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<Vec<Change>, String> {
+        let extra_files = ReplacementRule {
+            files: workflow.files.clone(),
+            pattern: "{old}".into(),
+            replacement: "{new}".into(),
+        };
+        let replaces_name = !old_name.is_empty() && old_name != new_name;
+        let mut changes = vec![];
+        for rule in replaces_name.then_some(&extra_files).into_iter().chain(&workflow.rules) {
+            let files = self.matching_files(project_root, &rule.files)?;
+            changes.extend(rule.changes(&files, old_name, new_name));
+        }
+        Ok(changes)
+    }
+
+    /// Check that the project is in a state the configured version control
+    /// mode accepts.
+    pub fn check_vcs(&self, project_root: &Path) -> Result<(), String> {
+        match self.vcs.mode {
+            VcsMode::None => Ok(()),
+            VcsMode::Git => check_git_clean(project_root),
+        }
+    }
+}
+
+fn match_options() -> MatchOptions {
+    MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    }
+}
+
+fn check_git_clean(project_root: &Path) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project_root)
+        .args(["status", "--porcelain", "--", ".", ":(exclude).renom"])
+        .output()
+        .map_err(|err| format!("could not run git: {}", err))?;
+    if !output.status.success() {
+        return Err("project root must be part of a Git working tree".into());
+    }
+    match output.stdout.is_empty() {
+        true => Ok(()),
+        false => Err("project must not have uncommitted changes".into()),
+    }
+}
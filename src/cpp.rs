@@ -0,0 +1,361 @@
+use std::{fmt::Display, ops::Range};
+
+/// Macros that implement a module, in the order they are checked.
+const IMPLEMENT_MACROS: &[(&str, ImplementMacroKind)] = &[
+    ("IMPLEMENT_MODULE", ImplementMacroKind::Module),
+    ("IMPLEMENT_GAME_MODULE", ImplementMacroKind::GameModule),
+    ("IMPLEMENT_PRIMARY_GAME_MODULE", ImplementMacroKind::PrimaryGameModule),
+];
+
+/// The kind of a token of C++ source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Identifier,
+    Literal,
+    Punctuation,
+}
+
+/// A token of C++ source code, as a range of bytes of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+}
+
+/// Split C++ source code into identifiers, literals and punctuation. Comments
+/// and preprocessor directives are skipped, so that only code is matched.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    let mut line_start = true;
+    while idx < bytes.len() {
+        let c = bytes[idx];
+        let start = idx;
+        match c {
+            b'\n' => {
+                line_start = true;
+                idx += 1;
+                continue;
+            }
+            c if c.is_ascii_whitespace() => {
+                idx += 1;
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                idx = line_end(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = find(bytes, idx + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+                continue;
+            }
+            b'#' if line_start => {
+                idx = line_end(bytes, idx);
+                continue;
+            }
+            b'"' | b'\'' => {
+                idx = literal_end(bytes, idx);
+                tokens.push(Token {
+                    kind: TokenKind::Literal,
+                    range: start..idx,
+                });
+            }
+            c if c == b'_' || c.is_ascii_alphabetic() => {
+                while idx < bytes.len() && is_identifier_byte(bytes[idx]) {
+                    idx += 1;
+                }
+                // Literals with an encoding prefix, such as L"Game" or u8"Game"
+                if matches!(bytes.get(idx), Some(b'"') | Some(b'\'')) {
+                    idx = literal_end(bytes, idx);
+                    tokens.push(Token {
+                        kind: TokenKind::Literal,
+                        range: start..idx,
+                    });
+                } else {
+                    tokens.push(Token {
+                        kind: TokenKind::Identifier,
+                        range: start..idx,
+                    });
+                }
+            }
+            c if c.is_ascii_digit() => {
+                while idx < bytes.len() && (bytes[idx] == b'.' || is_identifier_byte(bytes[idx])) {
+                    idx += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Literal,
+                    range: start..idx,
+                });
+            }
+            _ => {
+                idx += source[idx..].chars().next().map_or(1, char::len_utf8);
+                tokens.push(Token {
+                    kind: TokenKind::Punctuation,
+                    range: start..idx,
+                });
+            }
+        }
+        line_start = false;
+    }
+    tokens
+}
+
+fn is_identifier_byte(c: u8) -> bool {
+    c == b'_' || c.is_ascii_alphanumeric()
+}
+
+/// End of the line starting at `idx`, following line continuations.
+fn line_end(bytes: &[u8], mut idx: usize) -> usize {
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' if bytes[idx + 1..].starts_with(b"\n") => idx += 2,
+            b'\\' if bytes[idx + 1..].starts_with(b"\r\n") => idx += 3,
+            b'\n' => return idx,
+            _ => idx += 1,
+        }
+    }
+    idx
+}
+
+/// End of the string or character literal whose opening quote is at `idx`.
+fn literal_end(bytes: &[u8], idx: usize) -> usize {
+    let quote = bytes[idx];
+    let mut idx = idx + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            c if c == quote => return idx + 1,
+            b'\n' => return idx,
+            _ => idx += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
+}
+
+/// The macro used to implement a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImplementMacroKind {
+    /// `IMPLEMENT_MODULE(Class, Module)`
+    Module,
+    /// `IMPLEMENT_GAME_MODULE(Class, Module)`
+    GameModule,
+    /// `IMPLEMENT_PRIMARY_GAME_MODULE(Class, Module, "Game")`
+    PrimaryGameModule,
+}
+
+impl ImplementMacroKind {
+    fn argument_count(self) -> usize {
+        match self {
+            ImplementMacroKind::Module | ImplementMacroKind::GameModule => 2,
+            ImplementMacroKind::PrimaryGameModule => 3,
+        }
+    }
+}
+
+/// Reasons for which the module implementation macro of a source file could
+/// not be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImplementMacroError {
+    /// The source does not invoke any module implementation macro.
+    Missing,
+    /// The invocation is missing its closing parenthesis.
+    Unterminated,
+    /// The invocation has an unexpected number of arguments.
+    ArgumentCount { expected: usize, found: usize },
+}
+
+impl Display for ImplementMacroError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImplementMacroError::Missing => write!(f, "no IMPLEMENT_MODULE macro found"),
+            ImplementMacroError::Unterminated => {
+                write!(f, "IMPLEMENT_MODULE macro is missing its closing parenthesis")
+            }
+            ImplementMacroError::ArgumentCount { expected, found } => write!(
+                f,
+                "IMPLEMENT_MODULE macro has {} arguments instead of {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+/// An invocation of a module implementation macro, such as
+/// `IMPLEMENT_MODULE(FDefaultModuleImpl, Module)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplementMacro {
+    pub kind: ImplementMacroKind,
+    /// Range of the whole invocation within the source it was found in.
+    pub range: Range<usize>,
+    /// The source text of the whole invocation.
+    pub text: String,
+    /// Ranges of the arguments within `text`, without surrounding whitespace.
+    pub arguments: Vec<Range<usize>>,
+}
+
+impl ImplementMacro {
+    /// Find the module implementation macro invoked in C++ source code.
+    pub fn find(source: &str) -> Result<ImplementMacro, ImplementMacroError> {
+        let tokens = tokenize(source);
+        for (idx, token) in tokens.iter().enumerate() {
+            let text = &source[token.range.clone()];
+            let Some((_, kind)) = IMPLEMENT_MACROS.iter().find(|(name, _)| *name == text) else {
+                continue;
+            };
+            let is_call = tokens
+                .get(idx + 1)
+                .is_some_and(|next| &source[next.range.clone()] == "(");
+            if token.kind != TokenKind::Identifier || !is_call {
+                continue;
+            }
+            return ImplementMacro::parse(source, &tokens[idx..], *kind);
+        }
+        Err(ImplementMacroError::Missing)
+    }
+
+    /// Parse an invocation from its tokens, starting with the macro name.
+    fn parse(
+        source: &str,
+        tokens: &[Token],
+        kind: ImplementMacroKind,
+    ) -> Result<ImplementMacro, ImplementMacroError> {
+        let start = tokens[0].range.start;
+        let mut arguments: Vec<Range<usize>> = vec![];
+        let mut argument: Option<Range<usize>> = None;
+        let mut depth = 0;
+        for token in &tokens[1..] {
+            match &source[token.range.clone()] {
+                "(" if depth == 0 => {
+                    depth += 1;
+                    continue;
+                }
+                "(" => depth += 1,
+                ")" if depth == 1 => {
+                    arguments.extend(argument.take());
+                    let end = token.range.end;
+                    if arguments.len() != kind.argument_count() {
+                        return Err(ImplementMacroError::ArgumentCount {
+                            expected: kind.argument_count(),
+                            found: arguments.len(),
+                        });
+                    }
+                    return Ok(ImplementMacro {
+                        kind,
+                        range: start..end,
+                        text: source[start..end].to_owned(),
+                        arguments: arguments
+                            .into_iter()
+                            .map(|range| range.start - start..range.end - start)
+                            .collect(),
+                    });
+                }
+                ")" => depth -= 1,
+                "," if depth == 1 => {
+                    arguments.extend(argument.take());
+                    continue;
+                }
+                _ => {}
+            }
+            argument = Some(match argument {
+                Some(range) => range.start..token.range.end,
+                None => token.range.clone(),
+            });
+        }
+        Err(ImplementMacroError::Unterminated)
+    }
+
+    /// The argument at `idx`.
+    pub fn argument(&self, idx: usize) -> &str {
+        &self.text[self.arguments[idx].clone()]
+    }
+
+    /// The class implementing the module.
+    pub fn implementation(&self) -> &str {
+        self.argument(0)
+    }
+
+    /// The name of the module.
+    pub fn module_name(&self) -> &str {
+        self.argument(1)
+    }
+
+    /// The invocation with the module renamed. The game name passed to
+    /// `IMPLEMENT_PRIMARY_GAME_MODULE` is renamed only if it matches the old
+    /// module name. Everything else is kept as is.
+    pub fn renamed(&self, new_name: &str) -> String {
+        let old_name = self.module_name().to_owned();
+        self.replace_arguments(|idx, argument| match idx {
+            1 => Some(new_name.to_owned()),
+            2 if argument == format!(r#""{}""#, old_name) => Some(format!(r#""{}""#, new_name)),
+            _ => None,
+        })
+    }
+
+    /// The invocation with arguments replaced by `replace`, which receives the
+    /// index and text of each argument and returns its replacement, if any.
+    pub fn replace_arguments(&self, replace: impl Fn(usize, &str) -> Option<String>) -> String {
+        let mut text = self.text.clone();
+        for (idx, range) in self.arguments.iter().enumerate().rev() {
+            if let Some(replacement) = replace(idx, &self.text[range.clone()]) {
+                text.replace_range(range.clone(), &replacement);
+            }
+        }
+        text
+    }
+}
+
+/// Regex matching an identifier as a whole word.
+pub fn identifier_pattern(identifier: &str) -> String {
+    format!(r"\b{}\b", regex::escape(identifier))
+}
+
+/// An `#include` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Include {
+    /// The included path, without delimiters.
+    pub path: String,
+    /// Whether the path is delimited by angle brackets instead of quotes.
+    pub angled: bool,
+}
+
+impl Include {
+    /// The included path along with its delimiters, as written in the source.
+    pub fn literal(&self) -> String {
+        Include::literal_of(&self.path, self.angled)
+    }
+
+    /// A path along with delimiters.
+    pub fn literal_of(path: &str, angled: bool) -> String {
+        match angled {
+            true => format!("<{}>", path),
+            false => format!(r#""{}""#, path),
+        }
+    }
+}
+
+/// The `#include` directives of C++ source code, in order of appearance.
+pub fn includes(source: &str) -> Vec<Include> {
+    let regex = regex::Regex::new(r#"(?m)^[ \t]*#[ \t]*include[ \t]*(?:"([^"\n]+)"|<([^>\n]+)>)"#)
+        .expect("regex should be valid");
+    regex
+        .captures_iter(source)
+        .map(|captures| match captures.get(1) {
+            Some(path) => Include {
+                path: path.as_str().to_owned(),
+                angled: false,
+            },
+            None => Include {
+                path: captures[2].to_owned(),
+                angled: true,
+            },
+        })
+        .collect()
+}
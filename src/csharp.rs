@@ -0,0 +1,384 @@
+use std::ops::Range;
+
+/// The kind of a token of C# source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Identifier,
+    /// A string literal, regular, verbatim or interpolated.
+    String,
+    Other,
+}
+
+/// A token of C# source code, as a range of bytes of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+    /// For string literals, the range of their content between the quotes.
+    pub content: Range<usize>,
+}
+
+/// Split C# source code, such as a build or target file, into identifiers,
+/// string literals and other tokens. Comments and preprocessor directives are
+/// skipped, so that only code is matched.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    let mut line_start = true;
+    while idx < bytes.len() {
+        let c = bytes[idx];
+        let start = idx;
+        match c {
+            b'\n' => {
+                line_start = true;
+                idx += 1;
+                continue;
+            }
+            c if c.is_ascii_whitespace() => {
+                idx += 1;
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                idx = line_end(bytes, idx);
+                continue;
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = bytes[idx + 2..]
+                    .windows(2)
+                    .position(|window| window == b"*/")
+                    .map_or(bytes.len(), |end| idx + 2 + end + 2);
+                continue;
+            }
+            b'#' if line_start => {
+                idx = line_end(bytes, idx);
+                continue;
+            }
+            b'"' | b'@' | b'$' if string_prefix_len(&bytes[idx..]).is_some() => {
+                let prefix = string_prefix_len(&bytes[idx..]).unwrap();
+                let verbatim = bytes[idx..idx + prefix].contains(&b'@');
+                let content_start = idx + prefix + 1;
+                let (content_end, end) = string_end(bytes, content_start, verbatim);
+                idx = end;
+                tokens.push(Token {
+                    kind: TokenKind::String,
+                    range: start..end,
+                    content: content_start..content_end,
+                });
+            }
+            b'\'' => {
+                idx += 1;
+                while idx < bytes.len() && bytes[idx] != b'\'' && bytes[idx] != b'\n' {
+                    idx += if bytes[idx] == b'\\' { 2 } else { 1 };
+                }
+                idx = (idx + 1).min(bytes.len());
+                tokens.push(Token {
+                    kind: TokenKind::Other,
+                    range: start..idx,
+                    content: start..idx,
+                });
+            }
+            c if c == b'_'
+                || c.is_ascii_alphabetic()
+                || (c == b'@' && bytes.get(idx + 1).is_some_and(|c| is_identifier_byte(*c))) =>
+            {
+                idx += 1;
+                while idx < bytes.len() && is_identifier_byte(bytes[idx]) {
+                    idx += 1;
+                }
+                // Verbatim identifiers, such as @class, are named without the @.
+                let name_start = if c == b'@' { start + 1 } else { start };
+                tokens.push(Token {
+                    kind: TokenKind::Identifier,
+                    range: start..idx,
+                    content: name_start..idx,
+                });
+            }
+            c if c.is_ascii_digit() => {
+                while idx < bytes.len() && (bytes[idx] == b'.' || is_identifier_byte(bytes[idx])) {
+                    idx += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Other,
+                    range: start..idx,
+                    content: start..idx,
+                });
+            }
+            _ => {
+                idx += source[idx..].chars().next().map_or(1, char::len_utf8);
+                tokens.push(Token {
+                    kind: TokenKind::Other,
+                    range: start..idx,
+                    content: start..idx,
+                });
+            }
+        }
+        line_start = false;
+    }
+    tokens
+}
+
+fn is_identifier_byte(c: u8) -> bool {
+    c == b'_' || c.is_ascii_alphanumeric()
+}
+
+fn line_end(bytes: &[u8], idx: usize) -> usize {
+    bytes[idx..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(bytes.len(), |end| idx + end)
+}
+
+/// Length of the prefix of a string literal up to its opening quote, such as
+/// `@` or `$@`, if `bytes` start with a string literal.
+fn string_prefix_len(bytes: &[u8]) -> Option<usize> {
+    ["\"", "@\"", "$\"", "$@\"", "@$\""]
+        .iter()
+        .find(|prefix| bytes.starts_with(prefix.as_bytes()))
+        .map(|prefix| prefix.len() - 1)
+}
+
+/// End of the content and end of a string literal whose content starts at
+/// `idx`. Verbatim strings escape quotes by doubling them and span lines.
+fn string_end(bytes: &[u8], mut idx: usize, verbatim: bool) -> (usize, usize) {
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'"' if verbatim && bytes.get(idx + 1) == Some(&b'"') => idx += 2,
+            b'"' => return (idx, idx + 1),
+            b'\\' if !verbatim => idx += 2,
+            b'\n' if !verbatim => return (idx, idx),
+            _ => idx += 1,
+        }
+    }
+    (bytes.len(), bytes.len())
+}
+
+/// The property or variable that the string literal at `idx` is assigned to,
+/// added to or compared with, such as `LaunchModuleName` in
+/// `LaunchModuleName = "Game"`, `ExtraModuleNames` in
+/// `ExtraModuleNames.AddRange(new string[] { "Game" })` or `Name` in
+/// `Target.Name == "Game"`.
+pub fn property_of<'a>(source: &'a str, tokens: &[Token], idx: usize) -> Option<&'a str> {
+    let text = |idx: usize| &source[tokens[idx].range.clone()];
+    let identifier = |idx: Option<usize>| {
+        idx.filter(|idx| tokens[*idx].kind == TokenKind::Identifier)
+            .map(|idx| &source[tokens[idx].content.clone()])
+    };
+    // Skip other elements of the same list or collection initializer.
+    let mut idx = idx.checked_sub(1)?;
+    while tokens[idx].kind == TokenKind::String
+        || ["new", "string", ",", "{", "[", "]", "("].contains(&text(idx))
+    {
+        if text(idx) == "(" && idx > 0 && tokens[idx - 1].kind == TokenKind::Identifier {
+            break;
+        }
+        idx = idx.checked_sub(1)?;
+    }
+    match text(idx) {
+        // Comparisons, such as `Name == "Game"` and `Name != "Game"`
+        "=" if idx >= 2 && ["=", "!"].contains(&text(idx - 1)) => identifier(Some(idx - 2)),
+        // Assignments, such as `Name = "Game"` and `Names += "Game"`
+        "=" if idx >= 2 && text(idx - 1) == "+" => identifier(Some(idx - 2)),
+        "=" => identifier(idx.checked_sub(1)),
+        // Method calls, such as `Names.Add("Game")`
+        "(" if idx >= 3 && text(idx - 2) == "." => identifier(Some(idx - 3)),
+        _ => None,
+    }
+}
+
+/// Replace identifiers and/or string literals of C# source code that are
+/// exactly `from` with `to`. Partial matches, such as `GameplayTags` when
+/// replacing `Game`, and comments are left as they are.
+pub fn replace_references(
+    source: &str,
+    from: &str,
+    to: &str,
+    identifiers: bool,
+    strings: bool,
+) -> String {
+    replace_tokens(source, from, to, |tokens, idx| match tokens[idx].kind {
+        TokenKind::Identifier => identifiers,
+        TokenKind::String => strings,
+        TokenKind::Other => false,
+    })
+}
+
+/// Replace string literals of C# source code that are exactly `from` with
+/// `to`, if they are used with one of `properties` as found by
+/// [`property_of`].
+pub fn replace_property_references(
+    source: &str,
+    from: &str,
+    to: &str,
+    properties: &[String],
+) -> String {
+    replace_tokens(source, from, to, |tokens, idx| {
+        tokens[idx].kind == TokenKind::String
+            && property_of(source, tokens, idx)
+                .is_some_and(|property| properties.iter().any(|known| known == property))
+    })
+}
+
+fn replace_tokens(
+    source: &str,
+    from: &str,
+    to: &str,
+    matches: impl Fn(&[Token], usize) -> bool,
+) -> String {
+    let tokens = tokenize(source);
+    let mut result = source.to_owned();
+    for (idx, token) in tokens.iter().enumerate().rev() {
+        if &source[token.content.clone()] == from && matches(&tokens, idx) {
+            result.replace_range(token.content.clone(), to);
+        }
+    }
+    result
+}
+
+/// Add a string to a list property, such as `ExtraModuleNames`, in C# source
+/// code. The string is added to the last `AddRange` initializer of the
+/// property, or in a new `Add` call after the last one. Returns the range of
+/// the source to replace and its replacement, or `None` if the property is
+/// not added to.
+pub fn add_to_list(source: &str, property: &str, value: &str) -> Option<(Range<usize>, String)> {
+    let tokens = tokenize(source);
+    let text = |idx: usize| &source[tokens[idx].range.clone()];
+    let calls: Vec<(usize, &str)> = (0..tokens.len().saturating_sub(3))
+        .filter(|idx| tokens[*idx].kind == TokenKind::Identifier && text(*idx) == property)
+        .filter(|idx| text(idx + 1) == "." && text(idx + 3) == "(")
+        .map(|idx| (idx, text(idx + 2)))
+        .filter(|(_, method)| ["Add", "AddRange"].contains(method))
+        .collect();
+    let statement_end = |idx: usize| (idx..tokens.len()).find(|idx| text(*idx) == ";");
+    let literal = format!(r#""{}""#, value);
+
+    if let Some((start, _)) = calls.iter().rev().find(|(_, method)| *method == "AddRange") {
+        let end = statement_end(*start)?;
+        let close = (*start..end).rev().find(|idx| text(*idx) == "}")?;
+        // After the last element, or right after the brace of an empty list
+        let insert_at = tokens[close - 1].range.end;
+        let insertion = match tokens[close - 1].kind {
+            TokenKind::String => format!(", {}", literal),
+            _ => format!(" {}", literal),
+        };
+        let range = tokens[*start].range.start..tokens[end].range.end;
+        let replacement = format!(
+            "{}{}{}",
+            &source[range.start..insert_at],
+            insertion,
+            &source[insert_at..range.end]
+        );
+        return Some((range, replacement));
+    }
+
+    let (start, _) = calls.last()?;
+    let end = statement_end(*start)?;
+    let range = tokens[*start].range.start..tokens[end].range.end;
+    let line_start = source[..range.start].rfind('\n').map_or(0, |newline| newline + 1);
+    let indent = &source[line_start..range.start];
+    let replacement = format!(
+        "{}\n{}{}.Add({});",
+        &source[range.clone()],
+        indent,
+        property,
+        literal
+    );
+    Some((range, replacement))
+}
+
+/// The value assigned to a property in C# source code, such as
+/// `TargetType.Editor` in `Type = TargetType.Editor;`, with whitespace and
+/// comments removed. Returns the first assignment, or `None` if the property
+/// is not assigned to.
+pub fn assigned_value(source: &str, property: &str) -> Option<String> {
+    let tokens = tokenize(source);
+    let text = |idx: usize| tokens.get(idx).map_or("", |token| &source[token.range.clone()]);
+    let start = (0..tokens.len())
+        .filter(|idx| tokens[*idx].kind == TokenKind::Identifier && text(*idx) == property)
+        .find(|idx| text(idx + 1) == "=" && text(idx + 2) != "=")?
+        + 2;
+    let end = (start..tokens.len()).find(|idx| text(*idx) == ";")?;
+    Some((start..end).map(text).collect())
+}
+
+/// Properties that a string literal exactly `value` is used with, as found by
+/// [`property_of`], in order of first appearance.
+pub fn properties_with(source: &str, value: &str) -> Vec<String> {
+    let tokens = tokenize(source);
+    let mut properties: Vec<String> = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::String || &source[token.content.clone()] != value {
+            continue;
+        }
+        if let Some(property) = property_of(source, &tokens, idx) {
+            if !properties.iter().any(|known| known == property) {
+                properties.push(property.to_owned());
+            }
+        }
+    }
+    properties
+}
+
+/// Remove string literals that are exactly `value` from list properties, such
+/// as `ExtraModuleNames`, in C# source code. Elements of `AddRange`
+/// initializers are removed along with their separating comma, and `Add`
+/// calls with only that element are removed entirely. Assignments and
+/// comparisons are left as they are.
+pub fn remove_from_lists(source: &str, value: &str, properties: &[String]) -> String {
+    let tokens = tokenize(source);
+    let text = |idx: usize| tokens.get(idx).map_or("", |token| &source[token.range.clone()]);
+    let mut removals: Vec<Range<usize>> = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::String || &source[token.content.clone()] != value {
+            continue;
+        }
+        if !property_of(source, &tokens, idx)
+            .is_some_and(|property| properties.iter().any(|known| known == property))
+        {
+            continue;
+        }
+        let previous = idx.checked_sub(1).map_or("", text);
+        if previous == "=" {
+            continue;
+        }
+        let removal = if previous == "("
+            && idx >= 4
+            && text(idx - 2) == "Add"
+            && text(idx + 1) == ")"
+            && text(idx + 2) == ";"
+        {
+            // The whole statement, along with its line if it has one to itself
+            let start = tokens[idx - 4].range.start;
+            let end = tokens[idx + 2].range.end;
+            let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+            let line_end = source[end..].find('\n').map_or(source.len(), |newline| end + newline + 1);
+            match source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+                true => line_start..line_end,
+                false => start..end,
+            }
+        } else if text(idx + 1) == "," {
+            let next = tokens.get(idx + 2).map_or(source.len(), |next| next.range.start);
+            token.range.start..next
+        } else if previous == "," {
+            tokens[idx - 1].range.start..token.range.end
+        } else {
+            // The only element, along with the whitespace before it
+            tokens[idx - 1].range.end..token.range.end
+        };
+        removals.push(removal);
+    }
+
+    // Merge overlapping removals, such as those of adjacent duplicates.
+    let mut merged: Vec<Range<usize>> = vec![];
+    for removal in removals {
+        match merged.last_mut() {
+            Some(last) if removal.start <= last.end => last.end = last.end.max(removal.end),
+            _ => merged.push(removal),
+        }
+    }
+    let mut result = source.to_owned();
+    for removal in merged.into_iter().rev() {
+        result.replace_range(removal, "");
+    }
+    result
+}
@@ -0,0 +1,454 @@
+//! Edits of the `Modules` and `Plugins` arrays of project and plugin
+//! descriptors that keep the rest of the descriptor, including its
+//! formatting, as it is.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+/// Module types that can be declared in a descriptor.
+pub const MODULE_TYPES: &[&str] = &[
+    "Runtime",
+    "RuntimeNoCommandlet",
+    "RuntimeAndProgram",
+    "CookedOnly",
+    "UncookedOnly",
+    "Developer",
+    "DeveloperTool",
+    "Editor",
+    "EditorNoCommandlet",
+    "EditorAndProgram",
+    "Program",
+    "ServerOnly",
+    "ClientOnly",
+    "ClientOnlyNoCommandlet",
+];
+
+/// Loading phases that can be declared in a descriptor.
+pub const LOADING_PHASES: &[&str] = &[
+    "EarliestPossible",
+    "PostConfigInit",
+    "PostSplashScreen",
+    "PreEarlyLoadingScreen",
+    "PreLoadingScreen",
+    "PreDefault",
+    "Default",
+    "PostDefault",
+    "PostEngineInit",
+    "None",
+];
+
+/// A descriptor entry for a module, as JSON text without leading indentation.
+pub fn module_entry(name: &str, module_type: &str, loading_phase: &str) -> String {
+    format!(
+        "{{\n\t\"Name\": \"{}\",\n\t\"Type\": \"{}\",\n\t\"LoadingPhase\": \"{}\"\n}}",
+        name, module_type, loading_phase
+    )
+}
+
+/// A descriptor entry enabling a plugin, as JSON text without leading
+/// indentation.
+pub fn plugin_entry(name: &str) -> String {
+    format!("{{\n\t\"Name\": \"{}\",\n\t\"Enabled\": true\n}}", name)
+}
+
+/// A new plugin descriptor listing a single module entry, as returned by
+/// [`module_entry`].
+pub fn plugin_descriptor(name: &str, can_contain_content: bool, module_entry: &str) -> String {
+    format!(
+        "{{\n\
+        \t\"FileVersion\": 3,\n\
+        \t\"Version\": 1,\n\
+        \t\"VersionName\": \"1.0\",\n\
+        \t\"FriendlyName\": \"{name}\",\n\
+        \t\"Description\": \"\",\n\
+        \t\"Category\": \"Other\",\n\
+        \t\"CreatedBy\": \"\",\n\
+        \t\"CreatedByURL\": \"\",\n\
+        \t\"DocsURL\": \"\",\n\
+        \t\"MarketplaceURL\": \"\",\n\
+        \t\"SupportURL\": \"\",\n\
+        \t\"CanContainContent\": {can_contain_content},\n\
+        \t\"IsBetaVersion\": false,\n\
+        \t\"IsExperimentalVersion\": false,\n\
+        \t\"Installed\": false,\n\
+        \t\"Modules\": [\n\
+        \t\t{module}\n\
+        \t]\n\
+        }}\n",
+        module = reindent(module_entry, "\t\t", "\t"),
+    )
+}
+
+/// Add an entry, such as returned by [`module_entry`] or [`plugin_entry`], to
+/// an array of a descriptor. The array is created if it does not exist yet.
+pub fn add_entry(content: &str, array: &str, name: &str, entry: &str) -> Result<String, String> {
+    let bytes = content.as_bytes();
+    let root = skip_whitespace(bytes, 0);
+    let members = object_members(bytes, root)?;
+    let unit = indent_unit(content, &members);
+    let result = match members.iter().find(|member| member.key == array) {
+        Some(array_member) => {
+            if bytes.get(array_member.value.start) != Some(&b'[') {
+                return Err(format!("{} is not an array", array));
+            }
+            let elements = array_elements(bytes, array_member.value.start)?;
+            if find_entry(content, &elements, name).is_some() {
+                return Err(format!("{} is already listed in {}", name, array));
+            }
+            match elements.last() {
+                Some(last) => {
+                    let indent = line_indent(content, last.start);
+                    let entry = reindent(entry, indent, &unit);
+                    format!("{},\n{}{}{}", &content[..last.end], indent, entry, &content[last.end..])
+                }
+                None => {
+                    let indent = line_indent(content, array_member.key_start);
+                    let inner = format!("{}{}", indent, unit);
+                    let entry = reindent(entry, &inner, &unit);
+                    format!(
+                        "{}[\n{}{}\n{}]{}",
+                        &content[..array_member.value.start],
+                        inner,
+                        entry,
+                        indent,
+                        &content[array_member.value.end..]
+                    )
+                }
+            }
+        }
+        None => {
+            let Some(last) = members.last() else {
+                return Err("descriptor has no members".into());
+            };
+            let indent = line_indent(content, last.key_start);
+            let inner = format!("{}{}", indent, unit);
+            let entry = reindent(entry, &inner, &unit);
+            format!(
+                "{},\n{}\"{}\": [\n{}{}\n{}]{}",
+                &content[..last.value.end],
+                indent,
+                array,
+                inner,
+                entry,
+                indent,
+                &content[last.value.end..]
+            )
+        }
+    };
+    serde_json::from_str::<Value>(&result).map_err(|err| err.to_string())?;
+    Ok(result)
+}
+
+/// Remove the entry named `name` from an array of a descriptor, such as
+/// `Modules` or `Plugins`, along with its separating comma.
+pub fn remove_entry(content: &str, array: &str, name: &str) -> Result<String, String> {
+    let bytes = content.as_bytes();
+    let root = skip_whitespace(bytes, 0);
+    let members = object_members(bytes, root)?;
+    let array_member = members
+        .iter()
+        .find(|member| member.key == array)
+        .ok_or_else(|| format!("descriptor has no {}", array))?;
+    if bytes.get(array_member.value.start) != Some(&b'[') {
+        return Err(format!("{} is not an array", array));
+    }
+    let elements = array_elements(bytes, array_member.value.start)?;
+    let idx = find_entry(content, &elements, name)
+        .ok_or_else(|| format!("{} is not listed in {}", name, array))?;
+    let removal = match (elements.get(idx + 1), idx.checked_sub(1)) {
+        (Some(next), _) => elements[idx].start..next.start,
+        (None, Some(previous)) => elements[previous].end..elements[idx].end,
+        // The only element, leaving an empty array
+        (None, None) => array_member.value.start + 1..array_member.value.end - 1,
+    };
+    let result = format!("{}{}", &content[..removal.start], &content[removal.end..]);
+    serde_json::from_str::<Value>(&result).map_err(|err| err.to_string())?;
+    Ok(result)
+}
+
+/// The entry named `name` in an array of a descriptor as JSON text without
+/// leading indentation, indented with tabs like [`module_entry`], so that it
+/// can be added to another descriptor with [`add_entry`].
+pub fn listed_entry(content: &str, array: &str, name: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let members = object_members(bytes, skip_whitespace(bytes, 0)).ok()?;
+    let unit = indent_unit(content, &members);
+    let array_member = members.iter().find(|member| member.key == array)?;
+    let elements = array_elements(bytes, array_member.value.start).ok()?;
+    let element = elements[find_entry(content, &elements, name)?].clone();
+    let base = line_indent(content, element.start);
+    let entry = content[element]
+        .lines()
+        .map(|line| {
+            let line = line.strip_prefix(base).unwrap_or(line);
+            let mut depth = 0;
+            let mut rest = line;
+            while let Some(stripped) = rest.strip_prefix(unit.as_str()) {
+                depth += 1;
+                rest = stripped;
+            }
+            format!("{}{}", "\t".repeat(depth), rest)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(entry)
+}
+
+/// Names of the entries of an array of a descriptor, such as `Plugins`.
+pub fn listed_names(content: &str, array: &str) -> Vec<String> {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|descriptor| descriptor.get(array).cloned())
+        .and_then(|entries| entries.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| entry.get("Name")?.as_str().map(str::to_owned))
+        .collect()
+}
+
+/// Replace the string values exactly `from` in the `lists` of the entries of
+/// the `Modules` and `Plugins` arrays of a descriptor, such as the target
+/// names in `TargetAllowList`, keeping the rest of the descriptor as it is.
+pub fn replace_list_values(
+    content: &str,
+    lists: &[String],
+    from: &str,
+    to: &str,
+) -> Result<String, String> {
+    let bytes = content.as_bytes();
+    let members = object_members(bytes, skip_whitespace(bytes, 0))?;
+    let mut ranges = vec![];
+    for array_member in members
+        .iter()
+        .filter(|member| member.key == "Modules" || member.key == "Plugins")
+        .filter(|member| bytes.get(member.value.start) == Some(&b'['))
+    {
+        for element in array_elements(bytes, array_member.value.start)? {
+            if bytes.get(element.start) != Some(&b'{') {
+                continue;
+            }
+            for list in object_members(bytes, element.start)?
+                .iter()
+                .filter(|member| lists.contains(&member.key))
+                .filter(|member| bytes.get(member.value.start) == Some(&b'['))
+            {
+                ranges.extend(
+                    array_elements(bytes, list.value.start)?
+                        .into_iter()
+                        .filter(|value| {
+                            serde_json::from_str::<Value>(&content[value.clone()])
+                                .is_ok_and(|value| value.as_str() == Some(from))
+                        }),
+                );
+            }
+        }
+    }
+    let replacement = serde_json::to_string(to).map_err(|err| err.to_string())?;
+    let mut result = content.to_owned();
+    for range in ranges.into_iter().rev() {
+        result.replace_range(range, &replacement);
+    }
+    serde_json::from_str::<Value>(&result).map_err(|err| err.to_string())?;
+    Ok(result)
+}
+
+/// Whether the `lists` of the entries of the `Modules` and `Plugins` arrays of
+/// a descriptor contain `value`.
+pub fn lists_contain(content: &str, lists: &[String], value: &str) -> bool {
+    let Ok(descriptor) = serde_json::from_str::<Value>(content) else {
+        return false;
+    };
+    ["Modules", "Plugins"]
+        .iter()
+        .filter_map(|array| descriptor.get(*array)?.as_array())
+        .flatten()
+        .flat_map(|entry| lists.iter().filter_map(move |list| entry.get(list)?.as_array()))
+        .flatten()
+        .any(|listed| listed.as_str() == Some(value))
+}
+
+/// A module listed in a descriptor.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListedModule {
+    pub name: String,
+    pub module_type: String,
+}
+
+/// The modules listed in a descriptor, in order.
+pub fn listed_modules(content: &str) -> Vec<ListedModule> {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|descriptor| descriptor.get("Modules").cloned())
+        .and_then(|modules| modules.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|module| {
+            Some(ListedModule {
+                name: module.get("Name")?.as_str()?.to_owned(),
+                module_type: module.get("Type")?.as_str()?.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// A member of a JSON object.
+struct Member {
+    key: String,
+    key_start: usize,
+    value: Range<usize>,
+}
+
+fn find_entry(content: &str, elements: &[Range<usize>], name: &str) -> Option<usize> {
+    elements.iter().position(|element| {
+        serde_json::from_str::<Value>(&content[element.clone()])
+            .ok()
+            .is_some_and(|entry| entry.get("Name").and_then(Value::as_str) == Some(name))
+    })
+}
+
+/// The indentation of the line containing `idx`.
+fn line_indent(content: &str, idx: usize) -> &str {
+    let line_start = content[..idx].rfind('\n').map_or(0, |newline| newline + 1);
+    let line = &content[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// One level of indentation, as used by the top-level members of a descriptor.
+fn indent_unit(content: &str, members: &[Member]) -> String {
+    members
+        .first()
+        .map(|member| line_indent(content, member.key_start))
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or("\t")
+        .to_owned()
+}
+
+/// Indent all lines but the first of a JSON entry by `indent`, and convert its
+/// tabs to the indentation unit of the descriptor.
+fn reindent(entry: &str, indent: &str, unit: &str) -> String {
+    entry
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let trimmed = line.trim_start_matches('\t');
+            let depth = line.len() - trimmed.len();
+            match idx {
+                0 => format!("{}{}", unit.repeat(depth), trimmed),
+                _ => format!("{}{}{}", indent, unit.repeat(depth), trimmed),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn skip_whitespace(bytes: &[u8], mut idx: usize) -> usize {
+    while idx < bytes.len() && bytes[idx].is_ascii_whitespace() {
+        idx += 1;
+    }
+    idx
+}
+
+/// End of the JSON value starting at `idx`.
+fn value_end(bytes: &[u8], idx: usize) -> Result<usize, String> {
+    match bytes.get(idx) {
+        Some(b'{') => object_members(bytes, idx).map(|_| closing_end(bytes, idx)),
+        Some(b'[') => array_elements(bytes, idx).map(|_| closing_end(bytes, idx)),
+        Some(b'"') => string_end(bytes, idx),
+        Some(_) => {
+            let mut end = idx;
+            while end < bytes.len() && !b",]}".contains(&bytes[end]) && !bytes[end].is_ascii_whitespace() {
+                end += 1;
+            }
+            Ok(end)
+        }
+        None => Err("unexpected end of descriptor".into()),
+    }
+}
+
+/// End of the object or array starting at `idx`, which is known to be valid.
+fn closing_end(bytes: &[u8], idx: usize) -> usize {
+    let mut depth = 0;
+    let mut idx = idx;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'"' => {
+                idx = string_end(bytes, idx).unwrap_or(bytes.len());
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return idx + 1;
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    bytes.len()
+}
+
+fn string_end(bytes: &[u8], idx: usize) -> Result<usize, String> {
+    let mut idx = idx + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'"' => return Ok(idx + 1),
+            _ => idx += 1,
+        }
+    }
+    Err("unterminated string in descriptor".into())
+}
+
+fn object_members(bytes: &[u8], idx: usize) -> Result<Vec<Member>, String> {
+    if bytes.get(idx) != Some(&b'{') {
+        return Err("descriptor is not a JSON object".into());
+    }
+    let mut members = vec![];
+    let mut idx = skip_whitespace(bytes, idx + 1);
+    if bytes.get(idx) == Some(&b'}') {
+        return Ok(members);
+    }
+    loop {
+        let key_start = idx;
+        let key_end = string_end(bytes, key_start)?;
+        let key = String::from_utf8_lossy(&bytes[key_start + 1..key_end - 1]).into_owned();
+        idx = skip_whitespace(bytes, key_end);
+        if bytes.get(idx) != Some(&b':') {
+            return Err(format!("expected ':' after {} in descriptor", key));
+        }
+        let value_start = skip_whitespace(bytes, idx + 1);
+        let value_end = value_end(bytes, value_start)?;
+        members.push(Member {
+            key,
+            key_start,
+            value: value_start..value_end,
+        });
+        idx = skip_whitespace(bytes, value_end);
+        match bytes.get(idx) {
+            Some(b',') => idx = skip_whitespace(bytes, idx + 1),
+            Some(b'}') => return Ok(members),
+            _ => return Err("expected ',' or '}' in descriptor".into()),
+        }
+    }
+}
+
+fn array_elements(bytes: &[u8], idx: usize) -> Result<Vec<Range<usize>>, String> {
+    let mut elements = vec![];
+    let mut idx = skip_whitespace(bytes, idx + 1);
+    if bytes.get(idx) == Some(&b']') {
+        return Ok(elements);
+    }
+    loop {
+        let end = value_end(bytes, idx)?;
+        elements.push(idx..end);
+        idx = skip_whitespace(bytes, end);
+        match bytes.get(idx) {
+            Some(b',') => idx = skip_whitespace(bytes, idx + 1),
+            Some(b']') => return Ok(elements),
+            _ => return Err("expected ',' or ']' in descriptor".into()),
+        }
+    }
+}
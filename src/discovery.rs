@@ -0,0 +1,153 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    artifacts::GENERATED_DIR_NAMES,
+    csharp,
+    unreal::{Module, ModuleType, Plugin, Target},
+};
+
+/// Properties of build files that list the modules that a module depends on.
+pub const DEPENDENCY_PROPERTIES: &[&str] = &[
+    "PublicDependencyModuleNames",
+    "PrivateDependencyModuleNames",
+    "DynamicallyLoadedModuleNames",
+    "PublicIncludePathModuleNames",
+    "PrivateIncludePathModuleNames",
+    "CircularlyReferencedDependentModules",
+];
+
+/// A module whose build file lists another module as a dependency.
+pub struct Dependent {
+    pub module: Module,
+    pub build_file: PathBuf,
+    /// The dependency properties that list the other module.
+    pub properties: Vec<String>,
+}
+
+/// Find the project descriptor in the project root, if any.
+pub fn find_project_descriptor(project_root: &Path) -> Option<PathBuf> {
+    fs::read_dir(project_root)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "uproject"))
+}
+
+/// Walk a directory of the project, skipping directories generated by the
+/// engine, build tools and IDEs.
+fn walk_sources(dir: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !GENERATED_DIR_NAMES.iter().any(|name| entry.file_name() == *name))
+        .filter_map(Result::ok)
+}
+
+/// Detect all plugins in a project, including nested plugins.
+pub fn detect_project_plugins(project_root: &Path) -> Vec<Plugin> {
+    walk_sources(&project_root.join("Plugins"))
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "uplugin"))
+        .filter_map(|entry| {
+            Some(Plugin {
+                root: entry.path().parent()?.to_owned(),
+                name: entry.path().file_stem()?.to_str()?.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Detect the modules of a project and its plugins, including nested modules.
+pub fn detect_modules(project_root: &Path) -> Vec<Module> {
+    let project_modules = detect_modules_in(&project_root.join("Source"), ModuleType::Project, None);
+    let plugin_modules = detect_project_plugins(project_root).into_iter().flat_map(|plugin| {
+        detect_modules_in(&plugin.root.join("Source"), ModuleType::Plugin, Some(plugin))
+    });
+    project_modules.into_iter().chain(plugin_modules).collect()
+}
+
+fn detect_modules_in(dir: &Path, r#type: ModuleType, plugin: Option<Plugin>) -> Vec<Module> {
+    walk_sources(dir)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_owned();
+            entry.path().join(format!("{}.Build.cs", name)).is_file().then(|| Module {
+                root: entry.path().to_owned(),
+                name,
+                r#type: r#type.clone(),
+                plugin: plugin.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Detect the targets of a project.
+pub fn detect_project_targets(project_root: &Path) -> Vec<Target> {
+    let source_dir = project_root.join("Source");
+    let Ok(entries) = fs::read_dir(&source_dir) else {
+        return vec![];
+    };
+    let mut targets: Vec<Target> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_str()?.to_owned();
+            let name = file_name.strip_suffix(".Target.cs")?;
+            Some(Target {
+                name: name.to_owned(),
+                path: entry.path(),
+            })
+        })
+        .collect();
+    targets.sort_by(|a, b| a.name.cmp(&b.name));
+    targets
+}
+
+/// Detect the modules of a project and its plugins that depend on the module
+/// `name`, as listed in the dependency properties of their build files.
+pub fn detect_dependents(project_root: &Path, name: &str) -> Vec<Dependent> {
+    detect_modules(project_root)
+        .into_iter()
+        .filter(|module| module.name != name)
+        .filter_map(|module| {
+            let build_file = module.root.join(format!("{}.Build.cs", module.name));
+            let content = fs::read_to_string(&build_file).ok()?;
+            let properties: Vec<String> = csharp::properties_with(&content, name)
+                .into_iter()
+                .filter(|property| DEPENDENCY_PROPERTIES.contains(&property.as_str()))
+                .collect();
+            (!properties.is_empty()).then_some(Dependent {
+                module,
+                build_file,
+                properties,
+            })
+        })
+        .collect()
+}
+
+/// Names of the modules that a module depends on, as listed in the
+/// dependency properties of its build file.
+pub fn detect_dependencies(module: &Module) -> Vec<String> {
+    let build_file = module.root.join(format!("{}.Build.cs", module.name));
+    let Ok(content) = fs::read_to_string(build_file) else {
+        return vec![];
+    };
+    let tokens = csharp::tokenize(&content);
+    let mut dependencies: Vec<String> = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.kind != csharp::TokenKind::String {
+            continue;
+        }
+        let Some(property) = csharp::property_of(&content, &tokens, idx) else {
+            continue;
+        };
+        let name = &content[token.content.clone()];
+        if DEPENDENCY_PROPERTIES.contains(&property) && !dependencies.iter().any(|known| known == name) {
+            dependencies.push(name.to_owned());
+        }
+    }
+    dependencies
+}
@@ -1,17 +1,23 @@
-use std::path::Path;
+use std::{path::Path, rc::Rc};
 
 use crate::{
     changes::{Change, Revert},
-    presentation::log,
+    presentation::{log, reporter::Reporter},
 };
 
 pub struct Engine {
     history: Vec<(Change, Revert)>,
+    reporter: Rc<dyn Reporter>,
 }
 
 impl Engine {
-    pub fn new() -> Self {
-        Self { history: vec![] }
+    /// Create an engine that reports everything that happens while executing
+    /// or reverting changes to `reporter`.
+    pub fn with_reporter(reporter: Rc<dyn Reporter>) -> Self {
+        Self {
+            history: vec![],
+            reporter,
+        }
     }
 
     /// Execute a series of changes in sequential order and stores the
@@ -22,20 +28,21 @@ impl Engine {
         changeset: Vec<Change>,
         backup_dir: impl AsRef<Path>,
     ) -> Result<(), String> {
-        log::verbose(format!("Starting execution of {} changes", changeset.len()));
-        log::verbose_with_category("engine", format!("Backup directory: {:?}", backup_dir.as_ref()));
+        let reporter = self.reporter.clone();
+        log::verbose(&*reporter, format!("Starting execution of {} changes", changeset.len()));
+        log::verbose_with_category(&*reporter, "engine", format!("Backup directory: {:?}", backup_dir.as_ref()));
         for (idx, change) in changeset.into_iter().enumerate() {
-            log::verbose_with_category("engine", format!("Executing change {}: {}", idx + 1, &change));
-            log::step("apply", &change);
+            log::verbose_with_category(&*reporter, "engine", format!("Executing change {}: {}", idx + 1, &change));
+            log::change(&*reporter, "apply", &change);
             self.execute_single(change, backup_dir.as_ref())?;
-            log::verbose_with_category("engine", format!("Change {} completed successfully", idx + 1));
+            log::verbose_with_category(&*reporter, "engine", format!("Change {} completed successfully", idx + 1));
         }
-        log::verbose("All changes executed successfully");
+        log::verbose(&*reporter, "All changes executed successfully");
         Ok(())
     }
 
     fn execute_single(&mut self, change: Change, backup_dir: &Path) -> Result<(), String> {
-        match change.apply(backup_dir) {
+        match change.apply(backup_dir, &*self.reporter) {
             Ok(revert) => {
                 self.history.push((change, revert));
                 Ok(())
@@ -47,16 +54,21 @@ impl Engine {
     /// Revert entire history of actions.
     /// Upon error, it will halt execution and return the error.
     pub fn revert(&mut self) -> Result<(), String> {
-        log::verbose(format!("Starting revert of {} changes", self.history.len()));
+        let reporter = self.reporter.clone();
+        log::verbose(&*reporter, format!("Starting revert of {} changes", self.history.len()));
         let mut count = 0;
         while let Some((change, revert)) = self.history.pop() {
             count += 1;
-            log::verbose_with_category("revert", format!("Reverting change {}: {}", count, &change));
-            log::step("revert", &change);
+            log::verbose_with_category(&*reporter, "revert", format!("Reverting change {}: {}", count, &change));
+            log::change(&*reporter, "revert", &change);
             revert().map_err(|err| err.to_string())?;
-            log::verbose_with_category("revert", format!("Change {} reverted successfully", count));
+            log::verbose_with_category(
+                &*reporter,
+                "revert",
+                format!("Change {} reverted successfully", count),
+            );
         }
-        log::verbose("All changes reverted successfully");
+        log::verbose(&*reporter, "All changes reverted successfully");
         Ok(())
     }
 }
@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+/// Reasons a workflow can fail, each with a distinct process exit code so
+/// that scripts can tell what state the project was left in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The provided parameters are invalid. Nothing was changed.
+    Validation(String),
+    /// The project could not be inspected. Nothing was changed.
+    Detection(String),
+    /// Applying changes failed, but all of them were reverted. The project is
+    /// in its original state.
+    RolledBack(String),
+    /// Applying changes failed and reverting them failed too. The project may
+    /// be in an inconsistent state.
+    RollbackFailed(String),
+    /// Any other failure, such as being unable to create backups.
+    Other(String),
+}
+
+impl Error {
+    /// Process exit code for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Other(_) => 1,
+            Error::Validation(_) => 2,
+            Error::Detection(_) => 3,
+            Error::RolledBack(_) => 4,
+            Error::RollbackFailed(_) => 5,
+        }
+    }
+
+    /// Short machine-readable name of the failure class.
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            Error::Other(_) => "error",
+            Error::Validation(_) => "validation_failed",
+            Error::Detection(_) => "detection_failed",
+            Error::RolledBack(_) => "rolled_back",
+            Error::RollbackFailed(_) => "rollback_failed",
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Validation(message) | Error::Detection(message) | Error::Other(message) => {
+                write!(f, "{}", message)
+            }
+            Error::RolledBack(message) => write!(
+                f,
+                "{}; all changes were reverted and the project is in its original state",
+                message
+            ),
+            Error::RollbackFailed(message) => write!(
+                f,
+                "failed to revert changes: {}; the project may be in an inconsistent state",
+                message
+            ),
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
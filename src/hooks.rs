@@ -0,0 +1,131 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    rc::Rc,
+};
+
+use serde::Deserialize;
+
+use crate::presentation::{log, reporter::Reporter};
+
+/// The `[hooks]` table of the config file. Each hook is a list of shell
+/// commands run in order from the project root.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Commands run before any change is applied. A failing command aborts
+    /// the workflow.
+    pub pre_apply: Vec<String>,
+    /// Commands run after all changes were applied successfully.
+    pub post_apply: Vec<String>,
+    /// Commands run after changes were reverted.
+    pub post_revert: Vec<String>,
+    /// Revert all changes if a `post_apply` command fails.
+    pub revert_on_post_apply_failure: bool,
+}
+
+/// A point in a workflow at which hook commands run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreApply,
+    PostApply,
+    PostRevert,
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hook::PreApply => write!(f, "pre_apply"),
+            Hook::PostApply => write!(f, "post_apply"),
+            Hook::PostRevert => write!(f, "post_revert"),
+        }
+    }
+}
+
+/// Runs the hook commands of a single workflow run, describing the run to
+/// each command through environment variables:
+///
+/// - `RENOM_WORKFLOW`: the name of the workflow, such as `rename_module`
+/// - `RENOM_OLD_NAME`, `RENOM_NEW_NAME`: the old and new name
+/// - `RENOM_PROJECT_ROOT`: the project root at the time the hook runs
+/// - `RENOM_HOOK`: the hook being run
+pub struct HookRunner {
+    pub hooks: HooksConfig,
+    pub workflow: String,
+    pub old_name: String,
+    pub new_name: String,
+    /// The root of the project before changes are applied.
+    pub project_root: PathBuf,
+    /// The root of the project after changes are applied, if it moves.
+    pub new_project_root: Option<PathBuf>,
+    /// Reporter receiving the commands run and their output.
+    pub reporter: Rc<dyn Reporter>,
+}
+
+impl HookRunner {
+    /// Run all commands of a hook, stopping at the first failure.
+    pub fn run(&self, hook: Hook) -> Result<(), String> {
+        let commands = match hook {
+            Hook::PreApply => &self.hooks.pre_apply,
+            Hook::PostApply => &self.hooks.post_apply,
+            Hook::PostRevert => &self.hooks.post_revert,
+        };
+        let project_root = match (hook, &self.new_project_root) {
+            (Hook::PostApply, Some(new_project_root)) => new_project_root,
+            _ => &self.project_root,
+        };
+        for command in commands {
+            log::step(&*self.reporter, hook, command);
+            self.run_command(hook, command, project_root)
+                .map_err(|err| format!("{} hook {:?} failed: {}", hook, command, err))?;
+        }
+        Ok(())
+    }
+
+    /// Run the `post_revert` commands, reporting rather than returning a
+    /// failure, since the changes have already been reverted.
+    pub fn run_post_revert(&self) {
+        if let Err(err) = self.run(Hook::PostRevert) {
+            log::error(&*self.reporter, err);
+        }
+    }
+
+    /// Run a single command, reporting its output through the reporter so
+    /// that it does not end up in between machine-readable output.
+    fn run_command(&self, hook: Hook, command: &str, project_root: &Path) -> Result<(), String> {
+        let output = shell_command(command)
+            .current_dir(project_root)
+            .env("RENOM_WORKFLOW", &self.workflow)
+            .env("RENOM_OLD_NAME", &self.old_name)
+            .env("RENOM_NEW_NAME", &self.new_name)
+            .env("RENOM_PROJECT_ROOT", project_root)
+            .env("RENOM_HOOK", hook.to_string())
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|err| err.to_string())?;
+        for stream in [&output.stdout, &output.stderr] {
+            for line in String::from_utf8_lossy(stream).lines() {
+                log::basic(&*self.reporter, line);
+            }
+        }
+        match output.status.success() {
+            true => Ok(()),
+            false => Err(output.status.to_string()),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
@@ -1,7 +1,20 @@
+pub mod artifacts;
+pub mod backup;
 pub mod changes;
 pub mod cli;
+pub mod config;
+pub mod cpp;
+pub mod csharp;
+pub mod descriptor;
+pub mod discovery;
 pub mod engine;
+pub mod error;
+pub mod hooks;
 pub mod presentation;
+pub mod redirects;
+pub mod rules;
+pub mod snapshot;
 pub mod unreal;
+pub mod validation;
 pub mod wizard;
 pub mod workflows;
@@ -3,11 +3,18 @@ use lmk::init_crash_reporter;
 use renom::{
     cli::{
         Cli,
-        Command::{RenameModule, RenamePlugin, RenameProject, RenameTarget, Wizard},
+        Command::{
+            AddModule, AddPlugin, AddTarget, Backup, Clean, MoveModule, Redirects, RemoveModule,
+            RenameModule, RenamePlugin, RenameProject, RenameTarget, Wizard,
+        },
     },
-    presentation::log,
+    error::Error,
+    presentation::{log, reporter::TerminalReporter},
     wizard::start_interactive_dialogue,
-    workflows::{rename_module, rename_plugin, rename_project, rename_target},
+    workflows::{
+        add_module, add_plugin, add_target, clean, manage_backups, manage_redirects, move_module,
+        remove_module, rename_module, rename_plugin, rename_project, rename_target,
+    },
 };
 
 fn main() {
@@ -15,24 +22,47 @@ fn main() {
 
     let cli = Cli::parse();
 
-    // Set verbose mode if the flag is present
-    log::set_verbose(cli.verbose);
+    let reporter = match cli.reporter() {
+        Ok(reporter) => reporter,
+        Err(e) => {
+            log::error(&TerminalReporter::new(false), e);
+            std::process::exit(1);
+        }
+    };
+
+    let backup = cli.backup_options();
 
     match cli.command {
         None => { /* noop, clap will handle top-level help and version */ }
         Some(command) => {
-            if let Err(e) = match command {
-                RenameProject(params) => rename_project(params.into_params(cli.verbose)),
-                RenamePlugin(params) => rename_plugin(params.into_params(cli.verbose)),
-                RenameTarget(params) => rename_target(params.into_params(cli.verbose)),
-                RenameModule(params) => rename_module(params.into_params(cli.verbose)),
-                Wizard => {
-                    start_interactive_dialogue();
-                    Ok(())
+            let result = match command {
+                RenameProject(params) => rename_project(params.into_params(backup), reporter.clone()),
+                RenamePlugin(params) => rename_plugin(params.into_params(backup), reporter.clone()),
+                RenameTarget(params) => rename_target(params.into_params(backup), reporter.clone()),
+                RenameModule(params) => rename_module(params.into_params(backup), reporter.clone()),
+                AddModule(params) => add_module(params.into_params(backup), reporter.clone()),
+                AddPlugin(params) => add_plugin(params.into_params(backup), reporter.clone()),
+                AddTarget(params) => add_target(params.into_params(backup), reporter.clone()),
+                RemoveModule(params) => remove_module(params.into_params(backup), reporter.clone()),
+                MoveModule(params) => move_module(params.into_params(backup), reporter.clone()),
+                Clean(params) => clean(params.into_params(backup), reporter.clone()),
+                Backup(params) => manage_backups(params.into_params(backup), reporter.clone()),
+                Redirects(params) => manage_redirects(params.into_params(backup), reporter.clone()),
+                Wizard => start_interactive_dialogue(backup, reporter.clone()),
+            };
+            let (outcome, exit_code) = match result {
+                Ok(()) => ("success", 0),
+                Err(e) => {
+                    // Workflows report their own error and failure message
+                    // before rolling back.
+                    if !matches!(e, Error::RolledBack(_)) {
+                        log::error(&*reporter, e.to_string());
+                    }
+                    (e.outcome(), e.exit_code())
                 }
-            } {
-                log::error(e);
-            }
+            };
+            log::summary(&*reporter, outcome, exit_code);
+            std::process::exit(exit_code);
         }
     };
 }
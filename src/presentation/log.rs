@@ -0,0 +1,135 @@
+//! Logging functions used throughout Renom. Every message is turned into an
+//! [`Event`] and handed to the reporter passed along, which decides where and
+//! how it is presented.
+
+use std::{cell::RefCell, fmt::Display};
+
+use super::reporter::{Event, Record, Reporter};
+use crate::changes::Change;
+
+thread_local! {
+    /// Name of the workflow currently running, attached to every record
+    static WORKFLOW: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Format in which output is emitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Colored human-readable text.
+    #[default]
+    Text,
+    /// Newline-delimited JSON events.
+    Json,
+}
+
+/// Set the name of the workflow attached to subsequent records.
+pub fn set_workflow<S: AsRef<str>>(name: S) {
+    WORKFLOW.with(|workflow| *workflow.borrow_mut() = Some(name.as_ref().to_owned()));
+}
+
+/// Toggles coloring based on environment.
+/// For instance, colors do not work for `cmd`on Windows.
+pub fn check_support_for_colors() {
+    let term = term::stdout().unwrap();
+    if !term.supports_color() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Report a header. Includes a preliminary newline.
+pub fn header<S: AsRef<str>>(reporter: &dyn Reporter, text: S) {
+    report(reporter, Event::Header(text.as_ref().to_owned()));
+}
+
+/// Report the text without any frills.
+pub fn basic<S: AsRef<str>>(reporter: &dyn Reporter, text: S) {
+    report(reporter, Event::Message(text.as_ref().to_owned()));
+}
+
+/// Report a step.
+pub fn step<A: Display, B: Display>(reporter: &dyn Reporter, process: A, text: B) {
+    report(
+        reporter,
+        Event::Step {
+            process: process.to_string(),
+            message: text.to_string(),
+            change: None,
+            paths: vec![],
+        },
+    );
+}
+
+/// Report a step that applies or reverts a change, along with the kind of
+/// change and the paths it affects.
+pub fn change<A: Display>(reporter: &dyn Reporter, process: A, change: &Change) {
+    report(
+        reporter,
+        Event::Step {
+            process: process.to_string(),
+            message: change.to_string(),
+            change: Some(change.kind()),
+            paths: change.paths().into_iter().map(ToOwned::to_owned).collect(),
+        },
+    );
+}
+
+/// Report a success message.
+pub fn success<S: AsRef<str>>(reporter: &dyn Reporter, text: S) {
+    report(reporter, Event::Success(text.as_ref().to_owned()));
+}
+
+/// Report an error.
+pub fn error<S: AsRef<str>>(reporter: &dyn Reporter, text: S) {
+    report(reporter, Event::Error(text.as_ref().to_owned()));
+}
+
+/// Report a verbose message (only when verbose mode is enabled).
+pub fn verbose<S: AsRef<str>>(reporter: &dyn Reporter, text: S) {
+    if reporter.is_verbose() {
+        report(
+            reporter,
+            Event::Verbose {
+                category: None,
+                message: text.as_ref().to_owned(),
+            },
+        );
+    }
+}
+
+/// Report a verbose message with a category (only when verbose mode is enabled).
+pub fn verbose_with_category<A: Display, B: Display>(
+    reporter: &dyn Reporter,
+    category: A,
+    text: B,
+) {
+    if reporter.is_verbose() {
+        report(
+            reporter,
+            Event::Verbose {
+                category: Some(category.to_string()),
+                message: text.to_string(),
+            },
+        );
+    }
+}
+
+/// Report the final outcome of the run.
+pub fn summary<S: AsRef<str>>(reporter: &dyn Reporter, outcome: S, exit_code: i32) {
+    report(
+        reporter,
+        Event::Summary {
+            outcome: outcome.as_ref().to_owned(),
+            exit_code,
+        },
+    );
+}
+
+fn report(reporter: &dyn Reporter, event: Event) {
+    let record = Record {
+        timestamp: chrono::Utc::now(),
+        workflow: WORKFLOW.with(|workflow| workflow.borrow().clone()),
+        event,
+    };
+    reporter.report(&record);
+}
@@ -0,0 +1,325 @@
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::OnceLock,
+};
+
+use chrono::{DateTime, Utc};
+use colored::*;
+use regex::Regex;
+use serde_json::json;
+
+/// Something worth reporting to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A header introducing a group of output.
+    Header(String),
+    /// Text without any frills.
+    Message(String),
+    /// A step in a process, such as applying a change.
+    Step {
+        process: String,
+        message: String,
+        /// Kind of change applied or reverted by the step, if any.
+        change: Option<&'static str>,
+        /// Paths affected by the change, if any.
+        paths: Vec<PathBuf>,
+    },
+    /// A success message.
+    Success(String),
+    /// An error message.
+    Error(String),
+    /// A detailed message, only relevant in verbose mode.
+    Verbose {
+        category: Option<String>,
+        message: String,
+    },
+    /// The final outcome of a run.
+    Summary { outcome: String, exit_code: i32 },
+}
+
+/// An event along with when and where it was reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub timestamp: DateTime<Utc>,
+    /// Name of the workflow running when the event was reported.
+    pub workflow: Option<String>,
+    pub event: Event,
+}
+
+/// A destination for the output of workflows and the engine.
+pub trait Reporter {
+    /// Report a single record.
+    fn report(&self, record: &Record);
+
+    /// Whether verbose events should be reported at all.
+    fn is_verbose(&self) -> bool;
+}
+
+/// Reports colored human-readable text to the terminal. Errors are printed
+/// to stderr, everything else to stdout.
+pub struct TerminalReporter {
+    verbose: bool,
+}
+
+impl TerminalReporter {
+    pub fn new(verbose: bool) -> Self {
+        Self { verbose }
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn report(&self, record: &Record) {
+        match &record.event {
+            Event::Header(text) => println!(
+                "\n{open_brace} {text} {close_brace}",
+                open_brace = "[".green(),
+                text = text,
+                close_brace = "]".green()
+            ),
+            Event::Message(text) => println!("{}", text),
+            Event::Step {
+                process, message, ..
+            } => println!(
+                "{open_paren} {process} {close_paren} {text}",
+                open_paren = "(".purple(),
+                process = process,
+                close_paren = ")".purple(),
+                text = message
+            ),
+            Event::Success(text) => println!("\n\t[ Success ]\n\t{}\n", text.bright_green()),
+            Event::Error(text) => eprintln!("\n\t[ Error ]\n\t{}\n", text.red()),
+            Event::Verbose {
+                category: None,
+                message,
+            } => println!(
+                "{open_bracket} {verbose} {close_bracket} {text}",
+                open_bracket = "[".bright_black(),
+                verbose = "verbose".bright_black(),
+                close_bracket = "]".bright_black(),
+                text = message.bright_black()
+            ),
+            Event::Verbose {
+                category: Some(category),
+                message,
+            } => println!(
+                "{open_bracket} {verbose} {close_bracket} {open_paren}{category}{close_paren} {text}",
+                open_bracket = "[".bright_black(),
+                verbose = "verbose".bright_black(),
+                close_bracket = "]".bright_black(),
+                open_paren = "(".bright_black(),
+                category = category.bright_black(),
+                close_paren = ")".bright_black(),
+                text = message.bright_black()
+            ),
+            // Text output already ends with a success or error message.
+            Event::Summary { .. } => {}
+        }
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+}
+
+/// Reports newline-delimited JSON events to stdout.
+pub struct JsonReporter {
+    verbose: bool,
+}
+
+impl JsonReporter {
+    pub fn new(verbose: bool) -> Self {
+        Self { verbose }
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn report(&self, record: &Record) {
+        println!("{}", to_json(record));
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+}
+
+/// Discards everything.
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&self, _record: &Record) {}
+
+    fn is_verbose(&self) -> bool {
+        false
+    }
+}
+
+/// Keeps every record in memory, for instance to inspect the output of a
+/// workflow when embedding Renom in another tool.
+#[derive(Default)]
+pub struct CollectingReporter {
+    verbose: bool,
+    records: RefCell<Vec<Record>>,
+}
+
+impl CollectingReporter {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            records: RefCell::new(vec![]),
+        }
+    }
+
+    /// All records reported so far.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.borrow().clone()
+    }
+
+    /// All events reported so far.
+    pub fn events(&self) -> Vec<Event> {
+        self.records
+            .borrow()
+            .iter()
+            .map(|record| record.event.clone())
+            .collect()
+    }
+}
+
+impl Reporter for CollectingReporter {
+    fn report(&self, record: &Record) {
+        self.records.borrow_mut().push(record.clone());
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+}
+
+/// Writes plain timestamped text to a log file, including verbose events.
+pub struct FileReporter {
+    file: RefCell<File>,
+}
+
+impl FileReporter {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            file: RefCell::new(File::create(path)?),
+        })
+    }
+}
+
+impl Reporter for FileReporter {
+    fn report(&self, record: &Record) {
+        let text = match &record.event {
+            Event::Header(text) => format!("[header] {}", text),
+            Event::Message(text) => format!("[message] {}", text),
+            Event::Step {
+                process, message, ..
+            } => format!("[step] ({}) {}", process, message),
+            Event::Success(text) => format!("[success] {}", text),
+            Event::Error(text) => format!("[error] {}", text),
+            Event::Verbose {
+                category: None,
+                message,
+            } => format!("[verbose] {}", message),
+            Event::Verbose {
+                category: Some(category),
+                message,
+            } => format!("[verbose] ({}) {}", category, message),
+            Event::Summary { outcome, exit_code } => {
+                format!("[summary] {} (exit code {})", outcome, exit_code)
+            }
+        };
+        // A log file that cannot be written to must not abort the run.
+        let _ = writeln!(
+            self.file.borrow_mut(),
+            "{} {}",
+            record.timestamp.to_rfc3339(),
+            strip_colors(&text)
+        );
+    }
+
+    fn is_verbose(&self) -> bool {
+        true
+    }
+}
+
+/// Reports every record to several reporters. Verbose events only reach the
+/// reporters that want them.
+pub struct TeeReporter {
+    reporters: Vec<Rc<dyn Reporter>>,
+}
+
+impl TeeReporter {
+    pub fn new(reporters: Vec<Rc<dyn Reporter>>) -> Self {
+        Self { reporters }
+    }
+}
+
+impl Reporter for TeeReporter {
+    fn report(&self, record: &Record) {
+        for reporter in &self.reporters {
+            if matches!(record.event, Event::Verbose { .. }) && !reporter.is_verbose() {
+                continue;
+            }
+            reporter.report(record);
+        }
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.reporters.iter().any(|reporter| reporter.is_verbose())
+    }
+}
+
+/// Serialize a record as a single-line JSON object.
+pub fn to_json(record: &Record) -> String {
+    let mut object = json!({
+        "timestamp": record.timestamp.to_rfc3339(),
+        "workflow": record.workflow,
+    });
+    let fields = match &record.event {
+        Event::Header(text) => json!({ "type": "header", "message": strip_colors(text) }),
+        Event::Message(text) => json!({ "type": "message", "message": strip_colors(text) }),
+        Event::Step {
+            process,
+            message,
+            change,
+            paths,
+        } => match change {
+            Some(change) => json!({
+                "type": "step",
+                "process": process,
+                "message": strip_colors(message),
+                "change": change,
+                "paths": paths,
+            }),
+            None => json!({ "type": "step", "process": process, "message": strip_colors(message) }),
+        },
+        Event::Success(text) => json!({ "type": "success", "message": strip_colors(text) }),
+        Event::Error(text) => json!({ "type": "error", "message": strip_colors(text) }),
+        Event::Verbose { category, message } => json!({
+            "type": "verbose",
+            "category": category,
+            "message": strip_colors(message),
+        }),
+        Event::Summary { outcome, exit_code } => {
+            json!({ "type": "summary", "outcome": outcome, "exit_code": exit_code })
+        }
+    };
+    if let (Some(object), Some(fields)) = (object.as_object_mut(), fields.as_object()) {
+        object.extend(fields.clone());
+    }
+    object.to_string()
+}
+
+/// Remove ANSI color codes, which `Display` implementations of changes add.
+fn strip_colors(text: &str) -> String {
+    static COLOR_CODE: OnceLock<Regex> = OnceLock::new();
+    COLOR_CODE
+        .get_or_init(|| Regex::new("\x1b\\[[0-9;]*m").expect("regex should be valid"))
+        .replace_all(text, "")
+        .into_owned()
+}
@@ -0,0 +1,346 @@
+use std::collections::{HashMap, HashSet};
+
+use ini::{ini::Properties, Ini};
+
+/// Section of the engine config file holding core redirects.
+pub const CORE_REDIRECTS_SECTION: &str = "CoreRedirects";
+
+/// Section of the engine config file holding legacy redirects.
+pub const LEGACY_REDIRECTS_SECTION: &str = "/Script/Engine.Engine";
+
+/// Keys of the core redirects section, one per kind of redirected object.
+pub const CORE_REDIRECT_KEYS: &[&str] = &[
+    "ObjectRedirects",
+    "ClassRedirects",
+    "StructRedirects",
+    "EnumRedirects",
+    "FunctionRedirects",
+    "PropertyRedirects",
+    "PackageRedirects",
+    "AssetRedirects",
+];
+
+/// Keys of the legacy redirects section.
+pub const LEGACY_REDIRECT_KEYS: &[&str] = &["ActiveGameNameRedirects", "ActiveClassRedirects"];
+
+/// A field of a redirect, such as `OldName="/Script/Old"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+    pub quoted: bool,
+}
+
+/// A redirect entry of the engine config file, such as
+/// `+PackageRedirects=(OldName="/Script/Old",NewName="/Script/New")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    pub fields: Vec<Field>,
+    /// Separator between fields, kept so that rewritten entries look like
+    /// the original ones.
+    pub separator: String,
+}
+
+impl Redirect {
+    /// Parse the value of a redirect entry. Returns `None` if the value is not
+    /// a parenthesized list of fields.
+    pub fn parse(value: &str) -> Option<Redirect> {
+        let inner = value.trim().strip_prefix('(')?.strip_suffix(')')?;
+        let separator = match inner.contains(", ") {
+            true => ", ",
+            false => ",",
+        };
+        let fields = split_fields(inner)
+            .into_iter()
+            .map(|field| {
+                let (name, value) = field.split_once('=')?;
+                let value = value.trim();
+                let unquoted = value.strip_prefix('"').and_then(|value| value.strip_suffix('"'));
+                Some(Field {
+                    name: name.trim().to_owned(),
+                    value: unquoted.unwrap_or(value).to_owned(),
+                    quoted: unquoted.is_some(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Redirect {
+            fields,
+            separator: separator.to_owned(),
+        })
+    }
+
+    /// The value of a field.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+    }
+
+    /// Set the value of an existing field.
+    pub fn set(&mut self, name: &str, value: &str) {
+        if let Some(field) = self.fields.iter_mut().find(|field| field.name == name) {
+            field.value = value.to_owned();
+        }
+    }
+
+    /// Fields other than the ones naming the old and new name, which must be
+    /// equal for redirects to form a chain.
+    fn options(&self, kind: &RedirectKind) -> Vec<Field> {
+        self.fields
+            .iter()
+            .filter(|field| field.name != kind.old_field && field.name != kind.new_field)
+            .cloned()
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Redirect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| match field.quoted {
+                true => format!(r#"{}="{}""#, field.name, field.value),
+                false => format!("{}={}", field.name, field.value),
+            })
+            .collect();
+        write!(f, "({})", fields.join(&self.separator))
+    }
+}
+
+/// Where a kind of redirect is declared and which of its fields hold the old
+/// and new name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectKind {
+    pub section: &'static str,
+    /// Key of the entries, without array prefix.
+    pub key: String,
+    pub old_field: &'static str,
+    pub new_field: &'static str,
+}
+
+impl RedirectKind {
+    /// The kind of redirect declared by an entry of a section, if any.
+    pub fn of(section: &str, key: &str) -> Option<RedirectKind> {
+        let key = key.trim_start_matches(['+', '-', '.', '!']);
+        let (section, old_field, new_field) = match (section, key) {
+            (CORE_REDIRECTS_SECTION, key) if key.ends_with("Redirects") => {
+                (CORE_REDIRECTS_SECTION, "OldName", "NewName")
+            }
+            (LEGACY_REDIRECTS_SECTION, "ActiveGameNameRedirects") => {
+                (LEGACY_REDIRECTS_SECTION, "OldGameName", "NewGameName")
+            }
+            (LEGACY_REDIRECTS_SECTION, "ActiveClassRedirects") => {
+                (LEGACY_REDIRECTS_SECTION, "OldClassName", "NewClassName")
+            }
+            _ => return None,
+        };
+        Some(RedirectKind {
+            section,
+            key: key.to_owned(),
+            old_field,
+            new_field,
+        })
+    }
+
+    /// All kinds of redirects, core ones followed by legacy ones.
+    pub fn all() -> Vec<RedirectKind> {
+        let core = CORE_REDIRECT_KEYS.iter().map(|key| (CORE_REDIRECTS_SECTION, *key));
+        let legacy = LEGACY_REDIRECT_KEYS.iter().map(|key| (LEGACY_REDIRECTS_SECTION, *key));
+        core.chain(legacy)
+            .filter_map(|(section, key)| RedirectKind::of(section, key))
+            .collect()
+    }
+}
+
+/// The `[CoreRedirects]` key and value equivalent to a legacy redirect entry
+/// under `[/Script/Engine.Engine]`. Returns `None` for entries that are not
+/// legacy redirects or that have fields without a core equivalent.
+pub fn to_core_redirect(key: &str, value: &str) -> Option<(String, String)> {
+    let kind = RedirectKind::of(LEGACY_REDIRECTS_SECTION, key)?;
+    let redirect = Redirect::parse(value)?;
+    if redirect.fields.len() != 2 {
+        return None;
+    }
+    let old = redirect.get(kind.old_field)?;
+    let new = redirect.get(kind.new_field)?;
+    let (core_key, old, new) = match kind.key.as_str() {
+        // Game names may omit the script package prefix.
+        "ActiveGameNameRedirects" => ("PackageRedirects", script_package(old), script_package(new)),
+        _ => ("ClassRedirects", old.to_owned(), new.to_owned()),
+    };
+    let prefix = key.strip_suffix(kind.key.as_str()).unwrap_or_default();
+    Some((
+        format!("{}{}", prefix, core_key),
+        format!(r#"(OldName="{}",NewName="{}")"#, old, new),
+    ))
+}
+
+fn script_package(name: &str) -> String {
+    match name.starts_with('/') {
+        true => name.to_owned(),
+        false => format!("/Script/{}", name),
+    }
+}
+
+/// Outcome of compacting the redirects of a config file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Compaction {
+    /// Number of redirects pointed at the end of their chain.
+    pub collapsed: usize,
+    /// Number of self-redirects and duplicates removed.
+    pub removed: usize,
+    /// Redirect cycles, each listed as the names along the cycle. Redirects
+    /// that are part of a cycle are left unchanged.
+    pub cycles: Vec<Vec<String>>,
+}
+
+/// Compact the redirects of an engine config file: chains `A -> B -> C` are
+/// collapsed into `A -> C` and `B -> C`, and self-redirects and duplicates
+/// are removed. Only redirects of the same kind and with the same options,
+/// such as `MatchSubstring`, form chains. Entries that remove or clear
+/// redirects inherited from other config files are left untouched.
+pub fn compact(ini: &mut Ini) -> Compaction {
+    let mut compaction = Compaction::default();
+    for section in [CORE_REDIRECTS_SECTION, LEGACY_REDIRECTS_SECTION] {
+        for properties in ini.section_all_mut(Some(section)) {
+            compact_section(section, properties, &mut compaction);
+        }
+    }
+    compaction
+}
+
+fn compact_section(section: &str, properties: &mut Properties, compaction: &mut Compaction) {
+    let entries: Vec<(String, String)> = properties
+        .iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+    let redirects: Vec<Option<(RedirectKind, Redirect)>> = entries
+        .iter()
+        .map(|(key, value)| {
+            if key.starts_with(['-', '!']) {
+                return None;
+            }
+            let kind = RedirectKind::of(section, key)?;
+            let redirect = Redirect::parse(value)?;
+            redirect.get(kind.old_field)?;
+            redirect.get(kind.new_field)?;
+            Some((kind, redirect))
+        })
+        .collect();
+
+    // Targets of each name, per kind and options. Names with several targets
+    // are ambiguous and do not continue a chain.
+    let mut targets: HashMap<(String, Vec<Field>, String), HashSet<String>> = HashMap::new();
+    for (kind, redirect) in redirects.iter().flatten() {
+        let old = redirect.get(kind.old_field).unwrap();
+        let new = redirect.get(kind.new_field).unwrap();
+        if old != new {
+            targets
+                .entry((kind.key.clone(), redirect.options(kind), old.to_owned()))
+                .or_default()
+                .insert(new.to_owned());
+        }
+    }
+
+    let mut kept = Properties::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for ((key, value), redirect) in entries.into_iter().zip(redirects) {
+        let Some((kind, mut redirect)) = redirect else {
+            kept.append(key, value);
+            continue;
+        };
+        let old = redirect.get(kind.old_field).unwrap().to_owned();
+        let new = redirect.get(kind.new_field).unwrap().to_owned();
+        let options = redirect.options(&kind);
+        let next = |name: &str| match targets.get(&(kind.key.clone(), options.clone(), name.to_owned())) {
+            Some(next) if next.len() == 1 => next.iter().next().cloned(),
+            _ => None,
+        };
+
+        let mut chain = vec![old];
+        let mut current = Some(new);
+        while let Some(name) = current.take() {
+            match chain.iter().position(|visited| *visited == name) {
+                // A name redirecting to itself ends the chain.
+                Some(start) if start == chain.len() - 1 => {}
+                Some(start) => {
+                    let mut cycle = chain[start..].to_vec();
+                    cycle.push(name);
+                    record_cycle(compaction, cycle);
+                    chain.clear();
+                }
+                None => {
+                    current = next(&name);
+                    chain.push(name);
+                }
+            }
+        }
+
+        let value = match chain.len() {
+            // Part of a cycle
+            0 => value,
+            // Self-redirect
+            1 => {
+                compaction.removed += 1;
+                continue;
+            }
+            2 => value,
+            _ => {
+                redirect.set(kind.new_field, chain.last().unwrap());
+                compaction.collapsed += 1;
+                redirect.to_string()
+            }
+        };
+        if !seen.insert((kind.key.clone(), normalize(&value))) {
+            compaction.removed += 1;
+            continue;
+        }
+        kept.append(key, value);
+    }
+    *properties = kept;
+}
+
+/// Record a cycle once, however many of its redirects run into it.
+fn record_cycle(compaction: &mut Compaction, cycle: Vec<String>) {
+    let mut names: Vec<&String> = cycle.iter().skip(1).collect();
+    names.sort();
+    let known = compaction.cycles.iter().any(|known| {
+        let mut known: Vec<&String> = known.iter().skip(1).collect();
+        known.sort();
+        known == names
+    });
+    if !known {
+        compaction.cycles.push(cycle);
+    }
+}
+
+/// Redirect values that only differ in whitespace are duplicates.
+fn normalize(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Split the fields of a redirect on commas outside of quotes and parentheses.
+fn split_fields(inner: &str) -> Vec<&str> {
+    let mut fields = vec![];
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut start = 0;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                fields.push(&inner[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    if !inner[start..].trim().is_empty() {
+        fields.push(&inner[start..]);
+    }
+    fields
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use regex::{escape, Regex};
+use serde::Deserialize;
+
+use crate::changes::{Change, ReplaceInFile};
+
+/// A user-defined replacement applied to additional files during a workflow,
+/// declared in the project config file. The pattern and replacement are
+/// templates in which the following placeholders are expanded:
+///
+/// - `{old}`, `{new}`: the old and new name as is
+/// - `{old_lower}`, `{new_lower}`: the names in lowercase
+/// - `{OLD_UPPER}`, `{NEW_UPPER}`: the names in uppercase
+///
+/// The pattern is a regular expression, in which the expanded names are
+/// matched literally, and the replacement may refer to its capture groups.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ReplacementRule {
+    /// Glob patterns, relative to the project root, of the files to edit.
+    pub files: Vec<String>,
+    /// Template of the pattern to search for.
+    pub pattern: String,
+    /// Template of the replacement.
+    pub replacement: String,
+}
+
+impl ReplacementRule {
+    /// Check that the pattern is a valid regular expression once expanded.
+    pub fn validate(&self) -> Result<(), String> {
+        Regex::new(&self.expand_pattern("Old", "New"))
+            .map(|_| ())
+            .map_err(|err| format!("invalid rule pattern {}: {}", self.pattern, err))
+    }
+
+    /// The pattern with placeholders expanded to escaped names.
+    pub fn expand_pattern(&self, old_name: &str, new_name: &str) -> String {
+        expand_template(&self.pattern, &escape(old_name), &escape(new_name))
+    }
+
+    /// The replacement with placeholders expanded to names.
+    pub fn expand_replacement(&self, old_name: &str, new_name: &str) -> String {
+        expand_template(&self.replacement, old_name, new_name)
+    }
+
+    /// Changes that apply the rule to each of the given files.
+    pub fn changes(&self, files: &[PathBuf], old_name: &str, new_name: &str) -> Vec<Change> {
+        let pattern = self.expand_pattern(old_name, new_name);
+        let replacement = self.expand_replacement(old_name, new_name);
+        files
+            .iter()
+            .map(|file| {
+                Change::ReplaceInFile(ReplaceInFile::new(file, pattern.clone(), replacement.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Expand the name placeholders of a template.
+pub fn expand_template(template: &str, old_name: &str, new_name: &str) -> String {
+    template
+        .replace("{old}", old_name)
+        .replace("{new}", new_name)
+        .replace("{old_lower}", &old_name.to_lowercase())
+        .replace("{new_lower}", &new_name.to_lowercase())
+        .replace("{OLD_UPPER}", &old_name.to_uppercase())
+        .replace("{NEW_UPPER}", &new_name.to_uppercase())
+}
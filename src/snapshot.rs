@@ -0,0 +1,216 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backup::{copy_path, Manifest},
+    changes::Change,
+    presentation::{log, reporter::Reporter},
+};
+
+/// Name of the directory within a backup run that holds its snapshot.
+pub const SNAPSHOT_DIR_NAME: &str = "snapshot";
+
+/// Subtrees of a project captured in full before a run, so that the project
+/// can be restored even if reverting individual changes fails.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Captured files and directories, relative to the project root.
+    pub paths: Vec<PathBuf>,
+    /// Paths that the run creates by renaming, relative to the project root.
+    /// They are removed when the snapshot is restored.
+    pub created: Vec<PathBuf>,
+    /// New name of the project root directory if the run renames it. The
+    /// directory is renamed back when the snapshot is restored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_root: Option<PathBuf>,
+}
+
+/// Capture `subtrees` of a project, along with every path touched by
+/// `changeset`, into the backup run stored in `backup_dir`. Files are always
+/// copied, since hardlinks would share later edits made by other tools.
+pub fn create_snapshot(
+    project_root: &Path,
+    subtrees: &[PathBuf],
+    changeset: &[Change],
+    backup_dir: &Path,
+    reporter: &dyn Reporter,
+) -> Result<Snapshot, String> {
+    log::verbose(reporter, "Creating snapshot");
+    let mut paths: Vec<PathBuf> = vec![];
+    let mut created: Vec<PathBuf> = vec![];
+    let mut renamed_root: Option<PathBuf> = None;
+    let mut renames: Vec<(PathBuf, PathBuf)> = vec![];
+    for change in changeset {
+        match change {
+            Change::RenameFile(params)
+                if original_path(&params.from, &renames) == project_root =>
+            {
+                renamed_root = params.to.file_name().map(PathBuf::from);
+                renames.push((params.from.clone(), params.to.clone()));
+            }
+            Change::RenameFile(params) => {
+                paths.push(original_path(&params.from, &renames));
+                created.push(params.to.clone());
+                renames.push((params.from.clone(), params.to.clone()));
+            }
+            Change::ReplaceInFile(params) => paths.push(original_path(&params.path, &renames)),
+            Change::ReplaceCsReferences(params) => paths.push(original_path(&params.path, &renames)),
+            Change::SetIniEntry(params) => paths.push(original_path(&params.path, &renames)),
+            Change::AppendIniEntry(params) => paths.push(original_path(&params.path, &renames)),
+            Change::RemoveIniEntry(params) => paths.push(original_path(&params.path, &renames)),
+            Change::ReplaceIniValue(params) => paths.push(original_path(&params.path, &renames)),
+            Change::CompactRedirects(params) => paths.push(original_path(&params.path, &renames)),
+            Change::AddDescriptorEntry(params) => paths.push(original_path(&params.path, &renames)),
+            Change::RemoveDescriptorEntry(params) => paths.push(original_path(&params.path, &renames)),
+            Change::ReplaceDescriptorListValues(params) => {
+                paths.push(original_path(&params.path, &renames))
+            }
+            Change::RemoveCsListEntries(params) => paths.push(original_path(&params.path, &renames)),
+            Change::RenameImplementMacro(params) => paths.push(original_path(&params.path, &renames)),
+            Change::MoveToBackup(params) => paths.push(original_path(&params.path, &renames)),
+            Change::DeleteFile(params) => paths.push(original_path(&params.path, &renames)),
+            Change::CreateFile(params) => created.push(params.path.clone()),
+            Change::CopyFile(params) => created.push(params.to.clone()),
+            Change::CopyDir(params) => created.push(params.to.clone()),
+            Change::CreateDir(params) => created.push(params.path.clone()),
+        }
+    }
+    paths.extend(subtrees.iter().cloned());
+
+    // Paths created within the renamed project root are recorded relative to
+    // the original project root, since the root is renamed back first when
+    // the snapshot is restored.
+    if let Some(new_root) = renamed_root.as_ref().map(|name| project_root.with_file_name(name)) {
+        for path in &mut created {
+            if let Ok(rest) = path.strip_prefix(&new_root) {
+                *path = project_root.join(rest);
+            }
+        }
+    }
+    let paths = minimal_paths(relative_paths(project_root, &paths));
+    let created = relative_paths(project_root, &created);
+
+    let snapshot_dir = backup_dir.join(SNAPSHOT_DIR_NAME);
+    for path in &paths {
+        log::verbose_with_category(reporter, "snapshot", format!("Capturing {:?}", path));
+        let source = project_root.join(path);
+        if source.exists() {
+            copy_path(&source, &snapshot_dir.join(path)).map_err(|err| err.to_string())?;
+        }
+    }
+
+    let snapshot = Snapshot {
+        paths,
+        created,
+        renamed_root,
+    };
+    let mut manifest = Manifest::load(backup_dir).map_err(|err| err.to_string())?;
+    manifest.snapshot = Some(snapshot.clone());
+    manifest.save(backup_dir).map_err(|err| err.to_string())?;
+    log::verbose(reporter, "Snapshot created successfully");
+    Ok(snapshot)
+}
+
+/// Restore the snapshot of the backup run stored in `backup_dir` into the
+/// project, renaming the project root back if the run renamed it, removing
+/// paths created by the run and replacing every captured subtree with its
+/// captured state. `backup_dir` is where the run currently is, which is
+/// within the renamed project root if the run is stored inside the project.
+pub fn restore_snapshot(
+    project_root: &Path,
+    backup_dir: &Path,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let manifest = Manifest::load(backup_dir).map_err(|err| err.to_string())?;
+    let Manifest { id, snapshot, .. } = manifest;
+    let snapshot =
+        snapshot.ok_or_else(|| format!("backup run {} does not have a snapshot", id))?;
+    let mut backup_dir = backup_dir.to_owned();
+
+    let new_root = snapshot
+        .renamed_root
+        .as_ref()
+        .map(|name| project_root.with_file_name(name));
+    if let Some(new_root) = new_root {
+        if !project_root.exists() && new_root.is_dir() {
+            log::verbose_with_category(
+                reporter,
+                "snapshot",
+                format!("Renaming {:?} back", new_root),
+            );
+            fs::rename(&new_root, project_root).map_err(|err| err.to_string())?;
+            if let Ok(rest) = backup_dir.strip_prefix(&new_root) {
+                backup_dir = project_root.join(rest);
+            }
+        }
+    }
+    let snapshot_dir = backup_dir.join(SNAPSHOT_DIR_NAME);
+
+    for path in &snapshot.created {
+        log::verbose_with_category(reporter, "snapshot", format!("Removing {:?}", path));
+        remove(&project_root.join(path)).map_err(|err| err.to_string())?;
+    }
+
+    for path in &snapshot.paths {
+        log::verbose_with_category(reporter, "snapshot", format!("Restoring {:?}", path));
+        let target = project_root.join(path);
+        remove(&target).map_err(|err| err.to_string())?;
+        let source = snapshot_dir.join(path);
+        if source.exists() {
+            copy_path(&source, &target).map_err(|err| err.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Map a path that may have been renamed by earlier changes back to its
+/// original location.
+fn original_path(path: &Path, renames: &[(PathBuf, PathBuf)]) -> PathBuf {
+    renames
+        .iter()
+        .rev()
+        .fold(path.to_owned(), |path, (from, to)| match path.strip_prefix(to) {
+            Ok(rest) if rest.as_os_str().is_empty() => from.clone(),
+            Ok(rest) => from.join(rest),
+            Err(_) => path,
+        })
+}
+
+/// Paths relative to the project root. Paths outside of the project root or
+/// equal to it are dropped.
+fn relative_paths(project_root: &Path, paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(project_root).ok())
+        .filter(|path| !path.as_os_str().is_empty())
+        .map(Path::to_owned)
+        .collect()
+}
+
+/// Remove duplicates and paths nested within other paths.
+fn minimal_paths(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.sort();
+    paths.dedup();
+    let mut minimal: Vec<PathBuf> = vec![];
+    for path in paths {
+        if !minimal.iter().any(|other| path.starts_with(other)) {
+            minimal.push(path);
+        }
+    }
+    minimal
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+/// Maximum path length supported by default on Windows (`MAX_PATH`),
+/// including the terminating null character.
+pub const MAX_PATH_LEN: usize = 260;
+
+/// Names of engine modules that a project, plugin, module or target
+/// must not shadow.
+const RESERVED_ENGINE_NAMES: &[&str] = &[
+    "AIModule",
+    "ApplicationCore",
+    "AssetRegistry",
+    "AssetTools",
+    "AudioMixer",
+    "BlueprintGraph",
+    "Chaos",
+    "CinematicCamera",
+    "Core",
+    "CoreUObject",
+    "DeveloperSettings",
+    "EditorFramework",
+    "EditorStyle",
+    "Engine",
+    "EnhancedInput",
+    "Foliage",
+    "GameplayAbilities",
+    "GameplayTags",
+    "GameplayTasks",
+    "GraphEditor",
+    "HTTP",
+    "InputCore",
+    "Json",
+    "JsonUtilities",
+    "Kismet",
+    "KismetCompiler",
+    "Landscape",
+    "Launch",
+    "LevelSequence",
+    "MediaAssets",
+    "MovieScene",
+    "NavigationSystem",
+    "NetCore",
+    "Networking",
+    "Niagara",
+    "OnlineSubsystem",
+    "OnlineSubsystemUtils",
+    "PhysicsCore",
+    "Projects",
+    "PropertyEditor",
+    "RenderCore",
+    "Renderer",
+    "RHI",
+    "Slate",
+    "SlateCore",
+    "Sockets",
+    "ToolMenus",
+    "UMG",
+    "UnrealEd",
+    "UnrealBuildTool",
+];
+
+/// Device names that cannot be used as file or folder names on Windows.
+const RESERVED_DEVICE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Keywords that are not valid C++ identifiers.
+const CPP_KEYWORDS: &[&str] = &[
+    "alignas", "alignof", "and", "and_eq", "asm", "auto", "bitand", "bitor", "bool", "break",
+    "case", "catch", "char", "char8_t", "char16_t", "char32_t", "class", "compl", "concept",
+    "const", "consteval", "constexpr", "constinit", "const_cast", "continue", "co_await",
+    "co_return", "co_yield", "decltype", "default", "delete", "do", "double", "dynamic_cast",
+    "else", "enum", "explicit", "export", "extern", "false", "float", "for", "friend", "goto",
+    "if", "inline", "int", "long", "mutable", "namespace", "new", "noexcept", "not", "not_eq",
+    "nullptr", "operator", "or", "or_eq", "private", "protected", "public", "register",
+    "reinterpret_cast", "requires", "return", "short", "signed", "sizeof", "static",
+    "static_assert", "static_cast", "struct", "switch", "template", "this", "thread_local",
+    "throw", "true", "try", "typedef", "typeid", "typename", "union", "unsigned", "using",
+    "virtual", "void", "volatile", "wchar_t", "while", "xor", "xor_eq",
+];
+
+/// The kind of entity that a name is being validated for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameKind {
+    Project,
+    Plugin,
+    Module,
+    Target,
+}
+
+impl NameKind {
+    /// The maximum length of a name of this kind. Project names are limited
+    /// to 20 characters by the editor; other names are only limited to keep
+    /// derived paths and macros manageable.
+    pub fn max_len(&self) -> usize {
+        match self {
+            NameKind::Project => 20,
+            NameKind::Plugin | NameKind::Module | NameKind::Target => 30,
+        }
+    }
+}
+
+/// A reason that a name was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NameError {
+    Empty,
+    TooLong { max_len: usize },
+    NonAscii,
+    LeadingDigit,
+    InvalidCharacter(char),
+    CppKeyword,
+    ReservedEngineName(String),
+    ReservedDeviceName(String),
+    PathTooLong { path: PathBuf, max_len: usize },
+}
+
+impl Display for NameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "name must not be empty"),
+            NameError::TooLong { max_len } => {
+                write!(f, "name must not be longer than {} characters", max_len)
+            }
+            NameError::NonAscii => write!(f, "name must only contain ASCII characters"),
+            NameError::LeadingDigit => write!(f, "name must not start with a digit"),
+            NameError::InvalidCharacter(char) => write!(
+                f,
+                "name must be comprised of alphanumeric characters and underscores only, found '{}'",
+                char
+            ),
+            NameError::CppKeyword => write!(f, "name must not be a C++ keyword"),
+            NameError::ReservedEngineName(name) => {
+                write!(f, "name must not conflict with engine module {}", name)
+            }
+            NameError::ReservedDeviceName(name) => {
+                write!(f, "name must not be the Windows reserved device name {}", name)
+            }
+            NameError::PathTooLong { path, max_len } => write!(
+                f,
+                "name would make path {} longer than {} characters",
+                path.to_str().unwrap_or("invalid Unicode path"),
+                max_len
+            ),
+        }
+    }
+}
+
+/// Project-specific adjustments to the validation rules, set in the
+/// `[validation]` table of the project config file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ValidationOverrides {
+    /// Maximum name length, replacing the default for every kind of name.
+    pub max_name_length: Option<usize>,
+    /// Maximum path length, replacing [`MAX_PATH_LEN`].
+    pub max_path_length: Option<usize>,
+    /// Allow names that shadow engine modules.
+    pub allow_reserved_names: bool,
+}
+
+/// Validate that a name is usable for an entity of the given kind, that is,
+/// a concise ASCII C++ identifier that does not shadow an engine module or a
+/// Windows reserved device name.
+pub fn validate_name(name: &str, kind: NameKind) -> Result<(), NameError> {
+    validate_name_with(name, kind, &ValidationOverrides::default())
+}
+
+/// Validate a name like [`validate_name`], with project-specific overrides.
+pub fn validate_name_with(
+    name: &str,
+    kind: NameKind,
+    overrides: &ValidationOverrides,
+) -> Result<(), NameError> {
+    if name.trim().is_empty() {
+        return Err(NameError::Empty);
+    }
+
+    let max_len = overrides.max_name_length.unwrap_or(kind.max_len());
+    if name.len() > max_len {
+        return Err(NameError::TooLong { max_len });
+    }
+
+    if !name.is_ascii() {
+        return Err(NameError::NonAscii);
+    }
+
+    if name.starts_with(|char: char| char.is_ascii_digit()) {
+        return Err(NameError::LeadingDigit);
+    }
+
+    if let Some(char) = name
+        .chars()
+        .find(|char| !char.is_ascii_alphanumeric() && *char != '_')
+    {
+        return Err(NameError::InvalidCharacter(char));
+    }
+
+    if CPP_KEYWORDS.contains(&name) {
+        return Err(NameError::CppKeyword);
+    }
+
+    if !overrides.allow_reserved_names {
+        if let Some(reserved) = RESERVED_ENGINE_NAMES
+            .iter()
+            .find(|reserved| reserved.eq_ignore_ascii_case(name))
+        {
+            return Err(NameError::ReservedEngineName(reserved.to_string()));
+        }
+    }
+
+    if let Some(reserved) = RESERVED_DEVICE_NAMES
+        .iter()
+        .find(|reserved| reserved.eq_ignore_ascii_case(name))
+    {
+        return Err(NameError::ReservedDeviceName(reserved.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Validate that renaming `old_name` to `new_name` within `subtree` does not
+/// produce any path longer than [`MAX_PATH_LEN`]. Path components that are
+/// named after the old name (such as `Old` or `Old.Build.cs`) are assumed to
+/// be renamed. The subtree itself is included if it is a file.
+pub fn validate_path_lengths(
+    subtree: &Path,
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), NameError> {
+    validate_path_lengths_with(subtree, old_name, new_name, &ValidationOverrides::default())
+}
+
+/// Validate path lengths like [`validate_path_lengths`], with
+/// project-specific overrides.
+pub fn validate_path_lengths_with(
+    subtree: &Path,
+    old_name: &str,
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), NameError> {
+    let max_path_len = overrides.max_path_length.unwrap_or(MAX_PATH_LEN);
+    let abs_subtree = fs::canonicalize(subtree).unwrap_or_else(|_| subtree.to_owned());
+    WalkDir::new(&abs_subtree)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| renamed_path(entry.path(), old_name, new_name))
+        .find(|path| path_len(path) >= max_path_len)
+        .map_or(Ok(()), |path| {
+            Err(NameError::PathTooLong {
+                path,
+                max_len: max_path_len.saturating_sub(1),
+            })
+        })
+}
+
+fn renamed_path(path: &Path, old_name: &str, new_name: &str) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => match name.to_str() {
+                Some(name) if name == old_name => new_name.to_owned().into(),
+                Some(name) if name.starts_with(&format!("{}.", old_name)) => {
+                    name.replacen(old_name, new_name, 1).into()
+                }
+                _ => name.to_owned(),
+            },
+            other => other.as_os_str().to_owned(),
+        })
+        .collect()
+}
+
+/// Length of a path as seen by tools limited to `MAX_PATH`. Canonical paths
+/// on Windows carry a `\\?\` prefix that is not part of that length.
+fn path_len(path: &Path) -> usize {
+    let Some(path) = path.to_str() else {
+        return 0;
+    };
+    match path.strip_prefix(r"\\?\UNC\") {
+        // `\\?\UNC\server\share` stands for `\\server\share`.
+        Some(share) => share.chars().count() + 2,
+        None => path.strip_prefix(r"\\?\").unwrap_or(path).chars().count(),
+    }
+}
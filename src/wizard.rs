@@ -1,43 +1,38 @@
+use std::rc::Rc;
+
 use inquire::{Confirm, Select};
 
 use crate::{
-    presentation::log,
+    backup::BackupOptions,
+    error::Error,
+    presentation::{log, reporter::Reporter},
     workflows::{
         rename_module_interactive, rename_plugin_interactive, rename_project_interactive,
         rename_target_interactive, Workflow,
     },
 };
 
-/// Takes a result and returns its inner
-/// value if it is ok. In the case of error,
-/// logs the error and returns from the function.
-macro_rules! ok_or_quit {
-    ( $e:expr ) => {
-        match $e {
-            Ok(t) => t,
-            Err(e) => {
-                log::error(e);
-                return;
-            }
-        }
-    };
-}
-
-pub fn start_interactive_dialogue() {
+/// Run workflows chosen by the user until they want to stop. Returns the
+/// error of the first workflow that fails.
+pub fn start_interactive_dialogue(
+    backup: BackupOptions,
+    reporter: Rc<dyn Reporter>,
+) -> Result<(), Error> {
     set_up_terminal();
-    log::header("Welcome to Renom");
+    log::header(&*reporter, "Welcome to Renom");
     loop {
-        match ok_or_quit!(request_workflow_selection_from_user()) {
-            Workflow::RenameProject => ok_or_quit!(rename_project_interactive()),
-            Workflow::RenamePlugin => ok_or_quit!(rename_plugin_interactive()),
-            Workflow::RenameTarget => ok_or_quit!(rename_target_interactive()),
-            Workflow::RenameModule => ok_or_quit!(rename_module_interactive()),
+        match request_workflow_selection_from_user()? {
+            Workflow::RenameProject => rename_project_interactive(backup.clone(), reporter.clone())?,
+            Workflow::RenamePlugin => rename_plugin_interactive(backup.clone(), reporter.clone())?,
+            Workflow::RenameTarget => rename_target_interactive(backup.clone(), reporter.clone())?,
+            Workflow::RenameModule => rename_module_interactive(backup.clone(), reporter.clone())?,
         };
         if !user_wants_to_start_new_workflow() {
             break;
         }
     }
-    log::basic("Thanks for using Renom.");
+    log::basic(&*reporter, "Thanks for using Renom.");
+    Ok(())
 }
 
 fn set_up_terminal() {
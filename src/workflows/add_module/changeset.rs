@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use indoc::formatdoc;
+use regex::escape;
+
+use crate::{
+    changes::{AddDescriptorEntry, Change, CreateFile, ReplaceInFile},
+    descriptor::module_entry,
+    presentation::{log, reporter::Reporter},
+};
+
+use super::{is_editor_module, Context};
+
+/// Generate a changeset to add a module to an Unreal Engine project.
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
+    let Context {
+        name,
+        module_root,
+        descriptor,
+        module_type,
+        loading_phase,
+        target_updates,
+        ..
+    } = context;
+
+    log::verbose(reporter, "Generating changeset");
+    let mut changeset = scaffold_module(module_root, name, module_type);
+    log::verbose_with_category(
+        reporter,
+        "changeset",
+        format!("Listing module in {:?}", descriptor),
+    );
+    changeset.push(Change::AddDescriptorEntry(AddDescriptorEntry::new(
+        descriptor,
+        "Modules",
+        name,
+        module_entry(name, module_type, loading_phase),
+    )));
+    log::verbose_with_category(reporter, "changeset", format!("Adding module to {} targets", target_updates.len()));
+    changeset.extend(target_updates.iter().map(|update| {
+        Change::ReplaceInFile(ReplaceInFile::new(
+            &update.path,
+            escape(&update.from),
+            update.to.replace('$', "$$"),
+        ))
+    }));
+    log::verbose(reporter, "Changeset generation completed");
+    changeset
+}
+
+/// Create the build file, module header and module source of a new module.
+pub fn scaffold_module(module_root: &Path, name: &str, module_type: &str) -> Vec<Change> {
+    vec![
+        create_build_file(module_root, name, is_editor_module(module_type)),
+        create_module_header(module_root, name),
+        create_module_source(module_root, name),
+    ]
+}
+
+/// Templates are written with four spaces and converted to the tabs that
+/// engine generated files use.
+fn tabs(template: String) -> String {
+    template.replace("    ", "\t")
+}
+
+fn create_build_file(module_root: &Path, name: &str, editor: bool) -> Change {
+    let private_dependencies = match editor {
+        true => r#" "UnrealEd" "#,
+        false => " ",
+    };
+    Change::CreateFile(CreateFile::new(
+        module_root.join(name).with_extension("Build.cs"),
+        tabs(formatdoc! {r#"
+            using UnrealBuildTool;
+
+            public class {name} : ModuleRules
+            {{
+                public {name}(ReadOnlyTargetRules Target) : base(Target)
+                {{
+                    PCHUsage = PCHUsageMode.UseExplicitOrSharedPCHs;
+
+                    PublicDependencyModuleNames.AddRange(new string[] {{ "Core", "CoreUObject", "Engine" }});
+
+                    PrivateDependencyModuleNames.AddRange(new string[] {{{private_dependencies}}});
+                }}
+            }}
+        "#}),
+    ))
+}
+
+fn create_module_header(module_root: &Path, name: &str) -> Change {
+    Change::CreateFile(CreateFile::new(
+        module_root.join("Public").join(name).with_extension("h"),
+        tabs(formatdoc! {r#"
+            #pragma once
+
+            #include "CoreMinimal.h"
+            #include "Modules/ModuleInterface.h"
+
+            class F{name}Module : public IModuleInterface
+            {{
+            public:
+                virtual void StartupModule() override;
+                virtual void ShutdownModule() override;
+            }};
+        "#}),
+    ))
+}
+
+fn create_module_source(module_root: &Path, name: &str) -> Change {
+    Change::CreateFile(CreateFile::new(
+        module_root.join("Private").join(name).with_extension("cpp"),
+        formatdoc! {r#"
+            #include "{name}.h"
+
+            #include "Modules/ModuleManager.h"
+
+            void F{name}Module::StartupModule()
+            {{
+            }}
+
+            void F{name}Module::ShutdownModule()
+            {{
+            }}
+
+            IMPLEMENT_MODULE(F{name}Module, {name})
+        "#},
+    ))
+}
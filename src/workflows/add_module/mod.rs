@@ -0,0 +1,218 @@
+mod changeset;
+
+use std::{fs, path::PathBuf, rc::Rc};
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    csharp,
+    descriptor::{LOADING_PHASES, MODULE_TYPES},
+    discovery::{detect_modules, detect_project_plugins, detect_project_targets, find_project_descriptor},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    validation::{validate_name_with, NameKind},
+};
+
+use super::run::{run_changeset, Run};
+use self::changeset::generate_changeset;
+pub(crate) use self::changeset::scaffold_module;
+
+/// Params needed to add a module to an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The name of the new module.
+    pub name: String,
+    /// The plugin to add the module to, if not the project itself.
+    pub plugin: Option<String>,
+    /// The type of the module, such as `Runtime` or `Editor`.
+    pub module_type: String,
+    /// The loading phase of the module, such as `Default`.
+    pub loading_phase: String,
+    /// Add the module to the `ExtraModuleNames` of the project targets.
+    pub add_to_targets: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to add a module to an Unreal Engine project.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The name of the new module.
+    pub name: String,
+    /// The root of the new module.
+    pub module_root: PathBuf,
+    /// The project or plugin descriptor to list the module in.
+    pub descriptor: PathBuf,
+    /// The type of the module.
+    pub module_type: String,
+    /// The loading phase of the module.
+    pub loading_phase: String,
+    /// Edits adding the module to the `ExtraModuleNames` of targets.
+    pub target_updates: Vec<TargetUpdate>,
+}
+
+/// An edit of a target file, replacing a statement with another.
+pub struct TargetUpdate {
+    pub path: PathBuf,
+    pub from: String,
+    pub to: String,
+}
+
+/// Add a module to an Unreal Engine project or one of its plugins, with a build
+/// file, a module header and source, and an entry in the descriptor.
+pub fn add_module(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("add_module");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let changeset = generate_changeset(&context, &*reporter);
+    let run = Run {
+        workflow: "add_module",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: String::new(),
+        new_name: context.name.clone(),
+        description: format!("add module {}", context.name),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Successfully added module {}. Regenerate project files before building.",
+            context.name
+        ),
+        failure_message: format!("Failed to add module {}.", context.name),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.add_module, changeset)
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating name is valid");
+    validate_name_with(&params.name, NameKind::Module, &config.validation)
+        .map_err(|err| err.to_string())?;
+    log::verbose_with_category(reporter, "validation", "Validating name is unique");
+    if detect_modules(&params.project_root).iter().any(|module| module.name == params.name) {
+        return Err("name must not conflict with another module".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Validating module type and loading phase");
+    if !MODULE_TYPES.contains(&params.module_type.as_str()) {
+        return Err(format!("module type must be one of {}", MODULE_TYPES.join(", ")));
+    }
+    if !LOADING_PHASES.contains(&params.loading_phase.as_str()) {
+        return Err(format!("loading phase must be one of {}", LOADING_PHASES.join(", ")));
+    }
+    if let Some(plugin) = &params.plugin {
+        log::verbose_with_category(
+            reporter,
+            "validation",
+            format!("Validating plugin '{}' exists", plugin),
+        );
+        if !detect_project_plugins(&params.project_root).iter().any(|other| other.name == *plugin) {
+            return Err("plugin must be part of project".into());
+        }
+        if params.add_to_targets {
+            return Err("plugin modules are loaded by their plugin and cannot be added to targets".into());
+        }
+    }
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+/// Whether modules of a type are only built for editor targets.
+fn is_editor_module(module_type: &str) -> bool {
+    module_type.starts_with("Editor") || module_type == "UncookedOnly"
+}
+
+fn gather_target_updates(params: &Params, reporter: &dyn Reporter) -> Vec<TargetUpdate> {
+    detect_project_targets(&params.project_root)
+        .into_iter()
+        .filter_map(|target| {
+            let content = fs::read_to_string(&target.path).ok()?;
+            let is_editor_target = csharp::assigned_value(&content, "Type")
+                .is_some_and(|value| value == "TargetType.Editor");
+            if is_editor_module(&params.module_type) && !is_editor_target {
+                log::verbose_with_category(reporter, "context", format!("Skipping non-editor target {}", target.name));
+                return None;
+            }
+            match csharp::add_to_list(&content, "ExtraModuleNames", &params.name) {
+                Some((range, to)) => Some(TargetUpdate {
+                    path: target.path,
+                    from: content[range].to_owned(),
+                    to,
+                }),
+                None => {
+                    log::step(
+                        reporter,
+                        "skip",
+                        format!("{} does not add to ExtraModuleNames", target.name),
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    let project_root = params.project_root.clone();
+    let (source_root, descriptor) = match &params.plugin {
+        Some(name) => {
+            let plugin = detect_project_plugins(&project_root)
+                .into_iter()
+                .find(|plugin| plugin.name == *name)
+                .ok_or("plugin must be part of project")?;
+            let descriptor = plugin.root.join(&plugin.name).with_extension("uplugin");
+            (plugin.root.join("Source"), descriptor)
+        }
+        None => (
+            project_root.join("Source"),
+            find_project_descriptor(&project_root).ok_or("project descriptor should exist")?,
+        ),
+    };
+    let module_root = source_root.join(&params.name);
+    log::verbose_with_category(reporter, "context", format!("Module root: {:?}", module_root));
+    if module_root.exists() {
+        return Err(format!("{:?} already exists", module_root));
+    }
+    log::verbose_with_category(reporter, "context", format!("Descriptor: {:?}", descriptor));
+    let target_updates = match params.add_to_targets {
+        true => gather_target_updates(params, reporter),
+        false => vec![],
+    };
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Adding to {} targets", target_updates.len()),
+    );
+    log::verbose(reporter, "Context gathering completed");
+
+    Ok(Context {
+        project_root,
+        name: params.name.clone(),
+        module_root,
+        descriptor,
+        module_type: params.module_type.clone(),
+        loading_phase: params.loading_phase.clone(),
+        target_updates,
+    })
+}
@@ -0,0 +1,55 @@
+use crate::{
+    changes::{AddDescriptorEntry, Change, CreateDir, CreateFile},
+    descriptor::{module_entry, plugin_descriptor, plugin_entry},
+    presentation::{log, reporter::Reporter},
+    workflows::add_module::scaffold_module,
+};
+
+use super::Context;
+
+/// A plain placeholder for the icon shown in the plugin browser.
+const PLACEHOLDER_ICON: &[u8] = include_bytes!("Icon128.png");
+
+/// Generate a changeset to add a plugin to an Unreal Engine project.
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
+    let Context {
+        project_descriptor,
+        name,
+        plugin_root,
+        module,
+        module_type,
+        content,
+        icon,
+        enable,
+        ..
+    } = context;
+
+    log::verbose(reporter, "Generating changeset");
+    let mut changeset = vec![Change::CreateFile(CreateFile::new(
+        plugin_root.join(name).with_extension("uplugin"),
+        plugin_descriptor(name, *content, &module_entry(module, module_type, "Default")),
+    ))];
+    changeset.extend(scaffold_module(&plugin_root.join("Source").join(module), module, module_type));
+    if *content {
+        log::verbose_with_category(reporter, "changeset", "Creating Content folder");
+        changeset.push(Change::CreateDir(CreateDir::new(plugin_root.join("Content"))));
+    }
+    if *icon {
+        log::verbose_with_category(reporter, "changeset", "Creating placeholder icon");
+        changeset.push(Change::CreateFile(CreateFile::new(
+            plugin_root.join("Resources/Icon128.png"),
+            PLACEHOLDER_ICON,
+        )));
+    }
+    if *enable {
+        log::verbose_with_category(reporter, "changeset", format!("Enabling plugin in {:?}", project_descriptor));
+        changeset.push(Change::AddDescriptorEntry(AddDescriptorEntry::new(
+            project_descriptor,
+            "Plugins",
+            name,
+            plugin_entry(name),
+        )));
+    }
+    log::verbose(reporter, "Changeset generation completed");
+    changeset
+}
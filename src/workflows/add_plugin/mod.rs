@@ -0,0 +1,152 @@
+mod changeset;
+
+use std::{path::PathBuf, rc::Rc};
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    descriptor::MODULE_TYPES,
+    discovery::{detect_modules, detect_project_plugins, find_project_descriptor},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    validation::{validate_name_with, NameKind},
+};
+
+use super::run::{run_changeset, Run};
+use self::changeset::generate_changeset;
+
+/// Params needed to add a plugin to an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The name of the new plugin.
+    pub name: String,
+    /// The name of the initial module of the plugin. Defaults to the name of
+    /// the plugin.
+    pub module: Option<String>,
+    /// The type of the initial module, such as `Runtime` or `Editor`.
+    pub module_type: String,
+    /// Create a `Content` folder and allow the plugin to contain content.
+    pub content: bool,
+    /// Create a placeholder `Resources/Icon128.png`.
+    pub icon: bool,
+    /// Enable the plugin in the `Plugins` array of the project descriptor.
+    pub enable: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to add a plugin to an Unreal Engine project.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The project descriptor.
+    pub project_descriptor: PathBuf,
+    /// The name of the new plugin.
+    pub name: String,
+    /// The root of the new plugin.
+    pub plugin_root: PathBuf,
+    /// The name of the initial module.
+    pub module: String,
+    /// The type of the initial module.
+    pub module_type: String,
+    /// Create a `Content` folder.
+    pub content: bool,
+    /// Create a placeholder icon.
+    pub icon: bool,
+    /// Enable the plugin in the project descriptor.
+    pub enable: bool,
+}
+
+/// Add a plugin with an initial module to an Unreal Engine project.
+pub fn add_plugin(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("add_plugin");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let changeset = generate_changeset(&context, &*reporter);
+    let run = Run {
+        workflow: "add_plugin",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: String::new(),
+        new_name: context.name.clone(),
+        description: format!("add plugin {}", context.name),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Successfully added plugin {}. Regenerate project files before building.",
+            context.name
+        ),
+        failure_message: format!("Failed to add plugin {}.", context.name),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.add_plugin, changeset)
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating name is valid");
+    validate_name_with(&params.name, NameKind::Plugin, &config.validation)
+        .map_err(|err| err.to_string())?;
+    log::verbose_with_category(reporter, "validation", "Validating name is unique");
+    if detect_project_plugins(&params.project_root).iter().any(|plugin| plugin.name == params.name) {
+        return Err("name must not conflict with another plugin".into());
+    }
+    let module = params.module.as_ref().unwrap_or(&params.name);
+    log::verbose_with_category(reporter, "validation", "Validating module name is valid");
+    validate_name_with(module, NameKind::Module, &config.validation)
+        .map_err(|err| format!("module {}", err))?;
+    log::verbose_with_category(reporter, "validation", "Validating module name is unique");
+    if detect_modules(&params.project_root).iter().any(|other| other.name == *module) {
+        return Err("module name must not conflict with another module".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Validating module type");
+    if !MODULE_TYPES.contains(&params.module_type.as_str()) {
+        return Err(format!("module type must be one of {}", MODULE_TYPES.join(", ")));
+    }
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    let project_root = params.project_root.clone();
+    let project_descriptor =
+        find_project_descriptor(&project_root).ok_or("project descriptor should exist")?;
+    let plugin_root = project_root.join("Plugins").join(&params.name);
+    log::verbose_with_category(reporter, "context", format!("Plugin root: {:?}", plugin_root));
+    if plugin_root.exists() {
+        return Err(format!("{:?} already exists", plugin_root));
+    }
+    log::verbose(reporter, "Context gathering completed");
+
+    Ok(Context {
+        project_root,
+        project_descriptor,
+        name: params.name.clone(),
+        plugin_root,
+        module: params.module.clone().unwrap_or_else(|| params.name.clone()),
+        module_type: params.module_type.clone(),
+        content: params.content,
+        icon: params.icon,
+        enable: params.enable,
+    })
+}
@@ -0,0 +1,93 @@
+use indoc::formatdoc;
+use regex::{NoExpand, Regex};
+
+use crate::{
+    changes::{Change, CreateFile},
+    csharp::replace_references,
+    presentation::{log, reporter::Reporter},
+};
+
+use super::{Context, TargetSource};
+
+/// Generate a changeset to add a target to an Unreal Engine project.
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
+    let Context {
+        name,
+        target_path,
+        source,
+        ..
+    } = context;
+
+    log::verbose(reporter, "Generating changeset");
+    let content = match source {
+        TargetSource::Template {
+            target_type,
+            modules,
+        } => generate_target(name, target_type, modules),
+        TargetSource::Clone {
+            target,
+            content,
+            target_type,
+        } => clone_target(name, target, content, target_type.as_deref(), reporter),
+    };
+    log::verbose(reporter, "Changeset generation completed");
+    vec![Change::CreateFile(CreateFile::new(target_path, content))]
+}
+
+fn generate_target(name: &str, target_type: &str, modules: &[String]) -> String {
+    let extra_module_names = match modules.is_empty() {
+        true => String::new(),
+        false => {
+            let modules: Vec<String> = modules.iter().map(|module| format!("\"{}\"", module)).collect();
+            format!(
+                "\n\t\tExtraModuleNames.AddRange(new string[] {{ {} }});",
+                modules.join(", ")
+            )
+        }
+    };
+    formatdoc! {"
+        using UnrealBuildTool;
+        using System.Collections.Generic;
+
+        public class {name}Target : TargetRules
+        {{
+        \tpublic {name}Target(TargetInfo Target) : base(Target)
+        \t{{
+        \t\tType = TargetType.{target_type};
+        \t\tDefaultBuildSettings = BuildSettingsVersion.Latest;
+        \t\tIncludeOrderVersion = EngineIncludeOrderVersion.Latest;{extra_module_names}
+        \t}}
+        }}
+    "}
+}
+
+fn clone_target(
+    name: &str,
+    target: &str,
+    content: &str,
+    target_type: Option<&str>,
+    reporter: &dyn Reporter,
+) -> String {
+    let content = replace_references(
+        content,
+        &format!("{}Target", target),
+        &format!("{}Target", name),
+        true,
+        false,
+    );
+    let Some(target_type) = target_type else {
+        return content;
+    };
+    let type_assignment = Regex::new(r"\bType\s*=\s*TargetType\.\w+").unwrap();
+    if !type_assignment.is_match(&content) {
+        log::step(
+            reporter,
+            "skip",
+            format!("{} does not set its type, so the type of the clone is inherited", target),
+        );
+        return content;
+    }
+    type_assignment
+        .replace_all(&content, NoExpand(&format!("Type = TargetType.{}", target_type)))
+        .into_owned()
+}
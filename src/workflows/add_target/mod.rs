@@ -0,0 +1,208 @@
+mod changeset;
+
+use std::{fs, path::PathBuf, rc::Rc};
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    descriptor::listed_modules,
+    discovery::{detect_project_targets, find_project_descriptor},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    validation::{validate_name_with, NameKind},
+};
+
+use super::run::{run_changeset, Run};
+use self::changeset::generate_changeset;
+
+/// Target types that a target can be created with.
+pub const TARGET_TYPES: &[&str] = &["Game", "Editor", "Client", "Server", "Program"];
+
+/// Params needed to add a target to an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The name of the new target.
+    pub name: String,
+    /// The type of the new target. Defaults to `Game`, or to the type of the
+    /// cloned target.
+    pub target_type: Option<String>,
+    /// An existing target to clone instead of using the template.
+    pub from: Option<String>,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to add a target to an Unreal Engine project.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The name of the new target.
+    pub name: String,
+    /// The path of the new target file.
+    pub target_path: PathBuf,
+    /// What the new target file is generated from.
+    pub source: TargetSource,
+}
+
+/// What a new target file is generated from.
+pub enum TargetSource {
+    /// The built-in template, listing the given modules in `ExtraModuleNames`.
+    Template {
+        target_type: String,
+        modules: Vec<String>,
+    },
+    /// An existing target file, optionally with another target type.
+    Clone {
+        target: String,
+        content: String,
+        target_type: Option<String>,
+    },
+}
+
+/// Add a target to an Unreal Engine project, either from a template or by
+/// cloning an existing target.
+pub fn add_target(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("add_target");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let changeset = generate_changeset(&context, &*reporter);
+    let run = Run {
+        workflow: "add_target",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: String::new(),
+        new_name: context.name.clone(),
+        description: format!("add target {}", context.name),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Successfully added target {}. Regenerate project files before building.",
+            context.name
+        ),
+        failure_message: format!("Failed to add target {}.", context.name),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.add_target, changeset)
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains Source folder",
+    );
+    if !params.project_root.join("Source").is_dir() {
+        return Err("project root must contain a Source folder".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating name is valid");
+    validate_name_with(&params.name, NameKind::Target, &config.validation)
+        .map_err(|err| err.to_string())?;
+    let targets = detect_project_targets(&params.project_root);
+    log::verbose_with_category(reporter, "validation", "Validating name is unique");
+    if targets.iter().any(|target| target.name == params.name) {
+        return Err("name must not conflict with another target".into());
+    }
+    if let Some(target_type) = &params.target_type {
+        log::verbose_with_category(reporter, "validation", "Validating target type");
+        if !TARGET_TYPES.contains(&target_type.as_str()) {
+            return Err(format!("target type must be one of {}", TARGET_TYPES.join(", ")));
+        }
+    }
+    if let Some(from) = &params.from {
+        log::verbose_with_category(
+            reporter,
+            "validation",
+            format!("Validating target '{}' exists", from),
+        );
+        if !targets.iter().any(|target| target.name == *from) {
+            return Err("target to clone must be part of project".into());
+        }
+    }
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+/// Whether modules of a type are built for targets of a type.
+fn is_built_for(module_type: &str, target_type: &str) -> bool {
+    match target_type {
+        "Editor" => module_type != "Program",
+        "Program" => module_type.ends_with("Program"),
+        _ => match module_type {
+            "Runtime" | "RuntimeNoCommandlet" | "RuntimeAndProgram" | "CookedOnly" => true,
+            "ClientOnly" | "ClientOnlyNoCommandlet" => target_type != "Server",
+            "ServerOnly" => target_type != "Client",
+            _ => false,
+        },
+    }
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    let project_root = params.project_root.clone();
+    let target_path = project_root.join("Source").join(format!("{}.Target.cs", params.name));
+    if target_path.exists() {
+        return Err(format!("{:?} already exists", target_path));
+    }
+    let source = match &params.from {
+        Some(from) => {
+            let target = detect_project_targets(&project_root)
+                .into_iter()
+                .find(|target| target.name == *from)
+                .ok_or("target to clone should exist")?;
+            log::verbose_with_category(reporter, "context", format!("Cloning {:?}", target.path));
+            TargetSource::Clone {
+                target: target.name,
+                content: fs::read_to_string(&target.path).map_err(|err| err.to_string())?,
+                target_type: params.target_type.clone(),
+            }
+        }
+        None => {
+            let target_type = params.target_type.clone().unwrap_or_else(|| "Game".into());
+            let descriptor =
+                find_project_descriptor(&project_root).ok_or("project descriptor should exist")?;
+            let descriptor = fs::read_to_string(descriptor).map_err(|err| err.to_string())?;
+            let modules: Vec<String> = listed_modules(&descriptor)
+                .into_iter()
+                .filter(|module| is_built_for(&module.module_type, &target_type))
+                .map(|module| module.name)
+                .collect();
+            log::verbose_with_category(
+                reporter,
+                "context",
+                format!("Primary modules: {:?}", modules),
+            );
+            TargetSource::Template {
+                target_type,
+                modules,
+            }
+        }
+    };
+    log::verbose(reporter, "Context gathering completed");
+
+    Ok(Context {
+        project_root,
+        name: params.name.clone(),
+        target_path,
+        source,
+    })
+}
@@ -0,0 +1,9 @@
+use crate::{artifacts::generate_clean_changeset, changes::Change};
+
+use super::Context;
+
+/// Generate a changeset to clean the build artifacts of an Unreal Engine
+/// project.
+pub fn generate_changeset(context: &Context) -> Vec<Change> {
+    generate_clean_changeset(&context.project_root)
+}
@@ -0,0 +1,98 @@
+mod changeset;
+
+use std::{path::PathBuf, rc::Rc};
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    discovery::find_project_descriptor,
+    error::Error,
+    presentation::{log, reporter::Reporter},
+};
+
+use super::run::{run_changeset, Run};
+
+use self::changeset::generate_changeset;
+
+/// Params needed to clean the build artifacts of an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to clean the build artifacts of an Unreal Engine project.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+}
+
+/// Move the generated build artifacts of an Unreal Engine project, such as
+/// `Intermediate`, `Binaries` and IDE project files, into the backup area so
+/// that project files can be regenerated from a clean state.
+pub fn clean(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("clean");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter);
+    let changeset = generate_changeset(&context);
+    if changeset.is_empty() {
+        log::success(&*reporter, "No build artifacts found.");
+        return Ok(());
+    }
+
+    let run = Run {
+        workflow: "clean",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: String::new(),
+        new_name: String::new(),
+        description: "clean build artifacts".into(),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Moved {} build artifacts to the backup. Regenerate project files before building.",
+            changeset.len()
+        ),
+        failure_message: "Failed to clean build artifacts.".into(),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.clean, changeset)
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Context {
+    log::verbose(reporter, "Gathering context");
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Project root: {:?}", params.project_root),
+    );
+    log::verbose(reporter, "Context gathering completed");
+    Context {
+        project_root: params.project_root.clone(),
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    backup::{self, BackupOptions, Manifest},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+};
+
+/// Params needed to manage the backups of an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The action to perform on the backups.
+    pub action: Action,
+    /// Where and how backups are stored.
+    pub backup: BackupOptions,
+}
+
+/// An action to perform on the backups of a project.
+pub enum Action {
+    /// List all backup runs.
+    List,
+    /// Show the files backed up during a run.
+    Show { run: String },
+    /// Restore a single file, all files or the snapshot of a run.
+    Restore {
+        run: String,
+        file: Option<PathBuf>,
+        snapshot: bool,
+    },
+    /// Delete runs by age or count.
+    Prune {
+        older_than_days: Option<u32>,
+        keep: Option<usize>,
+    },
+}
+
+/// Manage the backups that workflows create for an Unreal Engine project.
+pub fn manage_backups(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("backup");
+    let Params {
+        project_root,
+        action,
+        backup,
+    } = params;
+
+    if !project_root.is_dir() {
+        return Err(Error::Validation("project root must be a directory".into()));
+    }
+
+    let options = backup.resolve(&project_root)?;
+
+    match action {
+        Action::List => list_runs(&project_root, &options, &*reporter),
+        Action::Show { run } => show_run(&project_root, &options, &run, &*reporter),
+        Action::Restore {
+            run,
+            file,
+            snapshot: true,
+        } if file.is_none() => restore_snapshot(&project_root, &options, &run, &*reporter),
+        Action::Restore { run, file, .. } => restore(&project_root, &options, &run, file, &*reporter),
+        Action::Prune {
+            older_than_days,
+            keep,
+        } => prune(&project_root, &options, older_than_days, keep, &*reporter),
+    }
+    .map_err(Error::from)
+}
+
+fn list_runs(
+    project_root: &Path,
+    options: &BackupOptions,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let runs = backup::list_runs(project_root, options)?;
+    log::header(reporter, "Backups");
+    if runs.is_empty() {
+        log::basic(reporter, "No backups found.");
+    }
+    for run in &runs {
+        log::basic(reporter, describe_run(run));
+    }
+    Ok(())
+}
+
+fn show_run(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &str,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let run = backup::find_run(project_root, options, run)?;
+    log::header(reporter, describe_run(&run));
+    for entry in run.original_entries() {
+        log::step(reporter, "file", entry.original.to_str().unwrap_or("invalid Unicode path"));
+    }
+    for entry in &run.moved {
+        log::step(reporter, "moved", entry.original.to_str().unwrap_or("invalid Unicode path"));
+    }
+    Ok(())
+}
+
+fn restore(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &str,
+    file: Option<PathBuf>,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let run = backup::find_run(project_root, options, run)?;
+    match file {
+        Some(file) => {
+            backup::restore_file(project_root, options, &run, &file, reporter)?;
+            log::step(reporter, "restore", file.to_str().unwrap_or("invalid Unicode path"));
+            log::success(reporter, format!("Restored {:?} from backup {}.", file, run.id));
+        }
+        None => {
+            for entry in run.original_entries().into_iter().chain(&run.moved) {
+                log::step(
+                    reporter,
+                    "restore",
+                    entry.original.to_str().unwrap_or("invalid Unicode path"),
+                );
+            }
+            backup::restore_run(project_root, options, &run, reporter)?;
+            log::success(reporter, format!("Restored all files from backup {}.", run.id));
+        }
+    }
+    Ok(())
+}
+
+fn restore_snapshot(
+    project_root: &Path,
+    options: &BackupOptions,
+    run: &str,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    let run = backup::find_run(project_root, options, run)?;
+    let snapshot = run
+        .snapshot
+        .as_ref()
+        .ok_or_else(|| format!("backup run {} does not have a snapshot", run.id))?;
+    for path in &snapshot.created {
+        log::step(reporter, "remove", path.to_str().unwrap_or("invalid Unicode path"));
+    }
+    for path in &snapshot.paths {
+        log::step(reporter, "restore", path.to_str().unwrap_or("invalid Unicode path"));
+    }
+    backup::restore_run_snapshot(project_root, options, &run, reporter)?;
+    log::success(reporter, format!("Restored snapshot from backup {}.", run.id));
+    Ok(())
+}
+
+fn prune(
+    project_root: &Path,
+    options: &BackupOptions,
+    older_than_days: Option<u32>,
+    keep: Option<usize>,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    if older_than_days.is_none() && keep.is_none() {
+        return Err("either a maximum age or a number of backups to keep is required".into());
+    }
+
+    let pruned = backup::prune(project_root, options, older_than_days, keep, reporter)?;
+    for run in &pruned {
+        log::step(reporter, "prune", describe_run(run));
+    }
+    log::success(reporter, format!("Pruned {} backups.", pruned.len()));
+    Ok(())
+}
+
+fn describe_run(run: &Manifest) -> String {
+    let created = run
+        .created_at()
+        .map(|created| created.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| run.created.clone());
+    format!(
+        "{} {} {} ({} files)",
+        run.id,
+        created,
+        run.description,
+        run.original_entries().len()
+    )
+}
@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use ini::Ini;
+use regex::escape;
+
+use crate::{
+    changes::{AppendIniEntry, Change, CompactRedirects, RemoveIniEntry},
+    presentation::{log, reporter::Reporter},
+    redirects::{to_core_redirect, CORE_REDIRECTS_SECTION, LEGACY_REDIRECTS_SECTION},
+};
+
+/// Generate a changeset that compacts the redirects of an engine config file.
+pub fn generate_compact_changeset(engine_config: &Path) -> Vec<Change> {
+    vec![Change::CompactRedirects(CompactRedirects::new(engine_config))]
+}
+
+/// Generate a changeset that replaces the legacy redirects of an engine
+/// config file with equivalent core redirects, then compacts them.
+pub fn generate_migrate_changeset(
+    engine_config: &Path,
+    reporter: &dyn Reporter,
+) -> Result<Vec<Change>, String> {
+    let ini = Ini::load_from_file_noescape(engine_config).map_err(|err| err.to_string())?;
+    let mut changeset = vec![];
+    for properties in ini.section_all(Some(LEGACY_REDIRECTS_SECTION)) {
+        for (key, value) in properties.iter() {
+            if !key.starts_with('+') {
+                continue;
+            }
+            let Some((core_key, core_value)) = to_core_redirect(key, value) else {
+                if key.ends_with("Redirects") {
+                    log::step(
+                        reporter,
+                        "skip",
+                        format!("{}={} has no core equivalent", key, value),
+                    );
+                }
+                continue;
+            };
+            changeset.push(Change::AppendIniEntry(AppendIniEntry::new(
+                engine_config,
+                CORE_REDIRECTS_SECTION,
+                core_key,
+                core_value,
+            )));
+            changeset.push(Change::RemoveIniEntry(RemoveIniEntry::new(
+                engine_config,
+                LEGACY_REDIRECTS_SECTION,
+                key,
+                Some(format!("^{}$", escape(value))),
+            )));
+        }
+    }
+    if !changeset.is_empty() {
+        changeset.extend(generate_compact_changeset(engine_config));
+    }
+    Ok(changeset)
+}
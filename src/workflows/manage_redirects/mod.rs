@@ -0,0 +1,86 @@
+mod changeset;
+
+use std::{path::PathBuf, rc::Rc};
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    error::Error,
+    presentation::{log, reporter::Reporter},
+};
+
+use super::run::{run_changeset, Run};
+
+use self::changeset::{generate_compact_changeset, generate_migrate_changeset};
+
+/// Params needed to manage the redirects of an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The action to perform on the redirects.
+    pub action: Action,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// An action to perform on the redirects of a project.
+pub enum Action {
+    /// Collapse redirect chains and remove self-redirects and duplicates.
+    Compact,
+    /// Replace legacy redirects with equivalent core redirects.
+    Migrate,
+}
+
+/// Manage the redirects declared in the engine config file of an Unreal
+/// Engine project.
+pub fn manage_redirects(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("redirects");
+    let Params {
+        project_root,
+        action,
+        backup,
+        ..
+    } = params;
+
+    if !project_root.is_dir() {
+        return Err(Error::Validation("project root must be a directory".into()));
+    }
+    let engine_config = project_root.join("Config").join("DefaultEngine.ini");
+    if !engine_config.is_file() {
+        return Err(Error::Validation("project must contain Config/DefaultEngine.ini".into()));
+    }
+    let config = Config::load(&project_root)?;
+    config.check_vcs(&project_root).map_err(Error::Validation)?;
+
+    let (description, changeset, success) = match action {
+        Action::Compact => (
+            "compact redirects",
+            generate_compact_changeset(&engine_config),
+            "Successfully compacted redirects.",
+        ),
+        Action::Migrate => (
+            "migrate legacy redirects",
+            generate_migrate_changeset(&engine_config, &*reporter).map_err(Error::Detection)?,
+            "Successfully migrated legacy redirects to [CoreRedirects].",
+        ),
+    };
+    if changeset.is_empty() {
+        log::success(&*reporter, "No redirects to update.");
+        return Ok(());
+    }
+
+    let run = Run {
+        workflow: "redirects",
+        project_root,
+        new_project_root: None,
+        old_name: String::new(),
+        new_name: String::new(),
+        description: description.into(),
+        snapshot_subtrees: vec![],
+        backup,
+        success_message: success.into(),
+        failure_message: format!("Failed to {}.", description),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.redirects, changeset)
+}
@@ -1,11 +1,28 @@
+pub mod add_module;
+pub mod add_plugin;
+pub mod add_target;
+pub mod clean;
+pub mod manage_backups;
+pub mod manage_redirects;
+pub mod move_module;
+pub mod remove_module;
 pub mod rename_module;
 pub mod rename_plugin;
 pub mod rename_project;
 pub mod rename_target;
+mod run;
 mod workflow;
 
-pub use rename_module::*;
-pub use rename_plugin::*;
-pub use rename_project::*;
-pub use rename_target::*;
+pub use add_module::add_module;
+pub use add_plugin::add_plugin;
+pub use add_target::add_target;
+pub use clean::clean;
+pub use manage_backups::manage_backups;
+pub use manage_redirects::manage_redirects;
+pub use move_module::move_module;
+pub use remove_module::remove_module;
+pub use rename_module::{rename_module, rename_module_interactive};
+pub use rename_plugin::{rename_plugin, rename_plugin_interactive};
+pub use rename_project::{rename_project, rename_project_interactive};
+pub use rename_target::{rename_target, rename_target_interactive};
 pub use workflow::*;
@@ -0,0 +1,56 @@
+use crate::{
+    changes::{AddDescriptorEntry, Change, CreateDir, RemoveCsListEntries, RemoveDescriptorEntry, RenameFile},
+    descriptor::plugin_entry,
+    presentation::{log, reporter::Reporter},
+};
+
+use super::Context;
+
+/// Generate a changeset to move a module between an Unreal Engine project and
+/// its plugins.
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
+    let Context {
+        module,
+        new_root,
+        descriptor_entry,
+        new_descriptor,
+        plugin_dependencies,
+        targets,
+        ..
+    } = context;
+
+    log::verbose(reporter, "Generating changeset");
+    let mut changeset = vec![];
+    let source_dir = new_root.parent().expect("module root should have a parent");
+    if !source_dir.is_dir() {
+        changeset.push(Change::CreateDir(CreateDir::new(source_dir)));
+    }
+    changeset.push(Change::RenameFile(RenameFile::new(&module.root, new_root)));
+    if let Some((descriptor, entry)) = descriptor_entry {
+        log::verbose_with_category(
+            reporter,
+            "changeset",
+            format!("Moving entry to {:?}", new_descriptor),
+        );
+        changeset.push(Change::RemoveDescriptorEntry(RemoveDescriptorEntry::new(
+            descriptor,
+            "Modules",
+            &module.name,
+        )));
+        changeset.push(Change::AddDescriptorEntry(AddDescriptorEntry::new(
+            new_descriptor,
+            "Modules",
+            &module.name,
+            entry,
+        )));
+    }
+    changeset.extend(plugin_dependencies.iter().map(|(descriptor, plugin)| {
+        Change::AddDescriptorEntry(AddDescriptorEntry::new(descriptor, "Plugins", plugin, plugin_entry(plugin)))
+    }));
+    log::verbose_with_category(reporter, "changeset", format!("Removing module from {} targets", targets.len()));
+    changeset.extend(targets.iter().map(|target| {
+        Change::RemoveCsListEntries(RemoveCsListEntries::new(&target.path, &module.name, &["ExtraModuleNames"]))
+    }));
+    log::verbose(reporter, "Changeset generation completed");
+    changeset
+}
@@ -0,0 +1,311 @@
+mod changeset;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    cpp::{ImplementMacro, ImplementMacroKind},
+    csharp,
+    descriptor::{listed_entry, listed_names},
+    discovery::{
+        detect_dependencies, detect_dependents, detect_modules, detect_project_plugins,
+        detect_project_targets, find_project_descriptor,
+    },
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    unreal::{Module, Plugin, Target},
+};
+
+use super::run::{run_changeset, Run};
+use self::changeset::generate_changeset;
+
+/// Params needed to move a module between an Unreal Engine project and its
+/// plugins.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The module to move.
+    pub module: String,
+    /// The plugin to move the module to, or `None` to move it to the project.
+    pub to_plugin: Option<String>,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to move a module between an Unreal Engine project and its
+/// plugins.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The module to move.
+    pub module: Module,
+    /// The new root of the module.
+    pub new_root: PathBuf,
+    /// The descriptor listing the module, along with its entry, if listed.
+    pub descriptor_entry: Option<(PathBuf, String)>,
+    /// The descriptor to list the module in.
+    pub new_descriptor: PathBuf,
+    /// Descriptors that must enable a plugin for modules to keep building,
+    /// along with the name of that plugin.
+    pub plugin_dependencies: Vec<(PathBuf, String)>,
+    /// Targets adding the module to their `ExtraModuleNames`, which only
+    /// project modules are added to.
+    pub targets: Vec<Target>,
+}
+
+/// Move a module from the project into a plugin, from a plugin into the
+/// project or between plugins. The module keeps its name, so `/Script/Module`
+/// paths of its classes stay the same.
+pub fn move_module(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("move_module");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let changeset = generate_changeset(&context, &*reporter);
+    let run = Run {
+        workflow: "move_module",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: context.module.name.clone(),
+        new_name: context.module.name.clone(),
+        description: format!("move module {}", context.module.name),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Successfully moved module {}. Regenerate project files before building.",
+            context.module.name
+        ),
+        failure_message: format!("Failed to move module {}.", context.module.name),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.move_module, changeset)
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", format!("Validating module '{}' exists", params.module));
+    let modules = detect_modules(&params.project_root);
+    let module = modules
+        .iter()
+        .find(|module| module.name == params.module)
+        .ok_or("module must be part of project")?;
+    if let Some(plugin) = &params.to_plugin {
+        log::verbose_with_category(
+            reporter,
+            "validation",
+            format!("Validating plugin '{}' exists", plugin),
+        );
+        if !detect_project_plugins(&params.project_root).iter().any(|other| other.name == *plugin) {
+            return Err("plugin must be part of project".into());
+        }
+    }
+    log::verbose_with_category(reporter, "validation", "Validating module is not already in place");
+    let current_plugin = module.plugin.as_ref().map(|plugin| &plugin.name);
+    if current_plugin == params.to_plugin.as_ref() {
+        return Err(match &params.to_plugin {
+            Some(_) => "module is already part of the plugin".into(),
+            None => "module is already part of the project".into(),
+        });
+    }
+    if params.to_plugin.is_some() {
+        log::verbose_with_category(
+            reporter,
+            "validation",
+            "Validating module is not the primary game module",
+        );
+        if is_primary_game_module(&params.project_root, module) {
+            return Err("primary game module must stay part of the project".into());
+        }
+        log::verbose_with_category(
+            reporter,
+            "validation",
+            "Validating module does not depend on project modules",
+        );
+        let project_dependencies: Vec<String> = detect_dependencies(module)
+            .into_iter()
+            .filter(|dependency| {
+                modules
+                    .iter()
+                    .any(|other| other.name == *dependency && other.plugin.is_none())
+            })
+            .collect();
+        if !project_dependencies.is_empty() {
+            return Err(format!(
+                "module depends on project modules {}, which plugin modules cannot depend on",
+                project_dependencies.join(", ")
+            ));
+        }
+    }
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    let project_root = params.project_root.clone();
+    let project_descriptor =
+        find_project_descriptor(&project_root).ok_or("project descriptor should exist")?;
+    let plugins = detect_project_plugins(&project_root);
+    let modules = detect_modules(&project_root);
+    let module = modules
+        .iter()
+        .find(|module| module.name == params.module)
+        .cloned()
+        .ok_or("module should exist")?;
+    let descriptor_of = |plugin: Option<&Plugin>| match plugin {
+        Some(plugin) => plugin.root.join(&plugin.name).with_extension("uplugin"),
+        None => project_descriptor.clone(),
+    };
+    let to_plugin = params
+        .to_plugin
+        .as_ref()
+        .map(|name| plugins.iter().find(|plugin| plugin.name == *name).ok_or("plugin should exist"))
+        .transpose()?;
+
+    let new_root = match to_plugin {
+        Some(plugin) => plugin.root.join("Source"),
+        None => project_root.join("Source"),
+    }
+    .join(&module.name);
+    log::verbose_with_category(reporter, "context", format!("New module root: {:?}", new_root));
+    if new_root.exists() {
+        return Err(format!("{:?} already exists", new_root));
+    }
+
+    let descriptor = descriptor_of(module.plugin.as_ref());
+    let content = fs::read_to_string(&descriptor).map_err(|err| err.to_string())?;
+    let descriptor_entry = match listed_entry(&content, "Modules", &module.name) {
+        Some(entry) => Some((descriptor, entry)),
+        None => {
+            log::step(
+                reporter,
+                "skip",
+                format!("{} is not listed in {:?}", module.name, descriptor),
+            );
+            None
+        }
+    };
+    let new_descriptor = descriptor_of(to_plugin);
+
+    // Plugins of the modules that the module depends on must be enabled where
+    // the module ends up.
+    let plugin_of = |name: &str| {
+        modules
+            .iter()
+            .find(|other| other.name == name)
+            .and_then(|other| other.plugin.as_ref())
+            .map(|plugin| plugin.name.clone())
+    };
+    let mut required: Vec<(PathBuf, String)> = detect_dependencies(&module)
+        .iter()
+        .filter_map(|dependency| plugin_of(dependency))
+        .filter(|plugin| Some(plugin) != params.to_plugin.as_ref())
+        .map(|plugin| (new_descriptor.clone(), plugin))
+        .collect();
+    if let Some(to_plugin) = to_plugin {
+        // The project used the module before, so it must enable the plugin.
+        required.push((project_descriptor.clone(), to_plugin.name.clone()));
+    }
+    // Modules that depend on the module must be able to find it.
+    for dependent in detect_dependents(&project_root, &module.name) {
+        match (&dependent.module.plugin, to_plugin) {
+            (Some(plugin), Some(to_plugin)) if plugin.name != to_plugin.name => {
+                required.push((descriptor_of(Some(plugin)), to_plugin.name.clone()))
+            }
+            (Some(plugin), None) => log::step(
+                reporter,
+                "manual",
+                format!(
+                    "{} of plugin {} depends on {}, which plugins cannot depend on once it is part of the project",
+                    dependent.module.name, plugin.name, module.name
+                ),
+            ),
+            _ => {}
+        }
+    }
+    let mut plugin_dependencies: Vec<(PathBuf, String)> = vec![];
+    for (descriptor, plugin) in required {
+        let listed = fs::read_to_string(&descriptor)
+            .map(|content| listed_names(&content, "Plugins").contains(&plugin))
+            .unwrap_or(false);
+        if !listed && !plugin_dependencies.contains(&(descriptor.clone(), plugin.clone())) {
+            log::verbose_with_category(reporter, "context", format!("{:?} must enable {}", descriptor, plugin));
+            plugin_dependencies.push((descriptor, plugin));
+        }
+    }
+
+    let targets = match to_plugin {
+        Some(_) => detect_project_targets(&project_root)
+            .into_iter()
+            .filter(|target| {
+                fs::read_to_string(&target.path)
+                    .map(|content| {
+                        csharp::properties_with(&content, &module.name)
+                            .iter()
+                            .any(|property| property == "ExtraModuleNames")
+                    })
+                    .unwrap_or(false)
+            })
+            .collect(),
+        None => vec![],
+    };
+    log::verbose(reporter, "Context gathering completed");
+
+    Ok(Context {
+        project_root,
+        module,
+        new_root,
+        descriptor_entry,
+        new_descriptor,
+        plugin_dependencies,
+        targets,
+    })
+}
+
+/// Check whether a module is the primary game module of a project, either
+/// because a target launches it or because it implements itself with
+/// `IMPLEMENT_PRIMARY_GAME_MODULE`.
+fn is_primary_game_module(project_root: &Path, module: &Module) -> bool {
+    let launched = detect_project_targets(project_root).iter().any(|target| {
+        fs::read_to_string(&target.path).is_ok_and(|content| {
+            csharp::properties_with(&content, &module.name)
+                .iter()
+                .any(|property| property == "LaunchModuleName")
+        })
+    });
+    launched
+        || WalkDir::new(&module.root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path().to_owned())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "cpp"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .any(|content| {
+                ImplementMacro::find(&content)
+                    .is_ok_and(|found| found.kind == ImplementMacroKind::PrimaryGameModule)
+            })
+}
@@ -0,0 +1,39 @@
+use crate::{
+    changes::{Change, MoveToBackup, RemoveCsListEntries, RemoveDescriptorEntry},
+    presentation::{log, reporter::Reporter},
+};
+
+use super::Context;
+
+/// Generate a changeset to remove a module from an Unreal Engine project.
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
+    let Context {
+        module,
+        descriptor,
+        dependents,
+        targets,
+        ..
+    } = context;
+
+    log::verbose(reporter, "Generating changeset");
+    let mut changeset = vec![];
+    log::verbose_with_category(reporter, "changeset", format!("Removing module from {} dependents", dependents.len()));
+    changeset.extend(dependents.iter().map(|dependent| {
+        let properties: Vec<&str> = dependent.properties.iter().map(String::as_str).collect();
+        Change::RemoveCsListEntries(RemoveCsListEntries::new(&dependent.build_file, &module.name, &properties))
+    }));
+    log::verbose_with_category(reporter, "changeset", format!("Removing module from {} targets", targets.len()));
+    changeset.extend(targets.iter().map(|target| {
+        Change::RemoveCsListEntries(RemoveCsListEntries::new(&target.path, &module.name, &["ExtraModuleNames"]))
+    }));
+    if let Some(descriptor) = descriptor {
+        changeset.push(Change::RemoveDescriptorEntry(RemoveDescriptorEntry::new(
+            descriptor,
+            "Modules",
+            &module.name,
+        )));
+    }
+    changeset.push(Change::MoveToBackup(MoveToBackup::new(&module.root)));
+    log::verbose(reporter, "Changeset generation completed");
+    changeset
+}
@@ -0,0 +1,261 @@
+mod changeset;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use regex::Regex;
+use walkdir::WalkDir;
+
+use crate::{
+    backup::BackupOptions,
+    config::Config,
+    cpp, csharp,
+    descriptor::listed_modules,
+    discovery::{detect_dependents, detect_modules, detect_project_targets, find_project_descriptor, Dependent},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    unreal::{Module, Target},
+};
+
+use super::run::{run_changeset, Run};
+use self::changeset::generate_changeset;
+
+/// Params needed to remove a module from an Unreal Engine project.
+pub struct Params {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The module to remove.
+    pub module: String,
+    /// Remove the module from the build files of modules that depend on it
+    /// instead of refusing to remove it.
+    pub force: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+}
+
+/// Context needed to remove a module from an Unreal Engine project.
+pub struct Context {
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The module to remove.
+    pub module: Module,
+    /// The project or plugin descriptor listing the module, if any.
+    pub descriptor: Option<PathBuf>,
+    /// Modules that depend on the module.
+    pub dependents: Vec<Dependent>,
+    /// Targets adding the module to their `ExtraModuleNames`.
+    pub targets: Vec<Target>,
+    /// Sources of dependent modules that include headers of the module, along
+    /// with each include as written.
+    pub dependent_includes: Vec<(PathBuf, String)>,
+}
+
+/// Remove a module from an Unreal Engine project or one of its plugins. The
+/// module folder is moved into the backup, and the module is removed from its
+/// descriptor, from target `ExtraModuleNames` and, when forced, from the build
+/// files of modules that depend on it.
+pub fn remove_module(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("remove_module");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let changeset = generate_changeset(&context, &*reporter);
+    let run = Run {
+        workflow: "remove_module",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: context.module.name.clone(),
+        new_name: String::new(),
+        description: format!("remove module {}", context.module.name),
+        snapshot_subtrees: vec![],
+        backup: params.backup,
+        success_message: format!(
+            "Successfully removed module {}. Regenerate project files before building.",
+            context.module.name
+        ),
+        failure_message: format!("Failed to remove module {}.", context.module.name),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.remove_module, changeset)?;
+    report_dependent_includes(&context, &*run.reporter);
+    report_config_references(&context, &*run.reporter);
+    Ok(())
+}
+
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    if !params.project_root.is_dir() {
+        return Err("project root must be a directory".into());
+    }
+    if find_project_descriptor(&params.project_root).is_none() {
+        return Err("project root must contain a project descriptor".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", format!("Validating module '{}' exists", params.module));
+    if !detect_modules(&params.project_root).iter().any(|module| module.name == params.module) {
+        return Err("module must be part of project".into());
+    }
+    log::verbose_with_category(reporter, "validation", "Checking modules depending on the module");
+    let dependents = detect_dependents(&params.project_root, &params.module);
+    for dependent in &dependents {
+        log::step(
+            reporter,
+            "dependent",
+            format!("{} lists {} in {}", dependent.module.name, params.module, dependent.properties.join(", ")),
+        );
+    }
+    if !dependents.is_empty() && !params.force {
+        let names: Vec<&str> = dependents.iter().map(|dependent| dependent.module.name.as_str()).collect();
+        return Err(format!(
+            "module is a dependency of {}; pass --force to remove it from their build files",
+            names.join(", ")
+        ));
+    }
+    log::verbose(reporter, "Parameter validation completed successfully");
+    Ok(())
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    let project_root = params.project_root.clone();
+    let module = detect_modules(&project_root)
+        .into_iter()
+        .find(|module| module.name == params.module)
+        .ok_or("module should exist")?;
+    log::verbose_with_category(reporter, "context", format!("Module root: {:?}", module.root));
+    let descriptor = match &module.plugin {
+        Some(plugin) => plugin.root.join(&plugin.name).with_extension("uplugin"),
+        None => find_project_descriptor(&project_root).ok_or("project descriptor should exist")?,
+    };
+    let descriptor_content = fs::read_to_string(&descriptor).map_err(|err| err.to_string())?;
+    let descriptor = match listed_modules(&descriptor_content).iter().any(|listed| listed.name == module.name) {
+        true => Some(descriptor),
+        false => {
+            log::step(
+                reporter,
+                "skip",
+                format!("{} is not listed in {:?}", module.name, descriptor),
+            );
+            None
+        }
+    };
+    let dependents = detect_dependents(&project_root, &module.name);
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Found {} dependent modules", dependents.len()),
+    );
+    let dependent_includes = gather_dependent_includes(&module, &dependents);
+    log::verbose_with_category(reporter, "context", format!("Found {} includes of the module", dependent_includes.len()));
+    let targets: Vec<Target> = detect_project_targets(&project_root)
+        .into_iter()
+        .filter(|target| {
+            let content = fs::read_to_string(&target.path).unwrap_or_default();
+            let properties = csharp::properties_with(&content, &module.name);
+            if properties.iter().any(|property| property == "LaunchModuleName") {
+                log::step(reporter, "skip", format!("{} launches {}, update it by hand", target.name, module.name));
+            }
+            properties.iter().any(|property| property == "ExtraModuleNames")
+        })
+        .collect();
+    log::verbose_with_category(reporter, "context", format!("Found {} targets adding the module", targets.len()));
+    log::verbose(reporter, "Context gathering completed");
+
+    Ok(Context {
+        project_root,
+        module,
+        descriptor,
+        dependents,
+        targets,
+        dependent_includes,
+    })
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ["h", "hpp", "inl", "cpp"].iter().any(|known| ext == *known))
+}
+
+/// Find includes in the sources of dependent modules that resolve to a header
+/// of the module, by matching the trailing components of their paths.
+fn gather_dependent_includes(module: &Module, dependents: &[Dependent]) -> Vec<(PathBuf, String)> {
+    let headers: Vec<PathBuf> = WalkDir::new(&module.root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| is_source(path) && path.extension().is_some_and(|ext| ext != "cpp"))
+        .collect();
+    let mut dependent_includes = vec![];
+    for dependent in dependents {
+        let sources = WalkDir::new(&dependent.module.root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .filter(|path| is_source(path));
+        for source in sources {
+            let content = fs::read_to_string(&source).unwrap_or_default();
+            for include in cpp::includes(&content) {
+                let components: PathBuf =
+                    include.path.split(['/', '\\']).filter(|segment| !segment.is_empty()).collect();
+                if headers.iter().any(|header| header.ends_with(&components)) {
+                    dependent_includes.push((source.clone(), include.literal()));
+                }
+            }
+        }
+    }
+    dependent_includes
+}
+
+fn report_dependent_includes(context: &Context, reporter: &dyn Reporter) {
+    for (source, include) in &context.dependent_includes {
+        log::step(
+            reporter,
+            "manual",
+            format!("{:?} includes {} of removed module {}", source, include, context.module.name),
+        );
+    }
+}
+
+/// Report config sections and entries that refer to classes of the removed
+/// module, since only the user can tell whether they are still needed.
+fn report_config_references(context: &Context, reporter: &dyn Reporter) {
+    let script_path = Regex::new(&format!(r"/Script/{}\b", regex::escape(&context.module.name))).unwrap();
+    let config_dirs = [
+        Some(context.project_root.join("Config")),
+        context.module.plugin.as_ref().map(|plugin| plugin.root.join("Config")),
+    ];
+    for path in config_dirs.iter().flatten().flat_map(|dir| config_files(dir)) {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let count = content.lines().filter(|line| script_path.is_match(line)).count();
+        if count > 0 {
+            log::step(
+                reporter,
+                "manual",
+                format!("{:?} refers to /Script/{} on {} lines", path, context.module.name, count),
+            );
+        }
+    }
+}
+
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "ini"))
+        .map(|entry| entry.into_path())
+        .collect()
+}
@@ -1,20 +1,22 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::Path;
 
-use regex::Regex;
+use regex::escape;
 
 use crate::{
-    changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile},
-    presentation::log,
+    changes::{
+        AppendIniEntry, Change, CompactRedirects, RenameFile, RenameImplementMacro,
+        ReplaceCsReferences, ReplaceInFile, ReplaceIniValue,
+    },
+    cpp::identifier_pattern,
+    presentation::{log, reporter::Reporter},
+    redirects::RedirectKind,
     unreal::{Module, ModuleType, Plugin},
 };
 
-use super::Context;
+use super::{Context, IncludePathReference, SourceRenames};
 
 /// Generate a changeset to rename an Unreal Engine module.
-pub fn generate_changeset(context: &Context) -> Vec<Change> {
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
     let Context {
         project_root,
         project_name,
@@ -31,33 +33,62 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
         new_name,
         source_with_implement_macro,
         headers_with_export_macro,
+        source_renames,
+        include_path_references,
+        ambiguous_include_paths,
     } = context;
 
-    log::verbose("Generating changeset");
+    log::verbose(reporter, "Generating changeset");
     let mut changeset = vec![];
-    log::verbose_with_category("changeset", format!("Renaming build class: {} -> {}", old_name, new_name));
+    log::verbose_with_category(reporter, "changeset", format!("Renaming build class: {} -> {}", old_name, new_name));
     changeset.push(rename_build_class(module_root, old_name, new_name));
-    log::verbose_with_category("changeset", format!("Renaming build file: {}.Build.cs -> {}.Build.cs", old_name, new_name));
+    log::verbose_with_category(reporter, "changeset", format!("Renaming build file: {}.Build.cs -> {}.Build.cs", old_name, new_name));
     changeset.push(rename_build_file(module_root, old_name, new_name));
 
-    if let Some(source_file) = source_with_implement_macro {
-        log::verbose_with_category("changeset", format!("Updating implement macro in {:?}", source_file));
+    if let Some((source_file, _)) = source_with_implement_macro {
+        log::verbose_with_category(reporter, "changeset", format!("Updating implement macro in {:?}", source_file));
         changeset.push(update_implement_macro(source_file, new_name));
     } else {
-        log::verbose_with_category("changeset", "No implement macro to update");
+        log::verbose_with_category(reporter, "changeset", "No implement macro to update");
     }
 
-    log::verbose_with_category("changeset", format!("Updating API macros in {} header files", headers_with_export_macro.len()));
+    log::verbose_with_category(reporter, "changeset", format!("Updating API macros in {} header files", headers_with_export_macro.len()));
     changeset.extend(
         headers_with_export_macro
             .iter()
             .map(|header_file| rename_api_macro_in_header(header_file, old_name, new_name)),
     );
 
-    log::verbose_with_category("changeset", format!("Renaming source subfolder to {}", new_name));
+    log::verbose_with_category(reporter, "changeset", format!("Updating {} include paths through the module folder", include_path_references.len()));
+    changeset.extend(
+        include_path_references
+            .iter()
+            .map(|reference| update_include_path(reference, module_root, old_name, new_name)),
+    );
+    for reference in ambiguous_include_paths {
+        log::step(
+            reporter,
+            "ambiguous",
+            format!(
+                "{} in {}",
+                reference.literal,
+                reference.file.to_str().unwrap_or("invalid Unicode path")
+            ),
+        );
+    }
+
+    if let Some(source_renames) = source_renames {
+        changeset.extend(rename_sources(source_renames, old_name, new_name, reporter));
+    }
+
+    log::verbose_with_category(
+        reporter,
+        "changeset",
+        format!("Renaming source subfolder to {}", new_name),
+    );
     changeset.push(rename_source_subfolder(module_root, new_name));
 
-    log::verbose_with_category("changeset", format!("Updating module references in {} target files", project_targets.len()));
+    log::verbose_with_category(reporter, "changeset", format!("Updating module references in {} target files", project_targets.len()));
     changeset.extend(
         project_targets
             .iter()
@@ -65,7 +96,7 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
     );
 
     let other_modules_count = modules.iter().filter(|m| &m.name != old_name).count();
-    log::verbose_with_category("changeset", format!("Updating module references in {} other module build files", other_modules_count));
+    log::verbose_with_category(reporter, "changeset", format!("Updating module references in {} other module build files", other_modules_count));
     changeset.extend(
         modules
             .iter()
@@ -79,7 +110,7 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
             }),
     );
 
-    log::verbose_with_category("changeset", format!("Updating module reference in {}.uproject", project_name));
+    log::verbose_with_category(reporter, "changeset", format!("Updating module reference in {}.uproject", project_name));
     changeset.push(replace_mod_reference_in_project_descriptor(
         project_root,
         project_name,
@@ -88,62 +119,153 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
     ));
 
     if let ModuleType::Plugin = r#type {
-        log::verbose_with_category("changeset", "Updating module reference in plugin descriptor");
+        log::verbose_with_category(
+            reporter,
+            "changeset",
+            "Updating module reference in plugin descriptor",
+        );
         changeset.push(replace_mod_reference_in_plugin_descriptor(
-            &plugin.as_ref().unwrap(),
+            plugin.as_ref().unwrap(),
             old_name,
             new_name,
         ));
     }
 
-    log::verbose_with_category("changeset", format!("Updating module references in {} config files", project_config_files.len()));
+    log::verbose_with_category(reporter, "changeset", format!("Updating module references in {} config files", project_config_files.len()));
     changeset.extend(
         project_config_files
             .iter()
             .map(|config_file| replace_mod_references_in_config(config_file, old_name, new_name)),
     );
 
-    log::verbose_with_category("changeset", "Updating existing module redirects in DefaultEngine.ini");
-    changeset.push(update_existing_redirects(project_root, old_name, new_name));
-    log::verbose_with_category("changeset", "Appending new module redirect to DefaultEngine.ini");
+    log::verbose_with_category(reporter, "changeset", "Updating existing module redirects in DefaultEngine.ini");
+    changeset.extend(update_existing_redirects(project_root, old_name, new_name));
+    log::verbose_with_category(
+        reporter,
+        "changeset",
+        "Appending new module redirect to DefaultEngine.ini",
+    );
     changeset.push(append_mod_redirect(project_root, old_name, new_name));
+    log::verbose_with_category(reporter, "changeset", "Compacting redirects in DefaultEngine.ini");
+    changeset.push(compact_redirects(project_root));
 
-    log::verbose("Changeset generation completed");
+    log::verbose(reporter, "Changeset generation completed");
     changeset
 }
 
-fn update_implement_macro(source_file: &PathBuf, new_name: &str) -> Change {
-    let content = fs::read_to_string(&source_file).unwrap();
-    let regex =
-        Regex::new(r#"(?P<macro>IMPLEMENT_(GAME_|PRIMARY_GAME_)?MODULE)\((?P<impl>.+?),"#).unwrap();
-    let captures = regex.captures(&content).unwrap();
-    let macr = captures.name("macro").unwrap().as_str();
-    let implementation = captures.name("impl").unwrap().as_str();
-    Change::ReplaceInFile(ReplaceInFile::new(
-        source_file,
-        r#"_MODULE\(.+\)"#,
-        if macr == "IMPLEMENT_PRIMARY_GAME_MODULE" {
-            format!(
-                r#"_MODULE({}, {}, "{}")"#,
-                implementation, new_name, new_name
-            )
-        } else {
-            format!(r#"_MODULE({}, {})"#, implementation, new_name)
-        },
-    ))
+fn update_implement_macro(source_file: &Path, new_name: &str) -> Change {
+    Change::RenameImplementMacro(RenameImplementMacro::new(source_file, new_name))
 }
 
-fn update_existing_redirects(project_root: &Path, old_name: &str, new_name: &str) -> Change {
+fn update_include_path(
+    reference: &IncludePathReference,
+    module_root: &Path,
+    old_name: &str,
+    new_name: &str,
+) -> Change {
+    // The build file of the module has already been renamed at this point.
+    let file = match reference.file == module_root.join(old_name).with_extension("Build.cs") {
+        true => module_root.join(new_name).with_extension("Build.cs"),
+        false => reference.file.clone(),
+    };
     Change::ReplaceInFile(ReplaceInFile::new(
-        project_root.join("Config").join("DefaultEngine.ini"),
-        format!(
-            r#"\(OldName="(?P<old>.+?)",\s*NewName="/Script/{}"\)"#,
-            old_name
-        ),
-        format!(r#"(OldName="$old", NewName="/Script/{}")"#, new_name),
+        file,
+        escape(&reference.literal),
+        reference.new_literal.replace('$', "$$"),
     ))
 }
 
+fn rename_sources(
+    source_renames: &SourceRenames,
+    old_name: &str,
+    new_name: &str,
+    reporter: &dyn Reporter,
+) -> Vec<Change> {
+    let SourceRenames {
+        main_files,
+        main_header_includes,
+        ambiguous_main_header_includes,
+        implementation_class,
+        sources_with_implementation_class,
+        sources_with_log_category,
+    } = source_renames;
+    let mut changeset = vec![];
+
+    log::verbose_with_category(reporter, "changeset", format!("Updating {} includes of {}.h", main_header_includes.len(), old_name));
+    changeset.extend(main_header_includes.iter().map(|reference| {
+        Change::ReplaceInFile(ReplaceInFile::new(
+            &reference.file,
+            escape(&reference.literal),
+            reference.new_literal.replace('$', "$$"),
+        ))
+    }));
+    for reference in ambiguous_main_header_includes {
+        log::step(
+            reporter,
+            "ambiguous",
+            format!(
+                "{} in {}",
+                reference.literal,
+                reference.file.to_str().unwrap_or("invalid Unicode path")
+            ),
+        );
+    }
+
+    if let Some((class, new_class)) = implementation_class {
+        log::verbose_with_category(reporter, "changeset", format!("Renaming implementation class: {} -> {}", class, new_class));
+        changeset.extend(sources_with_implementation_class.iter().map(|source| {
+            Change::ReplaceInFile(ReplaceInFile::new(source, identifier_pattern(class), new_class))
+        }));
+    }
+
+    log::verbose_with_category(reporter, "changeset", format!("Renaming log category in {} source files", sources_with_log_category.len()));
+    changeset.extend(sources_with_log_category.iter().map(|source| {
+        Change::ReplaceInFile(ReplaceInFile::new(
+            source,
+            identifier_pattern(&format!("Log{}", old_name)),
+            format!("Log{}", new_name),
+        ))
+    }));
+
+    log::verbose_with_category(
+        reporter,
+        "changeset",
+        format!("Renaming {} main files", main_files.len()),
+    );
+    changeset.extend(main_files.iter().map(|file| {
+        let extension = file.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        Change::RenameFile(RenameFile::new(
+            file,
+            file.with_file_name(format!("{}.{}", new_name, extension)),
+        ))
+    }));
+
+    changeset
+}
+
+/// Point existing redirects to the module at its new name, such as
+/// `NewName="/Script/Old"` or `NewClassName="/Script/Old.Class"`. Legacy game
+/// name redirects may omit the script package prefix.
+fn update_existing_redirects(project_root: &Path, old_name: &str, new_name: &str) -> Vec<Change> {
+    let engine_config = project_root.join("Config").join("DefaultEngine.ini");
+    RedirectKind::all()
+        .into_iter()
+        .map(|kind| {
+            let prefix = match kind.key.as_str() {
+                "ActiveGameNameRedirects" => "(?:/Script/)?",
+                _ => "/Script/",
+            };
+            Change::ReplaceIniValue(ReplaceIniValue::new(
+                &engine_config,
+                kind.section,
+                &kind.key,
+                format!(r#"{}(\s*=\s*"?){}{}([".,)])"#, kind.new_field, prefix, escape(old_name)),
+                format!("{}${{1}}/Script/{}${{2}}", kind.new_field, new_name),
+            ))
+        })
+        .collect()
+}
+
 fn append_mod_redirect(project_root: &Path, old_name: &str, new_name: &str) -> Change {
     Change::AppendIniEntry(AppendIniEntry::new(
         project_root.join("Config").join("DefaultEngine.ini"),
@@ -157,19 +279,11 @@ fn append_mod_redirect(project_root: &Path, old_name: &str, new_name: &str) -> C
 }
 
 fn replace_mod_reference_in_target(target: &Path, old_name: &str, new_name: &str) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
-        target,
-        format!(r#""{}""#, old_name),
-        format!(r#""{}""#, new_name),
-    ))
+    Change::ReplaceCsReferences(ReplaceCsReferences::new(target, old_name, new_name, false, true))
 }
 
 fn replace_mod_reference_in_mod(module: &Path, old_name: &str, new_name: &str) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
-        module,
-        format!(r#""{}""#, old_name),
-        format!(r#""{}""#, new_name),
-    ))
+    Change::ReplaceCsReferences(ReplaceCsReferences::new(module, old_name, new_name, false, true))
 }
 
 fn rename_build_file(module_root: &Path, old_name: &str, new_name: &str) -> Change {
@@ -180,17 +294,19 @@ fn rename_build_file(module_root: &Path, old_name: &str, new_name: &str) -> Chan
 }
 
 fn rename_build_class(module_root: &Path, old_name: &str, new_name: &str) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
+    Change::ReplaceCsReferences(ReplaceCsReferences::new(
         module_root.join(old_name).with_extension("Build.cs"),
         old_name,
         new_name,
+        true,
+        true,
     ))
 }
 
 fn rename_api_macro_in_header(header_file: &Path, old_name: &str, new_name: &str) -> Change {
     Change::ReplaceInFile(ReplaceInFile::new(
         header_file,
-        format!("{}_API", old_name.to_uppercase()),
+        identifier_pattern(&format!("{}_API", old_name.to_uppercase())),
         format!("{}_API", new_name.to_uppercase()),
     ))
 }
@@ -234,3 +350,9 @@ fn replace_mod_references_in_config(config: &Path, old_name: &str, new_name: &st
         format!(r#"/Script/{}."#, new_name),
     ))
 }
+
+fn compact_redirects(project_root: &Path) -> Change {
+    Change::CompactRedirects(CompactRedirects::new(
+        project_root.join("Config").join("DefaultEngine.ini"),
+    ))
+}
@@ -1,33 +1,34 @@
 use std::{
     ffi::OsStr,
     fs,
-    path::{Path, PathBuf},
+    path::PathBuf,
 };
 
-use inquire::{validator::Validation, CustomUserError, Select, Text};
-use regex::Regex;
-use walkdir::WalkDir;
+use inquire::{validator::Validation, Confirm, CustomUserError, Select, Text};
 
-use crate::presentation::log;
-use crate::unreal::{Module, ModuleType, Plugin};
+use crate::backup::BackupOptions;
+use crate::config::Config;
+use crate::discovery::detect_modules;
+use crate::validation::{validate_name_with, NameKind, ValidationOverrides};
+use crate::unreal::Module;
 
 use super::Params;
 
-pub fn get_params_from_user() -> Result<Params, String> {
+pub fn get_params_from_user(backup: BackupOptions) -> Result<Params, String> {
     let project_root = get_project_root_from_user()?;
-    let project_plugins = detect_project_plugins(&project_root)?;
-    let modules = detect_project_modules(&project_root)?
-        .into_iter()
-        .chain(detect_plugin_modules(&project_plugins)?)
-        .collect::<Vec<Module>>();
+    let modules = detect_modules(&project_root);
     let target_module = get_target_module_from_user(&modules)?;
-    let target_name = get_target_name_from_user(&modules)?;
+    let overrides = Config::load(&project_root)?.validation;
+    let target_name = get_target_name_from_user(&modules, overrides)?;
+    let rename_sources = get_rename_sources_from_user()?;
 
     Ok(Params {
         project_root,
         module: target_module.name,
         new_name: target_name,
-        verbose: log::is_verbose(),
+        rename_sources,
+        clean: false,
+        backup,
     })
 }
 
@@ -37,7 +38,7 @@ fn get_project_root_from_user() -> Result<PathBuf, String> {
         .with_validator(validate_project_root_contains_project_descriptor)
         .with_validator(validate_project_root_contains_source_dir)
         .prompt()
-        .map(|project_root| PathBuf::from(project_root))
+        .map(PathBuf::from)
         .map_err(|err| err.to_string())
 }
 
@@ -79,127 +80,38 @@ fn validate_project_root_contains_source_dir(
     }
 }
 
-/// Detect all plugins in a project given the path to the project root
-/// directory. Detects top-level plugins and nested plugins. Returns an error in
-/// case of I/O issues.
-fn detect_project_plugins(project_root: &PathBuf) -> Result<Vec<Plugin>, String> {
-    let plugins_dir = project_root.join("Plugins");
-    Ok(WalkDir::new(plugins_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .map_or(false, |ext| ext == "uplugin")
-        })
-        .map(|entry| Plugin {
-            root: entry.path().parent().unwrap().to_owned(),
-            name: entry
-                .path()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_owned(),
-        })
-        .collect())
-}
-
-/// Detect all project modules in a project given the path to the project root
-/// directory. Detects top-level modules and nested modules. Assumes that the
-/// Source folder exists. Returns an error in case of I/O issues.
-fn detect_project_modules(project_root: &PathBuf) -> Result<Vec<Module>, String> {
-    let source_dir = project_root.join("Source");
-    assert!(source_dir.is_dir());
-    Ok(WalkDir::new(source_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| entry.path().is_dir() && dir_contains_module_descriptor(entry.path()))
-        .map(|entry| Module {
-            root: entry.path().to_owned(),
-            name: get_dir_name(&entry.path()),
-            r#type: ModuleType::Project,
-            plugin: None,
-        })
-        .collect())
-}
-
-/// Detect all plugin modules in a project given the list of project plugins.
-/// Detects top-level modules and nested modules. Returns an error in case of
-/// I/O issues.
-fn detect_plugin_modules(project_plugins: &[Plugin]) -> Result<Vec<Module>, String> {
-    Ok(project_plugins
-        .iter()
-        .flat_map(|plugin| {
-            WalkDir::new(&plugin.root)
-                .into_iter()
-                .filter_map(Result::ok)
-                .filter(|entry| {
-                    entry.path().is_dir() && dir_contains_module_descriptor(entry.path())
-                })
-                .map(move |entry| Module {
-                    root: entry.path().to_owned(),
-                    name: get_dir_name(&entry.path()),
-                    r#type: ModuleType::Plugin,
-                    plugin: Some(plugin.clone()),
-                })
-        })
-        .collect())
-}
-
-fn dir_contains_module_descriptor(dir: &Path) -> bool {
-    assert!(dir.is_dir());
-    let dir_name = dir.file_name().expect("directory name should exist");
-    dir.join(dir_name).with_extension("Build.cs").is_file()
-}
-
-fn get_dir_name(dir: &Path) -> String {
-    dir.file_name()
-        .expect("directory name should exist")
-        .to_str()
-        .expect("name should be valid Unicode")
-        .to_string()
-}
-
 fn get_target_module_from_user(modules: &[Module]) -> Result<Module, String> {
     Select::new("Choose a module:", modules.to_vec())
         .prompt()
         .map_err(|err| err.to_string())
 }
 
-fn get_target_name_from_user(modules: &[Module]) -> Result<String, String> {
+fn get_target_name_from_user(
+    modules: &[Module],
+    overrides: ValidationOverrides,
+) -> Result<String, String> {
     let modules = modules.to_vec();
     Text::new("Provide a new name for the module:")
-        .with_validator(validate_target_name_is_not_empty)
-        .with_validator(validate_target_name_is_concise)
+        .with_validator(move |input: &str| validate_target_name_is_valid(input, &overrides))
         .with_validator(move |input: &str| validate_target_name_is_unique(input, &modules))
-        .with_validator(validate_target_name_is_valid_identifier)
         .prompt()
         .map_err(|err| err.to_string())
 }
 
-fn validate_target_name_is_not_empty(target_name: &str) -> Result<Validation, CustomUserError> {
-    match !target_name.trim().is_empty() {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message = "Target name must not be empty";
-            Ok(Validation::Invalid(error_message.into()))
-        }
-    }
+fn get_rename_sources_from_user() -> Result<bool, String> {
+    Confirm::new("Also rename the module's main source files, implementation class and log categories?")
+        .with_default(false)
+        .prompt()
+        .map_err(|err| err.to_string())
 }
 
-fn validate_target_name_is_concise(target_name: &str) -> Result<Validation, CustomUserError> {
-    let target_name_max_len = 30;
-    match target_name.len() <= target_name_max_len {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message = format!(
-                "Target name must not be longer than {} characters",
-                target_name_max_len
-            );
-            Ok(Validation::Invalid(error_message.into()))
-        }
+fn validate_target_name_is_valid(
+    target_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<Validation, CustomUserError> {
+    match validate_name_with(target_name, NameKind::Module, overrides) {
+        Ok(()) => Ok(Validation::Valid),
+        Err(err) => Ok(Validation::Invalid(format!("Target {}", err).into())),
     }
 }
 
@@ -215,17 +127,3 @@ fn validate_target_name_is_unique(
         }
     }
 }
-
-fn validate_target_name_is_valid_identifier(
-    target_name: &str,
-) -> Result<Validation, CustomUserError> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(target_name) {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message =
-                "Target name must be comprised of alphanumeric characters and underscores only";
-            Ok(Validation::Invalid(error_message.into()))
-        }
-    }
-}
@@ -5,17 +5,27 @@ use std::{
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
 use regex::Regex;
 use walkdir::WalkDir;
 
 use crate::{
-    engine::Engine,
-    presentation::log,
-    unreal::{Module, ModuleType, Plugin},
+    artifacts::{generate_stale_artifacts_changeset, GENERATED_DIR_NAMES},
+    backup::BackupOptions,
+    config::Config,
+    cpp::{
+        identifier_pattern, includes, ImplementMacro, ImplementMacroError, Include,
+    },
+    discovery::{detect_modules, detect_project_targets},
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    unreal::Module,
+    validation::{validate_name_with, validate_path_lengths_with, NameKind, ValidationOverrides},
 };
 
+use super::run::{run_changeset, Run};
 use self::{changeset::generate_changeset, interactive::get_params_from_user};
 
 /// Params needed to rename an Unreal Engine module.
@@ -26,8 +36,13 @@ pub struct Params {
     pub module: String,
     /// The new name for the module.
     pub new_name: String,
-    /// Enable verbose logging.
-    pub verbose: bool,
+    /// Also rename the main header and source files, the implementation class
+    /// and the log categories of the module.
+    pub rename_sources: bool,
+    /// Move stale build artifacts into the backup after renaming.
+    pub clean: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
 }
 
 /// Context needed to rename an Unreal Engine module.
@@ -46,65 +61,132 @@ pub struct Context {
     pub module: Module,
     /// The new name for the module.
     pub new_name: String,
-    /// The source file that includes the module implement macro.
-    pub source_with_implement_macro: Option<PathBuf>,
+    /// The source file that includes the module implement macro, along with
+    /// the macro invocation.
+    pub source_with_implement_macro: Option<(PathBuf, ImplementMacro)>,
     /// Header files that include the module export macro.
     pub headers_with_export_macro: Vec<PathBuf>,
+    /// Source files and identifiers named after the module, if they should be
+    /// renamed as well.
+    pub source_renames: Option<SourceRenames>,
+    /// Include directives and include paths that reference the module folder.
+    pub include_path_references: Vec<IncludePathReference>,
+    /// Include directives and include paths that may or may not reference the
+    /// module folder, and are left as they are.
+    pub ambiguous_include_paths: Vec<IncludePathReference>,
+}
+
+/// A path in a source or build file that goes through the module folder, such
+/// as `#include "Module/Public/Header.h"` or `PublicIncludePaths.Add("Module/Public")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludePathReference {
+    /// The file containing the path.
+    pub file: PathBuf,
+    /// The path literal, including its delimiters.
+    pub literal: String,
+    /// The path literal with the module folder renamed.
+    pub new_literal: String,
+}
+
+/// Source files and identifiers named after a module, such as `Module.h`,
+/// `FModuleModule` and `LogModule`.
+pub struct SourceRenames {
+    /// The main header and source files of the module.
+    pub main_files: Vec<PathBuf>,
+    /// Includes of the main header of the module, with the path of the
+    /// module folder already renamed.
+    pub main_header_includes: Vec<IncludePathReference>,
+    /// Includes that may be of the main header or of another header with the
+    /// same name.
+    pub ambiguous_main_header_includes: Vec<IncludePathReference>,
+    /// The class implementing the module and its new name, if it is named
+    /// after the module.
+    pub implementation_class: Option<(String, String)>,
+    /// Project source files that reference the implementation class.
+    pub sources_with_implementation_class: Vec<PathBuf>,
+    /// Project source files that reference the log category of the module.
+    pub sources_with_log_category: Vec<PathBuf>,
 }
 
 /// Rename an Unreal Engine module interactively, soliciting input parameters
 /// from the user with validation and guided selection.
-pub fn rename_module_interactive() -> Result<(), String> {
-    let params = get_params_from_user()?;
-    rename_module(params)
+pub fn rename_module_interactive(
+    backup: BackupOptions,
+    reporter: Rc<dyn Reporter>,
+) -> Result<(), Error> {
+    let params = get_params_from_user(backup)?;
+    rename_module(params, reporter)
 }
 
 /// Rename an Unreal Engine module.
-pub fn rename_module(params: Params) -> Result<(), String> {
-    validate_params(&params)?;
-    let context = gather_context(&params)?;
-    let changeset = generate_changeset(&context);
-    let backup_dir = create_backup_dir(&context.project_root)?;
-    let mut engine = Engine::new();
-    if let Err(e) = engine.execute(changeset, backup_dir) {
-        log::error(&e);
-        engine.revert()?;
-        print_failure_message(&context);
-        return Ok(());
+pub fn rename_module(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("rename_module");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let mut changeset = generate_changeset(&context, &*reporter);
+    if params.clean {
+        // Artifacts are moved first, before the directories containing them
+        // are renamed.
+        let artifacts =
+            generate_stale_artifacts_changeset(&context.project_root, &context.module.name);
+        changeset.splice(0..0, artifacts);
     }
-
-    print_success_message(&context);
-    Ok(())
+    let run = Run {
+        workflow: "rename_module",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: context.module.name.clone(),
+        new_name: context.new_name.clone(),
+        description: format!("rename module {} to {}", context.module.name, context.new_name),
+        snapshot_subtrees: snapshot_subtrees(&context),
+        backup: params.backup,
+        success_message: success_message(&context),
+        failure_message: failure_message(&context),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.rename_module, changeset)
 }
 
-fn validate_params(params: &Params) -> Result<(), String> {
-    log::verbose("Starting parameter validation");
-    log::verbose_with_category("validation", "Checking project root is a directory");
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(reporter, "validation", "Checking project root is a directory");
     validate_project_root_is_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains .uproject file");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
     validate_project_root_contains_project_descriptor(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains Source folder");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains Source folder",
+    );
     validate_project_root_contains_source_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Detecting project plugins");
-    let project_plugins = detect_project_plugins(&params.project_root)?;
-    log::verbose_with_category("validation", format!("Found {} plugins", project_plugins.len()));
-    log::verbose_with_category("validation", "Detecting project and plugin modules");
-    let modules = detect_project_modules(&params.project_root)?
-        .into_iter()
-        .chain(detect_plugin_modules(&project_plugins)?)
-        .collect::<Vec<Module>>();
-    log::verbose_with_category("validation", format!("Found {} modules total", modules.len()));
-    log::verbose_with_category("validation", format!("Validating module '{}' exists", params.module));
+    log::verbose_with_category(reporter, "validation", "Detecting project and plugin modules");
+    let modules = detect_modules(&params.project_root);
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        format!("Found {} modules total", modules.len()),
+    );
+    log::verbose_with_category(reporter, "validation", format!("Validating module '{}' exists", params.module));
     validate_module_exists(&params.module, &modules)?;
-    log::verbose_with_category("validation", "Validating new name is not empty");
-    validate_new_name_is_not_empty(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name length");
-    validate_new_name_is_concise(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name is unique");
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is valid");
+    validate_new_name_is_valid(&params.new_name, &config.validation)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is unique");
     validate_new_name_is_unique(&params.new_name, &modules)?;
-    log::verbose_with_category("validation", "Validating new name is valid identifier");
-    validate_new_name_is_valid_identifier(&params.new_name)?;
-    log::verbose("Parameter validation completed successfully");
+    log::verbose_with_category(reporter, "validation", "Validating resulting path lengths");
+    let module = modules.iter().find(|module| module.name == params.module).unwrap();
+    validate_new_name_keeps_paths_short(&module.root, &params.module, &params.new_name, &config.validation)?;
+    log::verbose(reporter, "Parameter validation completed successfully");
     Ok(())
 }
 
@@ -141,25 +223,11 @@ fn validate_module_exists(module: &str, modules: &[Module]) -> Result<(), String
     }
 }
 
-fn validate_new_name_is_not_empty(new_name: &str) -> Result<(), String> {
-    match !new_name.trim().is_empty() {
-        true => Ok(()),
-        false => Err("new name must not be empty".into()),
-    }
-}
-
-fn validate_new_name_is_concise(new_name: &str) -> Result<(), String> {
-    let new_name_max_len = 30;
-    match new_name.len() <= new_name_max_len {
-        true => Ok(()),
-        false => {
-            let error_message = format!(
-                "new name must not be longer than {} characters",
-                new_name_max_len
-            );
-            Err(error_message)
-        }
-    }
+fn validate_new_name_is_valid(
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_name_with(new_name, NameKind::Module, overrides).map_err(|err| format!("new {}", err))
 }
 
 fn validate_new_name_is_unique(new_name: &str, modules: &[Module]) -> Result<(), String> {
@@ -172,34 +240,31 @@ fn validate_new_name_is_unique(new_name: &str, modules: &[Module]) -> Result<(),
     }
 }
 
-fn validate_new_name_is_valid_identifier(new_name: &str) -> Result<(), String> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(new_name) {
-        true => Ok(()),
-        false => {
-            let error_message =
-                "new name must be comprised of alphanumeric characters and underscores only";
-            Err(error_message.into())
-        }
-    }
+fn validate_new_name_keeps_paths_short(
+    subtree: &Path,
+    old_name: &str,
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_path_lengths_with(subtree, old_name, new_name, overrides)
+        .map_err(|err| format!("new {}", err))
 }
 
 /// Detect the name of a project given the path to the project root directory.
 /// Assumes that the directory exists and that it contains a project descriptor.
 /// Returns an error in case of I/O issues.
-fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
+fn detect_project_name(project_root: &Path, reporter: &dyn Reporter) -> Result<String, String> {
     assert!(project_root.is_dir());
 
-    log::verbose_with_category("detect", format!("Searching for .uproject file in {:?}", project_root));
+    log::verbose_with_category(reporter, "detect", format!("Searching for .uproject file in {:?}", project_root));
     let project_descriptor = fs::read_dir(project_root)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .map(|entry| entry.path())
-        .filter(|path| path.extension().map_or(false, |ext| ext == "uproject"))
-        .next()
+        .find(|path| path.extension().is_some_and(|ext| ext == "uproject"))
         .expect("project descriptor should exist");
 
-    log::verbose_with_category("detect", format!("Found project descriptor: {:?}", project_descriptor));
+    log::verbose_with_category(reporter, "detect", format!("Found project descriptor: {:?}", project_descriptor));
 
     project_descriptor
         .file_stem()
@@ -208,185 +273,406 @@ fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
         .ok_or("project name is not valid Unicode".into())
 }
 
-/// Detect all plugins in a project given the path to the project root
-/// directory. Detects top-level plugins and nested plugins. Returns an error in
-/// case of I/O issues.
-fn detect_project_plugins(project_root: &PathBuf) -> Result<Vec<Plugin>, String> {
-    let plugins_dir = project_root.join("Plugins");
-    log::verbose_with_category("detect", format!("Searching for plugins in {:?}", plugins_dir));
-    let plugins: Vec<Plugin> = WalkDir::new(plugins_dir)
+fn detect_project_config_files(project_root: &Path) -> Result<Vec<PathBuf>, String> {
+    let config_dir = project_root.join("Config");
+    Ok(WalkDir::new(config_dir)
         .into_iter()
         .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .map_or(false, |ext| ext == "uplugin")
-        })
-        .map(|entry| Plugin {
-            root: entry.path().parent().unwrap().to_owned(),
-            name: entry
-                .path()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_owned(),
-        })
-        .collect();
-    log::verbose_with_category("detect", format!("Found {} plugins", plugins.len()));
-    Ok(plugins)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "ini"))
+        .map(|entry| entry.path().to_owned())
+        .collect())
 }
 
-/// Detect all project modules in a project given the path to the project root
-/// directory. Detects top-level modules and nested modules. Assumes that the
-/// Source folder exists. Returns an error in case of I/O issues.
-fn detect_project_modules(project_root: &PathBuf) -> Result<Vec<Module>, String> {
-    let source_dir = project_root.join("Source");
-    assert!(source_dir.is_dir());
-    log::verbose_with_category("detect", format!("Searching for project modules in {:?}", source_dir));
-    let modules: Vec<Module> = WalkDir::new(source_dir)
+/// Find the source file of a module that invokes the module implement macro.
+/// Returns an error if an invocation is found but cannot be parsed.
+fn find_implementing_source(module_root: &Path) -> Result<Option<(PathBuf, ImplementMacro)>, String> {
+    let sources = WalkDir::new(module_root)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(Result::ok)
-        .filter(|entry| entry.path().is_dir() && dir_contains_module_descriptor(entry.path()))
-        .map(|entry| Module {
-            root: entry.path().to_owned(),
-            name: get_dir_name(&entry.path()),
-            r#type: ModuleType::Project,
-            plugin: None,
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "cpp"));
+    for source in sources {
+        let Ok(content) = fs::read_to_string(&source) else {
+            continue;
+        };
+        match ImplementMacro::find(&content) {
+            Ok(implement_macro) => return Ok(Some((source, implement_macro))),
+            Err(ImplementMacroError::Missing) => continue,
+            Err(err) => return Err(format!("{} in {:?}", err, source)),
+        }
+    }
+    Ok(None)
+}
+
+fn find_headers_with_export_macro(module_root: &Path, module_name: &str) -> Vec<PathBuf> {
+    let export_macro = identifier_pattern(&format!("{}_API", module_name.to_uppercase()));
+    let export_macro = Regex::new(&export_macro).expect("pattern should be valid");
+    WalkDir::new(module_root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| {
+            fs::read_to_string(path).is_ok_and(|content| export_macro.is_match(&content))
         })
-        .collect();
-    log::verbose_with_category("detect", format!("Found {} project modules", modules.len()));
-    Ok(modules)
+        .collect()
 }
 
-/// Detect all plugin modules in a project given the list of project plugins.
-/// Detects top-level modules and nested modules. Returns an error in case of
-/// I/O issues.
-fn detect_plugin_modules(project_plugins: &[Plugin]) -> Result<Vec<Module>, String> {
-    Ok(project_plugins
+/// Engine classes that modules are commonly implemented with.
+const ENGINE_IMPLEMENTATION_CLASSES: &[&str] = &["FDefaultModuleImpl", "FDefaultGameModuleImpl"];
+
+fn detect_project_sources(project_root: &Path) -> Vec<PathBuf> {
+    [project_root.join("Source"), project_root.join("Plugins")]
         .iter()
-        .flat_map(|plugin| {
-            WalkDir::new(&plugin.root)
-                .into_iter()
-                .filter_map(Result::ok)
-                .filter(|entry| {
-                    entry.path().is_dir() && dir_contains_module_descriptor(entry.path())
-                })
-                .map(move |entry| Module {
-                    root: entry.path().to_owned(),
-                    name: get_dir_name(&entry.path()),
-                    r#type: ModuleType::Plugin,
-                    plugin: Some(plugin.clone()),
-                })
+        .flat_map(|dir| WalkDir::new(dir).sort_by_file_name())
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ["h", "hpp", "inl", "cpp"].iter().any(|known| ext == *known))
         })
-        .collect())
+        .collect()
 }
 
-fn detect_project_targets(project_root: &Path) -> Result<Vec<PathBuf>, String> {
-    let source_dir = project_root.join("Source");
-    assert!(source_dir.is_dir());
-    Ok(fs::read_dir(source_dir)
-        .map_err(|err| err.to_string())?
-        .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .path()
-                .to_str()
-                .map(|str| str.ends_with(".Target.cs"))
-                .unwrap_or(false)
+fn find_sources_matching(sources: &[PathBuf], pattern: &str) -> Vec<PathBuf> {
+    let regex = Regex::new(pattern).expect("pattern should be valid");
+    sources
+        .iter()
+        .filter(|source| fs::read_to_string(source).is_ok_and(|content| regex.is_match(&content)))
+        .cloned()
+        .collect()
+}
+
+fn gather_source_renames(
+    project_root: &Path,
+    module: &Module,
+    new_name: &str,
+    implement_macro: Option<&ImplementMacro>,
+) -> SourceRenames {
+    let project_sources = detect_project_sources(project_root);
+    let main_files: Vec<PathBuf> = ["h", "cpp"]
+        .iter()
+        .flat_map(|ext| {
+            let file_name = format!("{}.{}", module.name, ext);
+            project_sources
+                .iter()
+                .filter(move |source| {
+                    source.starts_with(&module.root)
+                        && source.file_name().is_some_and(|name| *name == *file_name)
+                })
+                .cloned()
         })
-        .map(|entry| entry.path().to_owned())
-        .collect())
+        .collect();
+    let (main_header_includes, ambiguous_main_header_includes) =
+        gather_main_header_includes(&project_sources, &main_files, module, new_name);
+    let implementation_class = implement_macro
+        .map(|implement_macro| implement_macro.implementation())
+        .filter(|class| !ENGINE_IMPLEMENTATION_CLASSES.contains(class))
+        .and_then(|class| {
+            let new_class = rename_implementation_class(class, &module.name, new_name)?;
+            Some((class.to_owned(), new_class))
+        });
+    let sources_with_implementation_class = match &implementation_class {
+        Some((class, _)) => find_sources_matching(&project_sources, &identifier_pattern(class)),
+        None => vec![],
+    };
+    let sources_with_log_category = find_sources_matching(
+        &project_sources,
+        &identifier_pattern(&format!("Log{}", module.name)),
+    );
+    SourceRenames {
+        main_files,
+        main_header_includes,
+        ambiguous_main_header_includes,
+        implementation_class,
+        sources_with_implementation_class,
+        sources_with_log_category,
+    }
 }
 
-fn dir_contains_module_descriptor(dir: &Path) -> bool {
-    assert!(dir.is_dir());
-    let dir_name = dir.file_name().expect("directory name should exist");
-    dir.join(dir_name).with_extension("Build.cs").is_file()
+/// The name of the class implementing a module once the module is renamed,
+/// if the class is named after the module, such as `FModule`,
+/// `FModuleModule` or `ModuleImpl`. Only a leading module name is replaced.
+fn rename_implementation_class(class: &str, old_name: &str, new_name: &str) -> Option<String> {
+    if let Some(rest) = class.strip_prefix('F').and_then(|rest| rest.strip_prefix(old_name)) {
+        return Some(format!("F{}{}", new_name, rest));
+    }
+    class.strip_prefix(old_name).map(|rest| format!("{}{}", new_name, rest))
 }
 
-fn get_dir_name(dir: &Path) -> String {
-    dir.file_name()
-        .expect("directory name should exist")
-        .to_str()
-        .expect("name should be valid Unicode")
-        .to_string()
+/// Find includes of the main header of the module, resolving their paths
+/// against the headers of the project so that headers of other modules with
+/// the same name are left alone. The new path of each include assumes that
+/// include paths through the module folder were updated first.
+fn gather_main_header_includes(
+    project_sources: &[PathBuf],
+    main_files: &[PathBuf],
+    module: &Module,
+    new_name: &str,
+) -> (Vec<IncludePathReference>, Vec<IncludePathReference>) {
+    let header_name = format!("{}.h", module.name);
+    let Some(main_header) = main_files.iter().find(|file| file.ends_with(&header_name)) else {
+        return (vec![], vec![]);
+    };
+    let mut references = vec![];
+    let mut ambiguous = vec![];
+    for source in project_sources {
+        let Ok(content) = fs::read_to_string(source) else {
+            continue;
+        };
+        for include in includes(&content) {
+            let components: PathBuf =
+                include.path.split(['/', '\\']).filter(|segment| !segment.is_empty()).collect();
+            if !components.ends_with(&header_name) || !main_header.ends_with(&components) {
+                continue;
+            }
+            let resolution =
+                resolve_include_path(&include.path, module, new_name, project_sources, false);
+            let path = match resolution {
+                Resolution::Module(new_path) => new_path,
+                _ => include.path.clone(),
+            };
+            let new_path = format!("{}{}.h", &path[..path.len() - header_name.len()], new_name);
+            let reference = IncludePathReference {
+                file: source.to_owned(),
+                literal: Include::literal_of(&path, include.angled),
+                new_literal: Include::literal_of(&new_path, include.angled),
+            };
+            let others = project_sources
+                .iter()
+                .filter(|other| *other != main_header && other.ends_with(&components))
+                .count();
+            let list = match others {
+                0 => &mut references,
+                _ => &mut ambiguous,
+            };
+            if !list.contains(&reference) {
+                list.push(reference);
+            }
+        }
+    }
+    (references, ambiguous)
 }
 
-fn detect_project_config_files(project_root: &Path) -> Result<Vec<PathBuf>, String> {
-    let config_dir = project_root.join("Config");
-    Ok(WalkDir::new(config_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "ini"))
-        .map(|entry| entry.path().to_owned())
-        .collect())
+/// How a path that goes through a folder named after the module resolves.
+enum Resolution {
+    /// The path does not resolve within the module folder.
+    Unrelated,
+    /// The path resolves within the module folder only.
+    Module(String),
+    /// The path resolves within the module folder and somewhere else.
+    Ambiguous(String),
 }
 
-fn find_implementing_source(module_root: &Path) -> Option<PathBuf> {
-    WalkDir::new(module_root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .map(|entry| entry.path().to_owned())
-        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "cpp"))
-        .find(|source| {
-            fs::read_to_string(source).map_or(false, |content| content.contains("_MODULE"))
-        })
+/// Resolve a path with `/` or `\` separators against the module folder. Every
+/// path of the project ending with the same components is a candidate.
+fn resolve_include_path(
+    path: &str,
+    module: &Module,
+    new_name: &str,
+    candidates: &[PathBuf],
+    is_dir: bool,
+) -> Resolution {
+    let segments: Vec<&str> = path.split(['/', '\\']).collect();
+    let separator = match path.contains('\\') && !path.contains('/') {
+        true => "\\",
+        false => "/",
+    };
+    for (idx, _) in segments.iter().enumerate().filter(|(_, segment)| **segment == module.name) {
+        let rest: PathBuf = segments[idx + 1..].iter().filter(|segment| !segment.is_empty()).collect();
+        let target = module.root.join(&rest);
+        let exists = match is_dir {
+            true => target.is_dir(),
+            false => !rest.as_os_str().is_empty() && target.is_file(),
+        };
+        if !exists {
+            continue;
+        }
+        let mut new_segments = segments.clone();
+        new_segments[idx] = new_name;
+        let new_path = new_segments.join(separator);
+        let suffix: PathBuf = segments[idx..].iter().filter(|segment| !segment.is_empty()).collect();
+        let others = candidates
+            .iter()
+            .filter(|candidate| candidate.ends_with(&suffix) && **candidate != target)
+            .count();
+        return match others {
+            0 => Resolution::Module(new_path),
+            _ => Resolution::Ambiguous(new_path),
+        };
+    }
+    match is_dir {
+        // Include paths naming a folder after the module that is not the
+        // module folder may still be meant to reference it.
+        true if segments.contains(&module.name.as_str()) => Resolution::Ambiguous(path.to_owned()),
+        _ => Resolution::Unrelated,
+    }
 }
 
-fn find_headers_with_export_macro(module_root: &Path, module_name: &str) -> Vec<PathBuf> {
-    WalkDir::new(module_root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .map(|entry| entry.path().to_owned())
-        .filter(|path| {
-            fs::read_to_string(path).map_or(false, |content| {
-                content.contains(&format!("{}_API", module_name.to_uppercase()))
-            })
+/// String literals passed to `PublicIncludePaths`, `PrivateIncludePaths` and
+/// the like in a build file.
+fn build_file_include_paths(content: &str) -> Vec<String> {
+    let statement = Regex::new(r"\w*IncludePaths\b[^;]*;").expect("regex should be valid");
+    let literal = Regex::new(r#""((?:[^"\\\n]|\\.)*)""#).expect("regex should be valid");
+    statement
+        .find_iter(content)
+        .flat_map(|statement| {
+            literal
+                .captures_iter(statement.as_str())
+                .map(|captures| captures[1].to_owned())
+                .collect::<Vec<_>>()
         })
         .collect()
 }
 
-fn gather_context(params: &Params) -> Result<Context, String> {
-    log::verbose("Gathering context");
+/// Find include directives and build file include paths across the project
+/// that go through the module folder. Returns the references that resolve to
+/// the module folder only, and the ambiguous ones.
+fn gather_include_path_references(
+    project_root: &Path,
+    module: &Module,
+    new_name: &str,
+) -> (Vec<IncludePathReference>, Vec<IncludePathReference>) {
+    let (dirs, files): (Vec<PathBuf>, Vec<PathBuf>) =
+        [project_root.join("Source"), project_root.join("Plugins")]
+            .iter()
+            .flat_map(|dir| {
+                WalkDir::new(dir).sort_by_file_name().into_iter().filter_entry(|entry| {
+                    !GENERATED_DIR_NAMES.iter().any(|name| entry.file_name() == *name)
+                })
+            })
+            .filter_map(Result::ok)
+            .map(|entry| entry.path().to_owned())
+            .partition(|path| path.is_dir());
+
+    let mut references = vec![];
+    let mut ambiguous = vec![];
+    let mut record = |file: &Path,
+                      literal: String,
+                      resolution: Resolution,
+                      new_literal: &dyn Fn(&str) -> String| {
+        let (list, new_path) = match resolution {
+            Resolution::Unrelated => return,
+            Resolution::Module(new_path) => (&mut references, new_path),
+            Resolution::Ambiguous(new_path) => (&mut ambiguous, new_path),
+        };
+        let reference = IncludePathReference {
+            file: file.to_owned(),
+            literal,
+            new_literal: new_literal(&new_path),
+        };
+        if !list.contains(&reference) {
+            list.push(reference);
+        }
+    };
+
+    for source in detect_project_sources(project_root) {
+        let Ok(content) = fs::read_to_string(&source) else {
+            continue;
+        };
+        for include in includes(&content) {
+            let resolution = resolve_include_path(&include.path, module, new_name, &files, false);
+            record(&source, include.literal(), resolution, &|new_path| {
+                Include::literal_of(new_path, include.angled)
+            });
+        }
+    }
+    let build_files = files
+        .iter()
+        .filter(|file| file.to_str().is_some_and(|file| file.ends_with(".Build.cs")));
+    for build_file in build_files {
+        let Ok(content) = fs::read_to_string(build_file) else {
+            continue;
+        };
+        for path in build_file_include_paths(&content) {
+            let resolution = resolve_include_path(&path, module, new_name, &dirs, true);
+            record(build_file, format!(r#""{}""#, path), resolution, &|new_path| {
+                format!(r#""{}""#, new_path)
+            });
+        }
+    }
+    (references, ambiguous)
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
     let project_root = params.project_root.clone();
-    log::verbose_with_category("context", format!("Project root: {:?}", project_root));
-    log::verbose_with_category("context", "Detecting project name");
-    let project_name = detect_project_name(&project_root)?;
-    log::verbose_with_category("context", format!("Project name: {}", project_name));
-    log::verbose_with_category("context", "Detecting project plugins");
-    let project_plugins = detect_project_plugins(&project_root)?;
-    log::verbose_with_category("context", "Detecting all modules");
-    let modules = detect_project_modules(&project_root)?
-        .into_iter()
-        .chain(detect_plugin_modules(&project_plugins)?)
-        .collect::<Vec<Module>>();
-    log::verbose_with_category("context", "Detecting project targets");
-    let project_targets = detect_project_targets(&project_root)?;
-    log::verbose_with_category("context", format!("Found {} targets", project_targets.len()));
-    log::verbose_with_category("context", "Detecting project config files");
+    log::verbose_with_category(reporter, "context", format!("Project root: {:?}", project_root));
+    log::verbose_with_category(reporter, "context", "Detecting project name");
+    let project_name = detect_project_name(&project_root, reporter)?;
+    log::verbose_with_category(reporter, "context", format!("Project name: {}", project_name));
+    log::verbose_with_category(reporter, "context", "Detecting all modules");
+    let modules = detect_modules(&project_root);
+    log::verbose_with_category(reporter, "context", "Detecting project targets");
+    let project_targets: Vec<PathBuf> =
+        detect_project_targets(&project_root).into_iter().map(|target| target.path).collect();
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Found {} targets", project_targets.len()),
+    );
+    log::verbose_with_category(reporter, "context", "Detecting project config files");
     let project_config_files = detect_project_config_files(&project_root)?;
-    log::verbose_with_category("context", format!("Found {} config files", project_config_files.len()));
-    log::verbose_with_category("context", format!("Finding target module: {}", params.module));
+    log::verbose_with_category(reporter, "context", format!("Found {} config files", project_config_files.len()));
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Finding target module: {}", params.module),
+    );
     let target_module = modules
         .iter()
         .find(|module| module.name == params.module)
         .unwrap()
         .clone();
-    log::verbose_with_category("context", format!("Module root: {:?}", target_module.root));
-    log::verbose_with_category("context", "Searching for implementing source file");
-    let implementing_source = find_implementing_source(&target_module.root);
-    if let Some(ref source) = implementing_source {
-        log::verbose_with_category("context", format!("Found implementing source: {:?}", source));
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Module root: {:?}", target_module.root),
+    );
+    log::verbose_with_category(reporter, "context", "Searching for implementing source file");
+    let implementing_source = find_implementing_source(&target_module.root)?;
+    if let Some((ref source, _)) = implementing_source {
+        log::verbose_with_category(
+            reporter,
+            "context",
+            format!("Found implementing source: {:?}", source),
+        );
     } else {
-        log::verbose_with_category("context", "No implementing source found");
+        log::verbose_with_category(reporter, "context", "No implementing source found");
     }
-    log::verbose_with_category("context", "Searching for headers with export macro");
+    log::verbose_with_category(reporter, "context", "Searching for headers with export macro");
     let headers_with_export_macro =
         find_headers_with_export_macro(&target_module.root, &target_module.name);
-    log::verbose_with_category("context", format!("Found {} headers with export macro", headers_with_export_macro.len()));
-    log::verbose("Context gathering completed");
+    log::verbose_with_category(reporter, "context", format!("Found {} headers with export macro", headers_with_export_macro.len()));
+    let source_renames = match params.rename_sources {
+        true => {
+            log::verbose_with_category(
+                reporter,
+                "context",
+                "Searching for sources named after the module",
+            );
+            let source_renames = gather_source_renames(
+                &project_root,
+                &target_module,
+                &params.new_name,
+                implementing_source.as_ref().map(|(_, implement_macro)| implement_macro),
+            );
+            log::verbose_with_category(reporter, "context", format!("Found {} main files", source_renames.main_files.len()));
+            Some(source_renames)
+        }
+        false => None,
+    };
+    log::verbose_with_category(
+        reporter,
+        "context",
+        "Searching for include paths through the module folder",
+    );
+    let (include_path_references, ambiguous_include_paths) =
+        gather_include_path_references(&project_root, &target_module, &params.new_name);
+    log::verbose_with_category(reporter, "context", format!("Found {} include paths, {} ambiguous", include_path_references.len(), ambiguous_include_paths.len()));
+    log::verbose(reporter, "Context gathering completed");
 
     Ok(Context {
         project_root,
@@ -398,27 +684,39 @@ fn gather_context(params: &Params) -> Result<Context, String> {
         new_name: params.new_name.clone(),
         source_with_implement_macro: implementing_source,
         headers_with_export_macro,
+        source_renames,
+        include_path_references,
+        ambiguous_include_paths,
     })
 }
 
-fn create_backup_dir(project_root: &Path) -> Result<PathBuf, String> {
-    let backup_dir = project_root.join(".renom/backup");
-    log::verbose_with_category("backup", format!("Creating backup directory: {:?}", backup_dir));
-    fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-    log::verbose_with_category("backup", "Backup directory created successfully");
-    Ok(backup_dir)
+/// Subtrees of the project captured when snapshot mode is enabled.
+fn snapshot_subtrees(context: &Context) -> Vec<PathBuf> {
+    let mut subtrees = vec![
+        context.module.root.clone(),
+        context.project_root.join("Config"),
+        context
+            .project_root
+            .join(&context.project_name)
+            .with_extension("uproject"),
+    ];
+    subtrees.extend(context.project_targets.iter().cloned());
+    if let Some(plugin) = &context.module.plugin {
+        subtrees.push(plugin.root.join(&plugin.name).with_extension("uplugin"));
+    }
+    subtrees
 }
 
-fn print_success_message(context: &Context) {
-    log::success(format!(
+fn success_message(context: &Context) -> String {
+    format!(
         "Successfully renamed module {} to {}.",
         context.module.name, context.new_name
-    ));
+    )
 }
 
-fn print_failure_message(context: &Context) {
-    log::error(format!(
+fn failure_message(context: &Context) -> String {
+    format!(
         "Failed to rename module {} to {}.",
         context.module.name, context.new_name
-    ));
+    )
 }
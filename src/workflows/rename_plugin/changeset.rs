@@ -1,7 +1,12 @@
 use std::path::Path;
 
+use regex::escape;
+
 use crate::{
-    changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile},
+    changes::{
+        AppendIniEntry, Change, CompactRedirects, RenameFile, ReplaceInFile, ReplaceIniValue,
+    },
+    redirects::RedirectKind,
     unreal::Plugin,
 };
 
@@ -36,8 +41,9 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
         old_name,
         new_name,
     ));
-    changeset.push(update_existing_redirects(project_root, old_name, new_name));
+    changeset.extend(update_existing_redirects(project_root, old_name, new_name));
     changeset.push(append_plugin_redirect(project_root, old_name, new_name));
+    changeset.push(compact_redirects(project_root));
 
     changeset
 }
@@ -73,8 +79,8 @@ fn rename_cross_plugin_references(
 ) -> Vec<Change> {
     project_plugins
         .iter()
-        .filter(|plugin| &plugin.name != old_name)
-        .map(|plugin| rename_plugin_references_in_plugin(&plugin, old_name, new_name))
+        .filter(|plugin| plugin.name != old_name)
+        .map(|plugin| rename_plugin_references_in_plugin(plugin, old_name, new_name))
         .collect()
 }
 
@@ -87,18 +93,22 @@ fn rename_plugin_references_in_plugin(plugin: &Plugin, old_name: &str, new_name:
     ))
 }
 
-fn update_existing_redirects(project_root: &Path, old_name: &str, new_name: &str) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
-        project_root.join("Config").join("DefaultEngine.ini"),
-        format!(
-            r#"\(OldName="/(?P<old>.+?)/",\s*NewName="/{}/",\s*MatchSubstring=true\)"#,
-            old_name
-        ),
-        format!(
-            r#"(OldName="/$old/",NewName="/{}/",MatchSubstring=true)"#,
-            new_name
-        ),
-    ))
+/// Point existing redirects to content of the plugin at its new mount point,
+/// such as `NewName="/Old/Maps/Main"` or `NewClassName="/Old/Blueprints/Actor.Actor_C"`.
+fn update_existing_redirects(project_root: &Path, old_name: &str, new_name: &str) -> Vec<Change> {
+    let engine_config = project_root.join("Config").join("DefaultEngine.ini");
+    RedirectKind::all()
+        .into_iter()
+        .map(|kind| {
+            Change::ReplaceIniValue(ReplaceIniValue::new(
+                &engine_config,
+                kind.section,
+                &kind.key,
+                format!(r#"{}(\s*=\s*"?)/{}([/".,)])"#, kind.new_field, escape(old_name)),
+                format!("{}${{1}}/{}${{2}}", kind.new_field, new_name),
+            ))
+        })
+        .collect()
 }
 
 fn append_plugin_redirect(project_root: &Path, old_name: &str, new_name: &str) -> Change {
@@ -112,3 +122,9 @@ fn append_plugin_redirect(project_root: &Path, old_name: &str, new_name: &str) -
         ),
     ))
 }
+
+fn compact_redirects(project_root: &Path) -> Change {
+    Change::CompactRedirects(CompactRedirects::new(
+        project_root.join("Config").join("DefaultEngine.ini"),
+    ))
+}
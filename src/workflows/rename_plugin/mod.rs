@@ -5,13 +5,21 @@ use std::{
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
-use regex::Regex;
-use walkdir::WalkDir;
-
-use crate::{engine::Engine, presentation::log, unreal::Plugin};
+use crate::{
+    artifacts::generate_stale_artifacts_changeset,
+    backup::BackupOptions,
+    config::Config,
+    discovery::detect_project_plugins,
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    unreal::Plugin,
+    validation::{validate_name_with, validate_path_lengths_with, NameKind, ValidationOverrides},
+};
 
+use super::run::{run_changeset, Run};
 use self::{changeset::generate_changeset, interactive::get_params_from_user};
 
 /// Params needed to rename an Unreal Engine plugin.
@@ -22,8 +30,10 @@ pub struct Params {
     pub plugin: String,
     /// The new name for the plugin.
     pub new_name: String,
-    /// Enable verbose logging.
-    pub verbose: bool,
+    /// Move stale build artifacts into the backup after renaming.
+    pub clean: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
 }
 
 /// Context needed to rename an Unreal Engine plugin.
@@ -42,51 +52,79 @@ pub struct Context {
 
 /// Rename an Unreal Engine plugin interactively, soliciting input parameters
 /// from the user with validation and guided selection.
-pub fn rename_plugin_interactive() -> Result<(), String> {
-    let params = get_params_from_user()?;
-    rename_plugin(params)
+pub fn rename_plugin_interactive(
+    backup: BackupOptions,
+    reporter: Rc<dyn Reporter>,
+) -> Result<(), Error> {
+    let params = get_params_from_user(backup)?;
+    rename_plugin(params, reporter)
 }
 
 /// Rename an Unreal Engine plugin.
-pub fn rename_plugin(params: Params) -> Result<(), String> {
-    validate_params(&params)?;
-    let context = gather_context(&params)?;
-    let changeset = generate_changeset(&context);
-    let backup_dir = create_backup_dir(&context.project_root)?;
-    let mut engine = Engine::new();
-    if let Err(e) = engine.execute(changeset, backup_dir) {
-        log::error(&e);
-        engine.revert()?;
-        print_failure_message(&context);
-        return Ok(());
+pub fn rename_plugin(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("rename_plugin");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let mut changeset = generate_changeset(&context);
+    if params.clean {
+        // Artifacts are moved first, before the directories containing them
+        // are renamed.
+        let artifacts =
+            generate_stale_artifacts_changeset(&context.project_root, &context.plugin.name);
+        changeset.splice(0..0, artifacts);
     }
-
-    print_success_message(&context);
-    Ok(())
+    let run = Run {
+        workflow: "rename_plugin",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: context.plugin.name.clone(),
+        new_name: context.new_name.clone(),
+        description: format!("rename plugin {} to {}", context.plugin.name, context.new_name),
+        snapshot_subtrees: snapshot_subtrees(&context),
+        backup: params.backup,
+        success_message: success_message(&context),
+        failure_message: failure_message(&context),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.rename_plugin, changeset)
 }
 
-fn validate_params(params: &Params) -> Result<(), String> {
-    log::verbose("Starting parameter validation");
-    log::verbose_with_category("validation", "Checking project root is a directory");
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(reporter, "validation", "Checking project root is a directory");
     validate_project_root_is_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains .uproject file");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
     validate_project_root_contains_project_descriptor(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains Source folder");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains Source folder",
+    );
     validate_project_root_contains_source_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Detecting project plugins");
-    let plugins = detect_project_plugins(&params.project_root)?;
-    log::verbose_with_category("validation", format!("Found {} plugins", plugins.len()));
-    log::verbose_with_category("validation", format!("Validating plugin '{}' exists", params.plugin));
+    log::verbose_with_category(reporter, "validation", "Detecting project plugins");
+    let plugins = detect_project_plugins(&params.project_root);
+    log::verbose_with_category(reporter, "validation", format!("Found {} plugins", plugins.len()));
+    log::verbose_with_category(reporter, "validation", format!("Validating plugin '{}' exists", params.plugin));
     validate_plugin_exists(&params.plugin, &plugins)?;
-    log::verbose_with_category("validation", "Validating new name is not empty");
-    validate_new_name_is_not_empty(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name length");
-    validate_new_name_is_concise(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name is unique");
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is valid");
+    validate_new_name_is_valid(&params.new_name, &config.validation)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is unique");
     validate_new_name_is_unique(&params.new_name, &plugins)?;
-    log::verbose_with_category("validation", "Validating new name is valid identifier");
-    validate_new_name_is_valid_identifier(&params.new_name)?;
-    log::verbose("Parameter validation completed successfully");
+    log::verbose_with_category(reporter, "validation", "Validating resulting path lengths");
+    let plugin = plugins.iter().find(|plugin| plugin.name == params.plugin).unwrap();
+    validate_new_name_keeps_paths_short(&plugin.root, &params.plugin, &params.new_name, &config.validation)?;
+    log::verbose(reporter, "Parameter validation completed successfully");
     Ok(())
 }
 
@@ -123,25 +161,11 @@ fn validate_plugin_exists(plugin: &str, plugins: &[Plugin]) -> Result<(), String
     }
 }
 
-fn validate_new_name_is_not_empty(new_name: &str) -> Result<(), String> {
-    match !new_name.trim().is_empty() {
-        true => Ok(()),
-        false => Err("new name must not be empty".into()),
-    }
-}
-
-fn validate_new_name_is_concise(new_name: &str) -> Result<(), String> {
-    let new_name_max_len = 30;
-    match new_name.len() <= new_name_max_len {
-        true => Ok(()),
-        false => {
-            let error_message = format!(
-                "new name must not be longer than {} characters",
-                new_name_max_len
-            );
-            Err(error_message)
-        }
-    }
+fn validate_new_name_is_valid(
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_name_with(new_name, NameKind::Plugin, overrides).map_err(|err| format!("new {}", err))
 }
 
 fn validate_new_name_is_unique(new_name: &str, plugins: &[Plugin]) -> Result<(), String> {
@@ -154,34 +178,36 @@ fn validate_new_name_is_unique(new_name: &str, plugins: &[Plugin]) -> Result<(),
     }
 }
 
-fn validate_new_name_is_valid_identifier(new_name: &str) -> Result<(), String> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(new_name) {
-        true => Ok(()),
-        false => {
-            let error_message =
-                "new name must be comprised of alphanumeric characters and underscores only";
-            Err(error_message.into())
-        }
-    }
+fn validate_new_name_keeps_paths_short(
+    subtree: &Path,
+    old_name: &str,
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_path_lengths_with(subtree, old_name, new_name, overrides)
+        .map_err(|err| format!("new {}", err))
 }
 
-fn gather_context(params: &Params) -> Result<Context, String> {
-    log::verbose("Gathering context");
-    log::verbose_with_category("context", format!("Project root: {:?}", params.project_root));
-    log::verbose_with_category("context", "Detecting project name");
-    let project_name = detect_project_name(&params.project_root)?;
-    log::verbose_with_category("context", format!("Project name: {}", project_name));
-    log::verbose_with_category("context", "Detecting project plugins");
-    let project_plugins = detect_project_plugins(&params.project_root)?;
-    log::verbose_with_category("context", format!("Finding plugin: {}", params.plugin));
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Project root: {:?}", params.project_root),
+    );
+    log::verbose_with_category(reporter, "context", "Detecting project name");
+    let project_name = detect_project_name(&params.project_root, reporter)?;
+    log::verbose_with_category(reporter, "context", format!("Project name: {}", project_name));
+    log::verbose_with_category(reporter, "context", "Detecting project plugins");
+    let project_plugins = detect_project_plugins(&params.project_root);
+    log::verbose_with_category(reporter, "context", format!("Finding plugin: {}", params.plugin));
     let plugin = project_plugins
         .iter()
         .find(|plugin| plugin.name == params.plugin)
         .unwrap()
         .clone();
-    log::verbose_with_category("context", format!("Plugin root: {:?}", plugin.root));
-    log::verbose("Context gathering completed");
+    log::verbose_with_category(reporter, "context", format!("Plugin root: {:?}", plugin.root));
+    log::verbose(reporter, "Context gathering completed");
 
     Ok(Context {
         project_root: params.project_root.clone(),
@@ -192,19 +218,18 @@ fn gather_context(params: &Params) -> Result<Context, String> {
     })
 }
 
-fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
+fn detect_project_name(project_root: &Path, reporter: &dyn Reporter) -> Result<String, String> {
     assert!(project_root.is_dir());
 
-    log::verbose_with_category("detect", format!("Searching for .uproject file in {:?}", project_root));
+    log::verbose_with_category(reporter, "detect", format!("Searching for .uproject file in {:?}", project_root));
     let project_descriptor = fs::read_dir(project_root)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .map(|entry| entry.path())
-        .filter(|path| path.extension().map_or(false, |ext| ext == "uproject"))
-        .next()
+        .find(|path| path.extension().is_some_and(|ext| ext == "uproject"))
         .expect("project descriptor should exist");
 
-    log::verbose_with_category("detect", format!("Found project descriptor: {:?}", project_descriptor));
+    log::verbose_with_category(reporter, "detect", format!("Found project descriptor: {:?}", project_descriptor));
 
     project_descriptor
         .file_stem()
@@ -213,51 +238,28 @@ fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
         .ok_or("project name is not valid Unicode".into())
 }
 
-fn detect_project_plugins(project_root: &PathBuf) -> Result<Vec<Plugin>, String> {
-    let plugins_dir = project_root.join("Plugins");
-    log::verbose_with_category("detect", format!("Searching for plugins in {:?}", plugins_dir));
-    let plugins: Vec<Plugin> = WalkDir::new(plugins_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .map_or(false, |ext| ext == "uplugin")
-        })
-        .map(|entry| Plugin {
-            root: entry.path().parent().unwrap().to_owned(),
-            name: entry
-                .path()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_owned(),
-        })
-        .collect();
-    log::verbose_with_category("detect", format!("Found {} plugins", plugins.len()));
-    Ok(plugins)
-}
-
-fn create_backup_dir(project_root: &Path) -> Result<PathBuf, String> {
-    let backup_dir = project_root.join(".renom/backup");
-    log::verbose_with_category("backup", format!("Creating backup directory: {:?}", backup_dir));
-    fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-    log::verbose_with_category("backup", "Backup directory created successfully");
-    Ok(backup_dir)
+/// Subtrees of the project captured when snapshot mode is enabled.
+fn snapshot_subtrees(context: &Context) -> Vec<PathBuf> {
+    vec![
+        context.plugin.root.clone(),
+        context.project_root.join("Config"),
+        context
+            .project_root
+            .join(&context.project_name)
+            .with_extension("uproject"),
+    ]
 }
 
-fn print_success_message(context: &Context) {
-    log::success(format!(
+fn success_message(context: &Context) -> String {
+    format!(
         "Successfully renamed plugin {} to {}.",
         context.plugin.name, context.new_name
-    ));
+    )
 }
 
-fn print_failure_message(context: &Context) {
-    log::error(format!(
+fn failure_message(context: &Context) -> String {
+    format!(
         "Failed to rename plugin {} to {}.",
         context.plugin.name, context.new_name
-    ));
+    )
 }
@@ -1,28 +1,28 @@
 use std::path::Path;
 
 use crate::changes::{Change, RenameFile, SetIniEntry};
-use crate::presentation::log;
+use crate::presentation::{log, reporter::Reporter};
 
 use super::Context;
 
 /// Generate a changeset to rename an Unreal Engine project.
-pub fn generate_changeset(context: &Context) -> Vec<Change> {
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
     let Context {
         project_root,
         project_name: old_name,
         new_name,
     } = context;
 
-    log::verbose("Generating changeset");
-    log::verbose_with_category("changeset", "Adding GameName to DefaultEngine.ini");
+    log::verbose(reporter, "Generating changeset");
+    log::verbose_with_category(reporter, "changeset", "Adding GameName to DefaultEngine.ini");
     let change1 = add_game_name_to_engine_config(project_root, new_name);
-    log::verbose_with_category("changeset", "Adding ProjectName to DefaultGame.ini");
+    log::verbose_with_category(reporter, "changeset", "Adding ProjectName to DefaultGame.ini");
     let change2 = add_project_name_to_game_config(project_root, new_name);
-    log::verbose_with_category("changeset", format!("Renaming project descriptor: {}.uproject -> {}.uproject", old_name, new_name));
+    log::verbose_with_category(reporter, "changeset", format!("Renaming project descriptor: {}.uproject -> {}.uproject", old_name, new_name));
     let change3 = rename_project_descriptor(project_root, old_name, new_name);
-    log::verbose_with_category("changeset", format!("Renaming project root directory to {}", new_name));
+    log::verbose_with_category(reporter, "changeset", format!("Renaming project root directory to {}", new_name));
     let change4 = rename_project_root(project_root, new_name);
-    log::verbose("Changeset generation completed");
+    log::verbose(reporter, "Changeset generation completed");
 
     vec![change1, change2, change3, change4]
 }
@@ -54,7 +54,7 @@ fn add_project_name_to_game_config(project_root: &Path, new_name: &str) -> Chang
 
 fn rename_project_root(project_root: &Path, new_name: &str) -> Change {
     Change::RenameFile(RenameFile::new(
-        &project_root,
+        project_root,
         project_root.with_file_name(new_name),
     ))
 }
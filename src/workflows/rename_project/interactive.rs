@@ -1,19 +1,22 @@
 use std::{ffi::OsStr, fs, path::PathBuf};
 
 use inquire::{validator::Validation, CustomUserError, Text};
-use regex::Regex;
 
-use crate::presentation::log;
+use crate::backup::BackupOptions;
+use crate::config::Config;
+use crate::validation::{validate_name_with, NameKind, ValidationOverrides};
 use super::Params;
 use indoc::indoc;
 
-pub fn get_params_from_user() -> Result<Params, String> {
+pub fn get_params_from_user(backup: BackupOptions) -> Result<Params, String> {
     let project_root = get_project_root_from_user()?;
-    let target_name = get_target_name_from_user()?;
+    let overrides = Config::load(&project_root)?.validation;
+    let target_name = get_target_name_from_user(overrides)?;
     Ok(Params {
         project_root,
         new_name: target_name,
-        verbose: log::is_verbose(),
+        clean: false,
+        backup,
     })
 }
 
@@ -24,7 +27,7 @@ fn get_project_root_from_user() -> Result<PathBuf, String> {
         .with_validator(validate_project_root_is_not_current_dir)
         .with_validator(validate_project_root_contains_project_descriptor)
         .prompt()
-        .map(|project_root| PathBuf::from(project_root))
+        .map(PathBuf::from)
         .map_err(|err| err.to_string())
 }
 
@@ -84,49 +87,19 @@ fn validate_project_root_contains_project_descriptor(
     }
 }
 
-fn get_target_name_from_user() -> Result<String, String> {
+fn get_target_name_from_user(overrides: ValidationOverrides) -> Result<String, String> {
     Text::new("Provide a new name for the project:")
-        .with_validator(validate_target_name_is_not_empty)
-        .with_validator(validate_target_name_is_concise)
-        .with_validator(validate_target_name_is_valid_identifier)
+        .with_validator(move |input: &str| validate_target_name_is_valid(input, &overrides))
         .prompt()
         .map_err(|err| err.to_string())
 }
 
-fn validate_target_name_is_not_empty(target_name: &str) -> Result<Validation, CustomUserError> {
-    match !target_name.trim().is_empty() {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message = "Target name must not be empty";
-            Ok(Validation::Invalid(error_message.into()))
-        }
-    }
-}
-
-fn validate_target_name_is_concise(target_name: &str) -> Result<Validation, CustomUserError> {
-    let target_name_max_len = 20;
-    match target_name.len() <= target_name_max_len {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message = format!(
-                "Target name must not be longer than {} characters",
-                target_name_max_len
-            );
-            Ok(Validation::Invalid(error_message.into()))
-        }
-    }
-}
-
-fn validate_target_name_is_valid_identifier(
+fn validate_target_name_is_valid(
     target_name: &str,
+    overrides: &ValidationOverrides,
 ) -> Result<Validation, CustomUserError> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(target_name) {
-        true => Ok(Validation::Valid),
-        false => {
-            let error_message =
-                "Target name must be comprised of alphanumeric characters and underscores only";
-            Ok(Validation::Invalid(error_message.into()))
-        }
+    match validate_name_with(target_name, NameKind::Project, overrides) {
+        Ok(()) => Ok(Validation::Valid),
+        Err(err) => Ok(Validation::Invalid(format!("Target {}", err).into())),
     }
 }
@@ -5,12 +5,19 @@ use std::{
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
-use regex::Regex;
-
-use crate::{engine::Engine, presentation::log};
+use crate::{
+    artifacts::generate_stale_artifacts_changeset,
+    backup::BackupOptions,
+    config::Config,
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    validation::{validate_name_with, validate_path_lengths_with, NameKind, ValidationOverrides},
+};
 
+use super::run::{run_changeset, Run};
 use self::{changeset::generate_changeset, interactive::get_params_from_user};
 
 /// Params needed to rename an Unreal Engine project.
@@ -19,8 +26,10 @@ pub struct Params {
     pub project_root: PathBuf,
     /// The new name for the project.
     pub new_name: String,
-    /// Enable verbose logging.
-    pub verbose: bool,
+    /// Move stale build artifacts into the backup after renaming.
+    pub clean: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
 }
 
 /// Context needed to rename an Unreal Engine project.
@@ -35,51 +44,93 @@ pub struct Context {
 
 /// Rename an Unreal Engine project interactively, soliciting input parameters
 /// from the user with validation and guided selection.
-pub fn rename_project_interactive() -> Result<(), String> {
-    let params = get_params_from_user()?;
-    rename_project(params)
+pub fn rename_project_interactive(
+    backup: BackupOptions,
+    reporter: Rc<dyn Reporter>,
+) -> Result<(), Error> {
+    let params = get_params_from_user(backup)?;
+    rename_project(params, reporter)
 }
 
 /// Rename an Unreal Engine project.
-pub fn rename_project(params: Params) -> Result<(), String> {
-    validate_params(&params)?;
-    let context = gather_context(&params)?;
-    let changeset = generate_changeset(&context);
-    let backup_dir = create_backup_dir(&context.project_root)?;
-    let mut engine = Engine::new();
-    if let Err(e) = engine.execute(changeset, backup_dir) {
-        log::error(&e);
-        engine.revert()?;
-        print_failure_message(&context);
-        return Ok(());
+pub fn rename_project(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("rename_project");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let mut changeset = generate_changeset(&context, &*reporter);
+    if params.clean {
+        // Artifacts are moved first, before the directories containing them
+        // are renamed.
+        let artifacts =
+            generate_stale_artifacts_changeset(&context.project_root, &context.project_name);
+        changeset.splice(0..0, artifacts);
     }
-
-    print_success_message(&context);
-    Ok(())
+    let run = Run {
+        workflow: "rename_project",
+        project_root: context.project_root.clone(),
+        new_project_root: Some(context.project_root.with_file_name(&context.new_name)),
+        old_name: context.project_name.clone(),
+        new_name: context.new_name.clone(),
+        description: format!("rename project {} to {}", context.project_name, context.new_name),
+        snapshot_subtrees: snapshot_subtrees(&context),
+        backup: params.backup,
+        success_message: success_message(&context),
+        failure_message: failure_message(&context),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.rename_project, changeset)
 }
 
-fn validate_params(params: &Params) -> Result<(), String> {
-    log::verbose("Starting parameter validation");
-    log::verbose_with_category("validation", "Checking project root is not special directory");
-    validate_project_root_is_not_special(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root is a directory");
-    validate_project_root_is_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root is not current directory");
-    validate_project_root_is_not_current_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains .uproject file");
-    validate_project_root_contains_project_descriptor(&params.project_root)?;
-    log::verbose_with_category("validation", "Detecting current project name");
-    let project_name = detect_project_name(&params.project_root)?;
-    log::verbose_with_category("validation", format!("Detected project name: {}", project_name));
-    log::verbose_with_category("validation", "Validating new name is not empty");
-    validate_new_name_is_not_empty(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name is different from current name");
-    validate_new_name_is_novel(&project_name, &params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name length");
-    validate_new_name_is_concise(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name is valid identifier");
-    validate_new_name_is_valid_identifier(&params.new_name)?;
-    log::verbose("Parameter validation completed successfully");
+fn validate_params(params: &Params, config: &Config, reporter: &dyn Reporter) -> Result<(), Error> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root is not special directory",
+    );
+    validate_project_root_is_not_special(&params.project_root).map_err(Error::Validation)?;
+    log::verbose_with_category(reporter, "validation", "Checking project root is a directory");
+    validate_project_root_is_dir(&params.project_root).map_err(Error::Validation)?;
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root is not current directory",
+    );
+    validate_project_root_is_not_current_dir(&params.project_root).map_err(Error::Validation)?;
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
+    validate_project_root_contains_project_descriptor(&params.project_root)
+        .map_err(Error::Validation)?;
+    log::verbose_with_category(reporter, "validation", "Detecting current project name");
+    let project_name = detect_project_name(&params.project_root, reporter).map_err(Error::Detection)?;
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        format!("Detected project name: {}", project_name),
+    );
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root).map_err(Error::Validation)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is valid");
+    validate_new_name_is_valid(&params.new_name, &config.validation).map_err(Error::Validation)?;
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Validating new name is different from current name",
+    );
+    validate_new_name_is_novel(&project_name, &params.new_name).map_err(Error::Validation)?;
+    log::verbose_with_category(reporter, "validation", "Validating resulting path lengths");
+    validate_new_name_keeps_paths_short(
+        &params.project_root,
+        &project_name,
+        &params.new_name,
+        &config.validation,
+    )
+    .map_err(Error::Validation)?;
+    log::verbose(reporter, "Parameter validation completed successfully");
     Ok(())
 }
 
@@ -111,7 +162,7 @@ fn validate_project_root_is_not_current_dir(project_root: &Path) -> Result<(), S
 }
 
 fn validate_project_root_contains_project_descriptor(project_root: &Path) -> Result<(), String> {
-    match fs::read_dir(&project_root)
+    match fs::read_dir(project_root)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .filter_map(|entry| entry.path().extension().map(OsStr::to_owned))
@@ -129,46 +180,38 @@ fn validate_new_name_is_novel(old_name: &str, new_name: &str) -> Result<(), Stri
     }
 }
 
-fn validate_new_name_is_not_empty(new_name: &str) -> Result<(), String> {
-    match !new_name.trim().is_empty() {
-        true => Ok(()),
-        false => Err("new name must not be empty".into()),
-    }
-}
-
-fn validate_new_name_is_concise(new_name: &str) -> Result<(), String> {
-    let new_name_max_len = 20;
-    match new_name.len() <= new_name_max_len {
-        true => Ok(()),
-        false => {
-            let error_message = format!(
-                "new name must not be longer than {} characters",
-                new_name_max_len
-            );
-            Err(error_message)
-        }
-    }
+fn validate_new_name_is_valid(
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_name_with(new_name, NameKind::Project, overrides).map_err(|err| format!("new {}", err))
 }
 
-fn validate_new_name_is_valid_identifier(new_name: &str) -> Result<(), String> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(new_name) {
-        true => Ok(()),
-        false => {
-            let error_message =
-                "new name must be comprised of alphanumeric characters and underscores only";
-            Err(error_message.into())
-        }
-    }
+fn validate_new_name_keeps_paths_short(
+    project_root: &Path,
+    project_name: &str,
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_path_lengths_with(project_root, project_name, new_name, overrides)
+        .map_err(|err| format!("new {}", err))
 }
 
-fn gather_context(params: &Params) -> Result<Context, String> {
-    log::verbose("Gathering context");
-    log::verbose_with_category("context", format!("Project root: {:?}", params.project_root));
-    let project_name = detect_project_name(&PathBuf::from(&params.project_root))?;
-    log::verbose_with_category("context", format!("Detected project name: {}", project_name));
-    log::verbose_with_category("context", format!("New name: {}", params.new_name));
-    log::verbose("Context gathering completed");
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Project root: {:?}", params.project_root),
+    );
+    let project_name = detect_project_name(&PathBuf::from(&params.project_root), reporter)?;
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!("Detected project name: {}", project_name),
+    );
+    log::verbose_with_category(reporter, "context", format!("New name: {}", params.new_name));
+    log::verbose(reporter, "Context gathering completed");
     Ok(Context {
         project_root: params.project_root.clone(),
         project_name,
@@ -176,19 +219,18 @@ fn gather_context(params: &Params) -> Result<Context, String> {
     })
 }
 
-fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
+fn detect_project_name(project_root: &Path, reporter: &dyn Reporter) -> Result<String, String> {
     assert!(project_root.is_dir());
 
-    log::verbose_with_category("detect", format!("Searching for .uproject file in {:?}", project_root));
+    log::verbose_with_category(reporter, "detect", format!("Searching for .uproject file in {:?}", project_root));
     let project_descriptor = fs::read_dir(project_root)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .map(|entry| entry.path())
-        .filter(|path| path.extension().map_or(false, |ext| ext == "uproject"))
-        .next()
+        .find(|path| path.extension().is_some_and(|ext| ext == "uproject"))
         .expect("project descriptor should exist");
 
-    log::verbose_with_category("detect", format!("Found project descriptor: {:?}", project_descriptor));
+    log::verbose_with_category(reporter, "detect", format!("Found project descriptor: {:?}", project_descriptor));
 
     project_descriptor
         .file_stem()
@@ -197,25 +239,27 @@ fn detect_project_name(project_root: &PathBuf) -> Result<String, String> {
         .ok_or("project name is not valid Unicode".into())
 }
 
-/// Create a directory to store backup files in
-fn create_backup_dir(project_root: &Path) -> Result<PathBuf, String> {
-    let backup_dir = project_root.join(".renom/backup");
-    log::verbose_with_category("backup", format!("Creating backup directory: {:?}", backup_dir));
-    fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-    log::verbose_with_category("backup", "Backup directory created successfully");
-    Ok(backup_dir)
+/// Subtrees of the project captured when snapshot mode is enabled.
+fn snapshot_subtrees(context: &Context) -> Vec<PathBuf> {
+    vec![
+        context.project_root.join("Config"),
+        context
+            .project_root
+            .join(&context.project_name)
+            .with_extension("uproject"),
+    ]
 }
 
-fn print_success_message(context: &Context) {
-    log::success(format!(
+fn success_message(context: &Context) -> String {
+    format!(
         "Successfully renamed project {} to {}.",
         context.project_name, context.new_name
-    ));
+    )
 }
 
-fn print_failure_message(context: &Context) {
-    log::error(format!(
+fn failure_message(context: &Context) -> String {
+    format!(
         "Failed to rename project {} to {}.",
         context.project_name, context.new_name
-    ));
+    )
 }
@@ -1,68 +1,121 @@
 use std::path::Path;
 
+use regex::escape;
+
 use crate::{
-    changes::{Change, RenameFile, ReplaceInFile},
+    changes::{Change, RenameFile, ReplaceCsReferences, ReplaceDescriptorListValues, ReplaceIniValue},
+    presentation::{log, reporter::Reporter},
     unreal::Target,
 };
 
-use super::Context;
+use super::{
+    Context, MODULE_NAME_PROPERTIES, TARGET_CONFIG_ENTRIES, TARGET_DESCRIPTOR_LISTS,
+    TARGET_NAME_PROPERTIES,
+};
 
 /// Generate a changeset to rename an Unreal Engine target.
-pub fn generate_changeset(context: &Context) -> Vec<Change> {
+pub fn generate_changeset(context: &Context, reporter: &dyn Reporter) -> Vec<Change> {
     let Context {
-        project_targets,
         target: Target {
             name: old_name,
             path: target_file,
         },
         new_name,
+        files_with_target_name,
+        targets_with_module_name,
+        targets_with_target_class,
+        target_name_is_module,
+        config_files_with_target,
+        descriptors_with_target,
         ..
     } = context;
 
     let mut changeset = vec![];
 
     changeset.push(rename_target_class(target_file, old_name, new_name));
+    changeset.extend(
+        files_with_target_name
+            .iter()
+            .map(|file| rename_target_name_references(file, old_name, new_name)),
+    );
+    if *target_name_is_module {
+        log::verbose_with_category(
+            reporter,
+            "changeset",
+            format!("Keeping module name properties, {} is also a module", old_name),
+        );
+    } else {
+        changeset.extend(
+            targets_with_module_name
+                .iter()
+                .map(|target_file| rename_module_name_references(target_file, old_name, new_name)),
+        );
+    }
     changeset.push(rename_target_file(target_file, new_name));
-    changeset.extend(rename_cross_target_references(
-        target_file,
-        project_targets,
-        old_name,
-        new_name,
-    ));
+    changeset.extend(
+        targets_with_target_class
+            .iter()
+            .map(|target_file| rename_target_class(target_file, old_name, new_name)),
+    );
+    changeset.extend(
+        config_files_with_target
+            .iter()
+            .flat_map(|config_file| rename_target_in_config(config_file, old_name, new_name)),
+    );
+    changeset.extend(
+        descriptors_with_target
+            .iter()
+            .map(|descriptor| rename_target_in_descriptor(descriptor, old_name, new_name)),
+    );
 
     changeset
 }
 
-fn rename_cross_target_references(
-    target_file: &Path,
-    project_targets: &[Target],
-    old_name: &str,
-    new_name: &str,
-) -> Vec<Change> {
-    project_targets
-        .iter()
-        .filter(|target| &&target.path != &target_file)
-        .map(|target| rename_target_references_in_target(&target.path, old_name, new_name))
-        .collect()
-}
-
-fn rename_target_references_in_target(
-    target_file: &Path,
-    old_name: &str,
-    new_name: &str,
-) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
+fn rename_target_class(target_file: &Path, old_name: &str, new_name: &str) -> Change {
+    Change::ReplaceCsReferences(ReplaceCsReferences::new(
         target_file,
         format!("{}Target", old_name),
         format!("{}Target", new_name),
+        true,
+        false,
     ))
 }
 
-fn rename_target_class(target_file: &Path, old_name: &str, new_name: &str) -> Change {
-    Change::ReplaceInFile(ReplaceInFile::new(
-        target_file,
-        format!("{}Target", old_name),
-        format!("{}Target", new_name),
+fn rename_target_name_references(file: &Path, old_name: &str, new_name: &str) -> Change {
+    Change::ReplaceCsReferences(
+        ReplaceCsReferences::new(file, old_name, new_name, false, true)
+            .in_properties(TARGET_NAME_PROPERTIES),
+    )
+}
+
+fn rename_module_name_references(target_file: &Path, old_name: &str, new_name: &str) -> Change {
+    Change::ReplaceCsReferences(
+        ReplaceCsReferences::new(target_file, old_name, new_name, false, true)
+            .in_properties(MODULE_NAME_PROPERTIES),
+    )
+}
+
+fn rename_target_in_config(config_file: &Path, old_name: &str, new_name: &str) -> Vec<Change> {
+    TARGET_CONFIG_ENTRIES
+        .iter()
+        .map(|(section, key)| {
+            Change::ReplaceIniValue(ReplaceIniValue::new(
+                config_file,
+                *section,
+                *key,
+                format!("^{}$", escape(old_name)),
+                new_name,
+            ))
+        })
+        .collect()
+}
+
+fn rename_target_in_descriptor(descriptor: &Path, old_name: &str, new_name: &str) -> Change {
+    Change::ReplaceDescriptorListValues(ReplaceDescriptorListValues::new(
+        descriptor,
+        TARGET_DESCRIPTOR_LISTS,
+        old_name,
+        new_name,
     ))
 }
 
@@ -5,12 +5,28 @@ use std::{
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
+    rc::Rc,
 };
 
-use regex::Regex;
+use ini::{Ini, ParseOption};
+use walkdir::WalkDir;
 
-use crate::{engine::Engine, presentation::log, unreal::Target};
+use crate::{
+    artifacts::generate_stale_artifacts_changeset,
+    backup::BackupOptions,
+    config::Config,
+    csharp::{self, TokenKind},
+    descriptor,
+    discovery::{
+        detect_modules, detect_project_plugins, detect_project_targets, find_project_descriptor,
+    },
+    error::Error,
+    presentation::{log, reporter::Reporter},
+    unreal::Target,
+    validation::{validate_name_with, validate_path_lengths_with, NameKind, ValidationOverrides},
+};
 
+use super::run::{run_changeset, Run};
 use self::{changeset::generate_changeset, interactive::get_params_from_user};
 
 /// Params needed to rename an Unreal Engine target.
@@ -21,8 +37,10 @@ pub struct Params {
     pub target: String,
     /// The new name for the target.
     pub new_name: String,
-    /// Enable verbose logging.
-    pub verbose: bool,
+    /// Move stale build artifacts into the backup after renaming.
+    pub clean: bool,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
 }
 
 /// Context needed to rename an Unreal Engine target.
@@ -35,55 +53,99 @@ pub struct Context {
     pub target: Target,
     /// The new name for the target.
     pub new_name: String,
+    /// Target and build files that use the target name in target name
+    /// properties.
+    pub files_with_target_name: Vec<PathBuf>,
+    /// Target files that use the target name in module name properties.
+    pub targets_with_module_name: Vec<PathBuf>,
+    /// Target files other than the target that reference its class, such as
+    /// targets deriving from it.
+    pub targets_with_target_class: Vec<PathBuf>,
+    /// Whether a module is named like the target, in which case references to
+    /// the target name in module name properties refer to the module instead.
+    pub target_name_is_module: bool,
+    /// Config files that reference the target by name.
+    pub config_files_with_target: Vec<PathBuf>,
+    /// Project and plugin descriptors that list the target in the
+    /// [`TARGET_DESCRIPTOR_LISTS`] of their modules or plugins.
+    pub descriptors_with_target: Vec<PathBuf>,
 }
 
 /// Rename an Unreal Engine target interactively, soliciting input parameters
 /// from the user with validation and guided selection.
-pub fn rename_target_interactive() -> Result<(), String> {
-    let params = get_params_from_user()?;
-    rename_target(params)
+pub fn rename_target_interactive(
+    backup: BackupOptions,
+    reporter: Rc<dyn Reporter>,
+) -> Result<(), Error> {
+    let params = get_params_from_user(backup)?;
+    rename_target(params, reporter)
 }
 
 /// Rename an Unreal Engine target.
-pub fn rename_target(params: Params) -> Result<(), String> {
-    validate_params(&params)?;
-    let context = gather_context(&params)?;
-    let changeset = generate_changeset(&context);
-    let backup_dir = create_backup_dir(&context.project_root)?;
-    let mut engine = Engine::new();
-    if let Err(e) = engine.execute(changeset, backup_dir) {
-        log::error(&e);
-        engine.revert()?;
-        print_failure_message(&context);
-        return Ok(());
+pub fn rename_target(params: Params, reporter: Rc<dyn Reporter>) -> Result<(), Error> {
+    log::set_workflow("rename_target");
+    let config = Config::load(&params.project_root).map_err(Error::Validation)?;
+    validate_params(&params, &config, &*reporter).map_err(Error::Validation)?;
+    let context = gather_context(&params, &*reporter).map_err(Error::Detection)?;
+    let mut changeset = generate_changeset(&context, &*reporter);
+    if params.clean {
+        // Artifacts are moved first, before the directories containing them
+        // are renamed.
+        let artifacts =
+            generate_stale_artifacts_changeset(&context.project_root, &context.target.name);
+        changeset.splice(0..0, artifacts);
     }
-
-    print_success_message(&context);
-    Ok(())
+    let run = Run {
+        workflow: "rename_target",
+        project_root: context.project_root.clone(),
+        new_project_root: None,
+        old_name: context.target.name.clone(),
+        new_name: context.new_name.clone(),
+        description: format!("rename target {} to {}", context.target.name, context.new_name),
+        snapshot_subtrees: snapshot_subtrees(&context),
+        backup: params.backup,
+        success_message: success_message(&context),
+        failure_message: failure_message(&context),
+        reporter,
+    };
+    run_changeset(&run, &config, &config.workflows.rename_target, changeset)
 }
 
-fn validate_params(params: &Params) -> Result<(), String> {
-    log::verbose("Starting parameter validation");
-    log::verbose_with_category("validation", "Checking project root is a directory");
+fn validate_params(
+    params: &Params,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<(), String> {
+    log::verbose(reporter, "Starting parameter validation");
+    log::verbose_with_category(reporter, "validation", "Checking project root is a directory");
     validate_project_root_is_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains .uproject file");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains .uproject file",
+    );
     validate_project_root_contains_project_descriptor(&params.project_root)?;
-    log::verbose_with_category("validation", "Checking project root contains Source folder");
+    log::verbose_with_category(
+        reporter,
+        "validation",
+        "Checking project root contains Source folder",
+    );
     validate_project_root_contains_source_dir(&params.project_root)?;
-    log::verbose_with_category("validation", "Detecting project targets");
-    let targets = detect_project_targets(&params.project_root)?;
-    log::verbose_with_category("validation", format!("Found {} targets", targets.len()));
-    log::verbose_with_category("validation", format!("Validating target '{}' exists", params.target));
+    log::verbose_with_category(reporter, "validation", "Detecting project targets");
+    let targets = detect_project_targets(&params.project_root);
+    log::verbose_with_category(reporter, "validation", format!("Found {} targets", targets.len()));
+    log::verbose_with_category(reporter, "validation", format!("Validating target '{}' exists", params.target));
     validate_target_exists(&params.target, &targets)?;
-    log::verbose_with_category("validation", "Validating new name is not empty");
-    validate_new_name_is_not_empty(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name length");
-    validate_new_name_is_concise(&params.new_name)?;
-    log::verbose_with_category("validation", "Validating new name is unique");
+    log::verbose_with_category(reporter, "validation", "Checking version control state");
+    config.check_vcs(&params.project_root)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is valid");
+    validate_new_name_is_valid(&params.new_name, &config.validation)?;
+    log::verbose_with_category(reporter, "validation", "Validating new name is unique");
     validate_new_name_is_unique(&params.new_name, &targets)?;
-    log::verbose_with_category("validation", "Validating new name is valid identifier");
-    validate_new_name_is_valid_identifier(&params.new_name)?;
-    log::verbose("Parameter validation completed successfully");
+    log::verbose_with_category(reporter, "validation", "Validating resulting path lengths");
+    let target = targets.iter().find(|target| target.name == params.target).unwrap();
+    validate_new_name_keeps_paths_short(&target.path, &params.target, &params.new_name, &config.validation)?;
+    log::verbose(reporter, "Parameter validation completed successfully");
     Ok(())
 }
 
@@ -120,25 +182,11 @@ fn validate_target_exists(target: &str, targets: &[Target]) -> Result<(), String
     }
 }
 
-fn validate_new_name_is_not_empty(new_name: &str) -> Result<(), String> {
-    match !new_name.trim().is_empty() {
-        true => Ok(()),
-        false => Err("new name must not be empty".into()),
-    }
-}
-
-fn validate_new_name_is_concise(new_name: &str) -> Result<(), String> {
-    let new_name_max_len = 30;
-    match new_name.len() <= new_name_max_len {
-        true => Ok(()),
-        false => {
-            let error_message = format!(
-                "new name must not be longer than {} characters",
-                new_name_max_len
-            );
-            Err(error_message)
-        }
-    }
+fn validate_new_name_is_valid(
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_name_with(new_name, NameKind::Target, overrides).map_err(|err| format!("new {}", err))
 }
 
 fn validate_new_name_is_unique(new_name: &str, targets: &[Target]) -> Result<(), String> {
@@ -151,83 +199,198 @@ fn validate_new_name_is_unique(new_name: &str, targets: &[Target]) -> Result<(),
     }
 }
 
-fn validate_new_name_is_valid_identifier(new_name: &str) -> Result<(), String> {
-    let identifier_regex = Regex::new("^[_[[:alnum:]]]*$").expect("regex should be valid");
-    match identifier_regex.is_match(new_name) {
-        true => Ok(()),
-        false => {
-            let error_message =
-                "new name must be comprised of alphanumeric characters and underscores only";
-            Err(error_message.into())
-        }
-    }
+fn validate_new_name_keeps_paths_short(
+    subtree: &Path,
+    old_name: &str,
+    new_name: &str,
+    overrides: &ValidationOverrides,
+) -> Result<(), String> {
+    validate_path_lengths_with(subtree, old_name, new_name, overrides)
+        .map_err(|err| format!("new {}", err))
+}
+
+/// Properties of target and build files that hold the name of a target. Other
+/// `TargetRules` properties, such as `BuildEnvironment`, `Type` or
+/// `LinkType`, only hold enum values and never name a target.
+const TARGET_NAME_PROPERTIES: &[&str] = &["Name"];
+
+/// Properties of target files that hold module names, which may be named
+/// after the target.
+const MODULE_NAME_PROPERTIES: &[&str] = &["ExtraModuleNames", "LaunchModuleName"];
+
+/// Config entries that hold the name of a target, by section and key.
+pub const TARGET_CONFIG_ENTRIES: &[(&str, &str)] = &[
+    ("/Script/UnrealEd.ProjectPackagingSettings", "BuildTarget"),
+    ("/Script/UnrealEd.ProjectPackagingSettings", "LaunchOnTarget"),
+];
+
+/// Lists of the module and plugin entries of descriptors that hold target
+/// names.
+pub const TARGET_DESCRIPTOR_LISTS: &[&str] = &["TargetAllowList", "TargetDenyList"];
+
+fn detect_descriptors_with_target(project_root: &Path, target_name: &str) -> Vec<PathBuf> {
+    let lists: Vec<String> = TARGET_DESCRIPTOR_LISTS.iter().map(|list| list.to_string()).collect();
+    find_project_descriptor(project_root)
+        .into_iter()
+        .chain(
+            detect_project_plugins(project_root)
+                .into_iter()
+                .map(|plugin| plugin.root.join(format!("{}.uplugin", plugin.name))),
+        )
+        .filter(|path| {
+            fs::read_to_string(path)
+                .is_ok_and(|content| descriptor::lists_contain(&content, &lists, target_name))
+        })
+        .collect()
 }
 
-fn detect_project_targets(project_root: &Path) -> Result<Vec<Target>, String> {
-    let source_dir = project_root.join("Source");
-    assert!(source_dir.is_dir());
-    log::verbose_with_category("detect", format!("Searching for targets in {:?}", source_dir));
-    let targets: Vec<Target> = fs::read_dir(&source_dir)
-        .map_err(|err| err.to_string())?
+fn detect_config_files_with_target(project_root: &Path, target_name: &str) -> Vec<PathBuf> {
+    WalkDir::new(project_root.join("Config"))
+        .sort_by_file_name()
+        .into_iter()
         .filter_map(Result::ok)
-        .filter_map(|entry| {
-            entry
-                .path()
-                .file_name()
-                .and_then(|file_name| file_name.to_str())
-                .and_then(|str| str.strip_suffix(".Target.cs"))
-                .map(|str| str.to_owned())
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ini"))
+        .filter(|path| {
+            let options = ParseOption {
+                enabled_escape: false,
+                enabled_quote: false,
+            };
+            Ini::load_from_file_opt(path, options).is_ok_and(|config| {
+                TARGET_CONFIG_ENTRIES.iter().any(|(section, key)| {
+                    config
+                        .section_all(Some(*section))
+                        .flat_map(|properties| properties.get_all(*key))
+                        .any(|value| value == target_name)
+                })
+            })
         })
-        .map(|target_name| Target {
-            name: target_name.clone(),
-            path: source_dir.join(target_name).with_extension("Target.cs"),
+        .collect()
+}
+
+/// Files that use `name` in a string literal with one of `properties`.
+fn files_with_property_references<'a>(
+    files: impl IntoIterator<Item = &'a PathBuf>,
+    name: &str,
+    properties: &[&str],
+) -> Vec<PathBuf> {
+    files
+        .into_iter()
+        .filter(|file| {
+            fs::read_to_string(file).is_ok_and(|content| {
+                csharp::properties_with(&content, name)
+                    .iter()
+                    .any(|property| properties.contains(&property.as_str()))
+            })
         })
-        .collect();
-    log::verbose_with_category("detect", format!("Found {} targets", targets.len()));
-    Ok(targets)
+        .cloned()
+        .collect()
 }
 
-fn gather_context(params: &Params) -> Result<Context, String> {
-    log::verbose("Gathering context");
+fn file_has_identifier(file: &Path, identifier: &str) -> bool {
+    fs::read_to_string(file).is_ok_and(|content| {
+        csharp::tokenize(&content).iter().any(|token| {
+            token.kind == TokenKind::Identifier && content[token.content.clone()] == *identifier
+        })
+    })
+}
+
+fn gather_context(params: &Params, reporter: &dyn Reporter) -> Result<Context, String> {
+    log::verbose(reporter, "Gathering context");
     let project_root = params.project_root.clone();
-    log::verbose_with_category("context", format!("Project root: {:?}", project_root));
-    log::verbose_with_category("context", "Detecting project targets");
-    let project_targets = detect_project_targets(&project_root)?;
-    log::verbose_with_category("context", format!("Finding target: {}", params.target));
+    log::verbose_with_category(reporter, "context", format!("Project root: {:?}", project_root));
+    log::verbose_with_category(reporter, "context", "Detecting project targets");
+    let project_targets = detect_project_targets(&project_root);
+    log::verbose_with_category(reporter, "context", format!("Finding target: {}", params.target));
     let target = project_targets
         .iter()
         .find(|target| target.name == params.target)
         .unwrap()
         .clone();
-    log::verbose_with_category("context", format!("Target path: {:?}", target.path));
-    log::verbose("Context gathering completed");
+    log::verbose_with_category(reporter, "context", format!("Target path: {:?}", target.path));
+    log::verbose_with_category(reporter, "context", "Detecting module build files");
+    let module_build_files: Vec<PathBuf> = detect_modules(&project_root)
+        .into_iter()
+        .map(|module| module.root.join(format!("{}.Build.cs", module.name)))
+        .collect();
+    let target_name_is_module = module_build_files.iter().any(|build_file| {
+        build_file.file_name().is_some_and(|name| *name == *format!("{}.Build.cs", target.name))
+    });
+    log::verbose_with_category(reporter, "context", format!("Found {} module build files", module_build_files.len()));
+    let target_files: Vec<PathBuf> =
+        project_targets.iter().map(|target| target.path.clone()).collect();
+    log::verbose_with_category(reporter, "context", "Detecting files referencing the target");
+    let files_with_target_name = files_with_property_references(
+        target_files.iter().chain(&module_build_files),
+        &target.name,
+        TARGET_NAME_PROPERTIES,
+    );
+    let targets_with_module_name =
+        files_with_property_references(&target_files, &target.name, MODULE_NAME_PROPERTIES);
+    let target_class = format!("{}Target", target.name);
+    let targets_with_target_class: Vec<PathBuf> = target_files
+        .iter()
+        .filter(|file| **file != target.path && file_has_identifier(file, &target_class))
+        .cloned()
+        .collect();
+    log::verbose_with_category(
+        reporter,
+        "context",
+        format!(
+            "Found {} files with target name, {} with module names, {} with target class",
+            files_with_target_name.len(),
+            targets_with_module_name.len(),
+            targets_with_target_class.len()
+        ),
+    );
+    log::verbose_with_category(
+        reporter,
+        "context",
+        "Detecting config files referencing the target",
+    );
+    let config_files_with_target = detect_config_files_with_target(&project_root, &target.name);
+    log::verbose_with_category(reporter, "context", format!("Found {} config files", config_files_with_target.len()));
+    log::verbose_with_category(reporter, "context", "Detecting descriptors referencing the target");
+    let descriptors_with_target = detect_descriptors_with_target(&project_root, &target.name);
+    log::verbose_with_category(reporter, "context", format!("Found {} descriptors", descriptors_with_target.len()));
+    log::verbose(reporter, "Context gathering completed");
 
     Ok(Context {
         project_root,
         project_targets,
         target,
         new_name: params.new_name.clone(),
+        files_with_target_name,
+        targets_with_module_name,
+        targets_with_target_class,
+        target_name_is_module,
+        config_files_with_target,
+        descriptors_with_target,
     })
 }
 
-fn create_backup_dir(project_root: &Path) -> Result<PathBuf, String> {
-    let backup_dir = project_root.join(".renom/backup");
-    log::verbose_with_category("backup", format!("Creating backup directory: {:?}", backup_dir));
-    fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-    log::verbose_with_category("backup", "Backup directory created successfully");
-    Ok(backup_dir)
+/// Subtrees of the project captured when snapshot mode is enabled.
+fn snapshot_subtrees(context: &Context) -> Vec<PathBuf> {
+    context
+        .project_targets
+        .iter()
+        .map(|target| target.path.clone())
+        .chain(context.files_with_target_name.iter().cloned())
+        .chain(context.config_files_with_target.iter().cloned())
+        .chain(context.descriptors_with_target.iter().cloned())
+        .collect()
 }
 
-fn print_success_message(context: &Context) {
-    log::success(format!(
+fn success_message(context: &Context) -> String {
+    format!(
         "Successfully renamed target {} to {}.",
         context.target.name, context.new_name
-    ));
+    )
 }
 
-fn print_failure_message(context: &Context) {
-    log::error(format!(
+fn failure_message(context: &Context) -> String {
+    format!(
         "Failed to rename target {} to {}.",
         context.target.name, context.new_name
-    ));
+    )
 }
@@ -0,0 +1,152 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    backup::{create_backup_dir, finalize_backup_dir, BackupOptions},
+    changes::Change,
+    config::{Config, WorkflowConfig},
+    engine::Engine,
+    error::Error,
+    hooks::{Hook, HookRunner},
+    presentation::{log, reporter::Reporter},
+    snapshot::{create_snapshot, restore_snapshot},
+};
+
+/// A single run of a workflow that changes a project.
+pub(crate) struct Run {
+    /// Name of the workflow, such as `rename_module`.
+    pub workflow: &'static str,
+    /// The root of the project.
+    pub project_root: PathBuf,
+    /// The root of the project after changes are applied, if it moves.
+    pub new_project_root: Option<PathBuf>,
+    /// The old name, passed to replacement rules and hooks. Empty for
+    /// workflows that add something.
+    pub old_name: String,
+    /// The new name, passed to replacement rules and hooks. Empty for
+    /// workflows that remove something.
+    pub new_name: String,
+    /// Description of the run stored in its backup manifest.
+    pub description: String,
+    /// Subtrees of the project captured when snapshot mode is enabled.
+    pub snapshot_subtrees: Vec<PathBuf>,
+    /// Where and how to store backups.
+    pub backup: BackupOptions,
+    /// Message reported once all changes were applied.
+    pub success_message: String,
+    /// Message reported once all changes were reverted.
+    pub failure_message: String,
+    /// Reporter receiving everything that happens during the run.
+    pub reporter: Rc<dyn Reporter>,
+}
+
+/// Apply the changeset of a run, preceded by the extra changes configured
+/// for the workflow, with hooks around it and every change backed up. If
+/// applying changes or a `post_apply` hook fails, all changes are reverted,
+/// falling back to the snapshot of the run if reverting fails.
+pub(crate) fn run_changeset(
+    run: &Run,
+    config: &Config,
+    settings: &WorkflowConfig,
+    changeset: Vec<Change>,
+) -> Result<(), Error> {
+    let mut changes =
+        config.extra_changes(&run.project_root, settings, &run.old_name, &run.new_name)?;
+    changes.extend(changeset);
+    let reporter = &*run.reporter;
+    let changeset = config.filter_changeset(&run.project_root, changes, reporter);
+    let hooks = HookRunner {
+        hooks: config.hooks.clone(),
+        workflow: run.workflow.into(),
+        old_name: run.old_name.clone(),
+        new_name: run.new_name.clone(),
+        project_root: run.project_root.clone(),
+        new_project_root: run.new_project_root.clone(),
+        reporter: run.reporter.clone(),
+    };
+    hooks.run(Hook::PreApply)?;
+    let backup = run.backup.resolve(&run.project_root)?;
+    let backup_dir = create_backup_dir(
+        &run.project_root,
+        &backup,
+        run.workflow,
+        &run.description,
+        reporter,
+    )?;
+    if backup.snapshot {
+        create_snapshot(
+            &run.project_root,
+            &run.snapshot_subtrees,
+            &changeset,
+            &backup_dir,
+            reporter,
+        )?;
+    }
+
+    let mut engine = Engine::with_reporter(run.reporter.clone());
+    let failure = match engine.execute(changeset, &backup_dir) {
+        Err(e) => e,
+        Ok(()) => match hooks.run(Hook::PostApply) {
+            Ok(()) => {
+                finalize_backup_dir(&relocated_backup_dir(run, &backup_dir), &backup, reporter)?;
+                log::success(reporter, &run.success_message);
+                return Ok(());
+            }
+            Err(e) if !config.hooks.revert_on_post_apply_failure => {
+                finalize_backup_dir(&relocated_backup_dir(run, &backup_dir), &backup, reporter)?;
+                log::success(reporter, &run.success_message);
+                return Err(Error::Other(format!("{}; changes were kept", e)));
+            }
+            Err(e) => e,
+        },
+    };
+
+    log::error(reporter, &failure);
+    revert(run, &mut engine, &backup, &backup_dir)?;
+    hooks.run_post_revert();
+    // The project was restored, which matters more than storing the backup run.
+    if let Err(e) = finalize_backup_dir(&backup_dir, &backup, reporter) {
+        log::error(reporter, e);
+    }
+    log::error(reporter, &run.failure_message);
+    Err(Error::RolledBack(failure))
+}
+
+/// Revert all applied changes. If that fails and snapshot mode is enabled,
+/// the snapshot of the run is restored instead.
+fn revert(
+    run: &Run,
+    engine: &mut Engine,
+    backup: &BackupOptions,
+    backup_dir: &Path,
+) -> Result<(), Error> {
+    let reporter = &*run.reporter;
+    let revert_error = match engine.revert() {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+    if !backup.snapshot {
+        return Err(Error::RollbackFailed(revert_error));
+    }
+
+    log::error(reporter, &revert_error);
+    log::header(reporter, "Restoring snapshot");
+    // The failed revert may have left the project root renamed, along with
+    // the backup run if it is stored within the project.
+    let backup_dir = match run.project_root.is_dir() {
+        true => backup_dir.to_owned(),
+        false => relocated_backup_dir(run, backup_dir),
+    };
+    restore_snapshot(&run.project_root, &backup_dir, reporter).map_err(Error::RollbackFailed)
+}
+
+/// The location of the backup run once all changes were applied, which
+/// moves along with the project root if the run is stored within it.
+fn relocated_backup_dir(run: &Run, backup_dir: &Path) -> PathBuf {
+    match (&run.new_project_root, backup_dir.strip_prefix(&run.project_root)) {
+        (Some(new_project_root), Ok(relative)) => new_project_root.join(relative),
+        _ => backup_dir.to_owned(),
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use renom::{
+    backup::BackupOptions,
+    descriptor::{add_entry, module_entry},
+    presentation::reporter::SilentReporter,
+    workflows::{add_module as add_module_workflow, add_module::Params},
+};
+
+use common::stage_project;
+
+fn params(project_root: &Path, name: &str) -> Params {
+    Params {
+        project_root: project_root.to_owned(),
+        name: name.into(),
+        plugin: None,
+        module_type: "Runtime".into(),
+        loading_phase: "Default".into(),
+        add_to_targets: false,
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn add_module_should_scaffold_module_and_list_it() {
+    let project_root = stage_project("add_module", "project");
+
+    let params = Params {
+        module_type: "Editor".into(),
+        add_to_targets: true,
+        ..params(&project_root, "CodeEditor")
+    };
+    add_module_workflow(params, Rc::new(SilentReporter)).unwrap();
+
+    let module_root = project_root.join("Source/CodeEditor");
+    let build_file = fs::read_to_string(module_root.join("CodeEditor.Build.cs")).unwrap();
+    assert!(build_file.contains("public class CodeEditor : ModuleRules"));
+    assert!(build_file.contains(r#"PrivateDependencyModuleNames.AddRange(new string[] { "UnrealEd" });"#));
+    assert!(fs::read_to_string(module_root.join("Public/CodeEditor.h"))
+        .unwrap()
+        .contains("class FCodeEditorModule : public IModuleInterface"));
+    assert!(fs::read_to_string(module_root.join("Private/CodeEditor.cpp"))
+        .unwrap()
+        .contains("IMPLEMENT_MODULE(FCodeEditorModule, CodeEditor)"));
+    assert!(fs::read_to_string(project_root.join("Project.uproject")).unwrap().contains(
+        "\t\t},\n\t\t{\n\t\t\t\"Name\": \"CodeEditor\",\n\t\t\t\"Type\": \"Editor\",\n\t\t\t\"LoadingPhase\": \"Default\"\n\t\t}\n\t]"
+    ));
+    // Editor modules are only added to editor targets.
+    assert!(fs::read_to_string(project_root.join("Source/Code.Target.cs"))
+        .unwrap()
+        .contains(r#"ExtraModuleNames.AddRange(new string[] { "Code" });"#));
+    assert!(fs::read_to_string(project_root.join("Source/CodeEditor.Target.cs"))
+        .unwrap()
+        .contains("ExtraModuleNames.Add(\"Code\");\n\t\tExtraModuleNames.Add(\"CodeEditor\");"));
+}
+
+#[test]
+fn add_module_should_add_plugin_modules_to_plugin_descriptor() {
+    let project_root = stage_project("add_module", "plugin");
+
+    let params = Params {
+        plugin: Some("Tools".into()),
+        ..params(&project_root, "ToolsRuntime")
+    };
+    add_module_workflow(params, Rc::new(SilentReporter)).unwrap();
+
+    assert!(project_root.join("Plugins/Tools/Source/ToolsRuntime/ToolsRuntime.Build.cs").is_file());
+    assert_eq!(
+        fs::read_to_string(project_root.join("Plugins/Tools/Tools.uplugin")).unwrap(),
+        "{\n    \"FileVersion\": 3,\n    \"Modules\": [\n        {\n            \"Name\": \"ToolsRuntime\",\n            \"Type\": \"Runtime\",\n            \"LoadingPhase\": \"Default\"\n        }\n    ]\n}\n"
+    );
+}
+
+#[test]
+fn add_module_should_reject_existing_module_names() {
+    let project_root = stage_project("add_module", "existing");
+
+    assert!(add_module_workflow(params(&project_root, "Code"), Rc::new(SilentReporter)).is_err());
+}
+
+#[test]
+fn add_module_should_run_hooks_and_config_rules() {
+    let project_root = stage_project("add_module", "config");
+    fs::create_dir_all(project_root.join(".renom")).unwrap();
+    fs::write(project_root.join("Modules.txt"), "Code\n").unwrap();
+    fs::write(
+        project_root.join(".renom/config.toml"),
+        r#"[hooks]
+post_apply = ["echo $RENOM_NEW_NAME > hook.txt"]
+
+[[workflows.add_module.rules]]
+files = ["Modules.txt"]
+pattern = "Code\n$"
+replacement = "Code\n{new}\n"
+"#,
+    )
+    .unwrap();
+
+    add_module_workflow(params(&project_root, "Tools"), Rc::new(SilentReporter)).unwrap();
+
+    assert_eq!(fs::read_to_string(project_root.join("hook.txt")).unwrap(), "Tools\n");
+    assert_eq!(fs::read_to_string(project_root.join("Modules.txt")).unwrap(), "Code\nTools\n");
+}
+
+#[test]
+fn descriptor_add_entry_should_reject_listed_modules() {
+    let descriptor = r#"{ "Modules": [ { "Name": "Code" } ] }"#;
+
+    assert!(add_entry(descriptor, "Modules", "Code", &module_entry("Code", "Runtime", "Default")).is_err());
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use renom::{
+    backup::BackupOptions,
+    presentation::reporter::SilentReporter,
+    workflows::{add_plugin, add_plugin::Params},
+};
+use serde_json::Value;
+
+use common::stage_project;
+
+fn params(project_root: &Path, name: &str) -> Params {
+    Params {
+        project_root: project_root.to_owned(),
+        name: name.into(),
+        module: None,
+        module_type: "Runtime".into(),
+        content: false,
+        icon: false,
+        enable: false,
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn add_plugin_should_scaffold_plugin_with_initial_module() {
+    let project_root = stage_project("add_plugin", "scaffold");
+
+    let params = Params {
+        content: true,
+        icon: true,
+        enable: true,
+        ..params(&project_root, "Tools")
+    };
+    add_plugin(params, Rc::new(SilentReporter)).unwrap();
+
+    let plugin_root = project_root.join("Plugins/Tools");
+    let descriptor: Value =
+        serde_json::from_str(&fs::read_to_string(plugin_root.join("Tools.uplugin")).unwrap()).unwrap();
+    assert_eq!(descriptor["FriendlyName"], "Tools");
+    assert_eq!(descriptor["CanContainContent"], true);
+    assert_eq!(descriptor["Modules"][0]["Name"], "Tools");
+    assert_eq!(descriptor["Modules"][0]["Type"], "Runtime");
+    assert!(plugin_root.join("Source/Tools/Tools.Build.cs").is_file());
+    assert!(fs::read_to_string(plugin_root.join("Source/Tools/Private/Tools.cpp"))
+        .unwrap()
+        .contains("IMPLEMENT_MODULE(FToolsModule, Tools)"));
+    assert!(plugin_root.join("Content").is_dir());
+    assert!(fs::read(plugin_root.join("Resources/Icon128.png")).unwrap().starts_with(b"\x89PNG"));
+    assert!(fs::read_to_string(project_root.join("Project.uproject")).unwrap().contains(
+        "\t\t},\n\t\t{\n\t\t\t\"Name\": \"Tools\",\n\t\t\t\"Enabled\": true\n\t\t}\n\t]"
+    ));
+}
+
+#[test]
+fn add_plugin_should_name_initial_module_independently() {
+    let project_root = stage_project("add_plugin", "module");
+
+    let params = Params {
+        module: Some("ToolsEditor".into()),
+        module_type: "Editor".into(),
+        ..params(&project_root, "Tools")
+    };
+    add_plugin(params, Rc::new(SilentReporter)).unwrap();
+
+    let plugin_root = project_root.join("Plugins/Tools");
+    assert!(plugin_root.join("Source/ToolsEditor/ToolsEditor.Build.cs").is_file());
+    assert!(!plugin_root.join("Content").exists());
+    assert!(!plugin_root.join("Resources").exists());
+    let descriptor: Value =
+        serde_json::from_str(&fs::read_to_string(plugin_root.join("Tools.uplugin")).unwrap()).unwrap();
+    assert_eq!(descriptor["CanContainContent"], false);
+    assert_eq!(descriptor["Modules"][0]["Name"], "ToolsEditor");
+    assert_eq!(descriptor["Modules"][0]["Type"], "Editor");
+}
+
+#[test]
+fn add_plugin_should_reject_module_names_in_use() {
+    let project_root = stage_project("add_plugin", "conflict");
+
+    let params = Params {
+        module: Some("Code".into()),
+        ..params(&project_root, "Tools")
+    };
+    assert!(add_plugin(params, Rc::new(SilentReporter)).is_err());
+    assert!(!project_root.join("Plugins/Tools").exists());
+}
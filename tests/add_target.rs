@@ -0,0 +1,88 @@
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use renom::{
+    backup::BackupOptions,
+    presentation::reporter::SilentReporter,
+    workflows::{add_target, add_target::Params},
+};
+
+use common::stage_project;
+
+fn params(project_root: &Path, name: &str) -> Params {
+    Params {
+        project_root: project_root.to_owned(),
+        name: name.into(),
+        target_type: None,
+        from: None,
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn add_target_should_list_modules_built_for_target_type() {
+    let project_root = stage_project("add_target", "template");
+
+    let client = Params {
+        target_type: Some("Client".into()),
+        ..params(&project_root, "CodeClient")
+    };
+    add_target(client, Rc::new(SilentReporter)).unwrap();
+    let editor = Params {
+        target_type: Some("Editor".into()),
+        ..params(&project_root, "CodeEditor")
+    };
+    add_target(editor, Rc::new(SilentReporter)).unwrap();
+
+    let client = fs::read_to_string(project_root.join("Source/CodeClient.Target.cs")).unwrap();
+    assert!(client.contains("public class CodeClientTarget : TargetRules"));
+    assert!(client.contains("public CodeClientTarget(TargetInfo Target) : base(Target)"));
+    assert!(client.contains("Type = TargetType.Client;"));
+    assert!(client.contains("DefaultBuildSettings = BuildSettingsVersion.Latest;"));
+    assert!(client.contains("IncludeOrderVersion = EngineIncludeOrderVersion.Latest;"));
+    assert!(client.contains(r#"ExtraModuleNames.AddRange(new string[] { "Code" });"#));
+    let editor = fs::read_to_string(project_root.join("Source/CodeEditor.Target.cs")).unwrap();
+    assert!(editor.contains(
+        r#"ExtraModuleNames.AddRange(new string[] { "Code", "CodeEditor", "CodeServer" });"#
+    ));
+}
+
+#[test]
+fn add_target_should_clone_existing_target() {
+    let project_root = stage_project("add_target", "clone");
+
+    let params = Params {
+        target_type: Some("Server".into()),
+        from: Some("Code".into()),
+        ..params(&project_root, "CodeServer")
+    };
+    add_target(params, Rc::new(SilentReporter)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/CodeServer.Target.cs")).unwrap(),
+        r#"public class CodeServerTarget : TargetRules
+{
+	public CodeServerTarget(TargetInfo Target) : base(Target)
+	{
+		Type = TargetType.Server;
+		ExtraModuleNames.Add("Code");
+	}
+}"#
+    );
+}
+
+#[test]
+fn add_target_should_reject_unknown_target_types() {
+    let project_root = stage_project("add_target", "unknown_type");
+
+    let params = Params {
+        target_type: Some("Dedicated".into()),
+        ..params(&project_root, "CodeDedicated")
+    };
+    assert!(add_target(params, Rc::new(SilentReporter)).is_err());
+}
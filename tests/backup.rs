@@ -0,0 +1,166 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use renom::{
+    backup::{self, BackupOptions, Manifest},
+    changes::{Change, ReplaceInFile},
+    presentation::reporter::SilentReporter,
+};
+
+use common::fresh_dir;
+
+fn stage_project(name: &str) -> PathBuf {
+    let staging_dir = fresh_dir("backup", name);
+    fs::create_dir_all(staging_dir.join("Config")).unwrap();
+    fs::write(staging_dir.join("Config/DefaultGame.ini"), "Name=Old\n").unwrap();
+    fs::write(staging_dir.join("Project.uproject"), "{}").unwrap();
+    staging_dir
+}
+
+#[test]
+fn backup_should_record_original_files_in_manifest() {
+    let project_root = stage_project("record_original_files");
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+
+    let config = project_root.join("Config/DefaultGame.ini");
+    let change = Change::ReplaceInFile(ReplaceInFile::new(&config, "Old", "New"));
+    let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+    let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+
+    let manifest = Manifest::load(&backup_dir).unwrap();
+    assert_eq!(manifest.workflow, "test");
+    assert_eq!(manifest.files.len(), 2);
+    let originals = manifest.original_entries();
+    assert_eq!(originals.len(), 1);
+    assert_eq!(originals[0].original, PathBuf::from("Config/DefaultGame.ini"));
+}
+
+#[test]
+fn restore_run_should_restore_original_content() {
+    let project_root = stage_project("restore_run");
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+
+    let config = project_root.join("Config/DefaultGame.ini");
+    let change = Change::ReplaceInFile(ReplaceInFile::new(&config, "Old", "New"));
+    let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+    assert_eq!(fs::read_to_string(&config).unwrap(), "Name=New\n");
+
+    let options = BackupOptions::default();
+    let id = Manifest::load(&backup_dir).unwrap().id;
+    let run = backup::find_run(&project_root, &options, &id).unwrap();
+    backup::restore_run(&project_root, &options, &run, &SilentReporter).unwrap();
+    assert_eq!(fs::read_to_string(&config).unwrap(), "Name=Old\n");
+}
+
+#[test]
+fn prune_should_keep_most_recent_runs() {
+    let project_root = stage_project("prune_keep");
+    for _ in 0..3 {
+        backup::create_backup_dir(
+            &project_root,
+            &BackupOptions::default(),
+            "test",
+            "test run",
+            &SilentReporter,
+        ).unwrap();
+    }
+    let options = BackupOptions::default();
+    let newest = backup::list_runs(&project_root, &options).unwrap().pop().unwrap();
+
+    let pruned = backup::prune(&project_root, &options, None, Some(1), &SilentReporter).unwrap();
+    assert_eq!(pruned.len(), 2);
+    assert_eq!(backup::list_runs(&project_root, &options).unwrap(), vec![newest]);
+}
+
+#[test]
+fn prune_should_keep_runs_when_cutoff_is_out_of_range() {
+    let project_root = stage_project("prune_out_of_range");
+    backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+    let options = BackupOptions::default();
+
+    let pruned =
+        backup::prune(&project_root, &options, Some(u32::MAX), None, &SilentReporter).unwrap();
+    assert!(pruned.is_empty());
+    assert_eq!(backup::list_runs(&project_root, &options).unwrap().len(), 1);
+}
+
+#[test]
+fn compressed_backup_outside_project_should_be_restorable() {
+    let project_root = stage_project("compressed_outside_project");
+    let options = BackupOptions {
+        dir: Some(PathBuf::from("tests/temp/backup/compressed_outside_project_backups")),
+        compress: true,
+        snapshot: false,
+    };
+    if let Some(dir) = options.dir.as_ref().filter(|dir| dir.is_dir()) {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &options,
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+    let id = fs::read_to_string(project_root.join(".renom/id")).unwrap();
+    assert!(backup_dir
+        .starts_with(PathBuf::from("tests/temp/backup/compressed_outside_project_backups").join(id.trim())));
+
+    let config = project_root.join("Config/DefaultGame.ini");
+    let change = Change::ReplaceInFile(ReplaceInFile::new(&config, "Old", "New"));
+    let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+    backup::finalize_backup_dir(&backup_dir, &options, &SilentReporter).unwrap();
+    assert!(!backup_dir.exists());
+    assert!(backup_dir.with_extension("tar.gz").is_file());
+
+    let runs = backup::list_runs(&project_root, &options).unwrap();
+    assert_eq!(runs.len(), 1);
+    backup::restore_file(
+        &project_root,
+        &options,
+        &runs[0],
+        "Config/DefaultGame.ini".as_ref(),
+        &SilentReporter,
+    )
+    .unwrap();
+    assert_eq!(fs::read_to_string(&config).unwrap(), "Name=Old\n");
+}
+
+#[test]
+fn backups_outside_project_should_be_separate_for_projects_with_same_name() {
+    let first = stage_project("same_name_first");
+    let second = stage_project("same_name_second");
+    let options = BackupOptions {
+        dir: Some(PathBuf::from("tests/temp/backup/same_name_backups")),
+        compress: false,
+        snapshot: false,
+    };
+    if let Some(dir) = options.dir.as_ref().filter(|dir| dir.is_dir()) {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    backup::create_backup_dir(&first, &options, "test", "first run", &SilentReporter).unwrap();
+    backup::create_backup_dir(&second, &options, "test", "second run", &SilentReporter).unwrap();
+
+    let runs = backup::list_runs(&first, &options).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].description, "first run");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::{fs, path::PathBuf, rc::Rc};
+
+use renom::{
+    changes::{Change, CopyDir, CopyFile, CreateDir, CreateFile, DeleteFile},
+    engine::Engine,
+    presentation::reporter::SilentReporter,
+};
+
+use common::fresh_dir;
+
+fn stage(name: &str) -> PathBuf {
+    let staging_dir = fresh_dir("changes", name);
+    fs::create_dir_all(staging_dir.join("Source/Game")).unwrap();
+    fs::write(staging_dir.join("Source/Game/Game.cpp"), "// Game").unwrap();
+    fs::write(staging_dir.join("Source/Game/Game.h"), "#pragma once").unwrap();
+    staging_dir
+}
+
+#[test]
+fn create_changes_should_be_removed_on_revert() {
+    let root = stage("create");
+    let backup_dir = root.join("backup");
+    fs::create_dir_all(&backup_dir).unwrap();
+
+    let mut engine = Engine::with_reporter(Rc::new(SilentReporter));
+    engine
+        .execute(
+            vec![
+                Change::CreateDir(CreateDir::new(root.join("Plugins/Tools"))),
+                Change::CreateFile(CreateFile::new(root.join("Source/Tools/Tools.h"), "#pragma once")),
+                Change::CopyFile(CopyFile::new(root.join("Source/Game/Game.h"), root.join("Source/Game/Copy.h"))),
+                Change::CopyDir(CopyDir::new(root.join("Source/Game"), root.join("Source/Editor/Game"))),
+            ],
+            &backup_dir,
+        )
+        .unwrap();
+    assert!(root.join("Plugins/Tools").is_dir());
+    assert_eq!(fs::read_to_string(root.join("Source/Tools/Tools.h")).unwrap(), "#pragma once");
+    assert_eq!(fs::read_to_string(root.join("Source/Game/Copy.h")).unwrap(), "#pragma once");
+    assert_eq!(fs::read_to_string(root.join("Source/Editor/Game/Game.cpp")).unwrap(), "// Game");
+
+    engine.revert().unwrap();
+    assert!(!root.join("Plugins").exists());
+    assert!(!root.join("Source/Tools").exists());
+    assert!(!root.join("Source/Game/Copy.h").exists());
+    assert!(!root.join("Source/Editor").exists());
+    assert!(root.join("Source/Game/Game.h").is_file());
+}
+
+#[test]
+fn create_changes_should_not_overwrite_existing_paths() {
+    let root = stage("no_overwrite");
+
+    let change = Change::CreateFile(CreateFile::new(root.join("Source/Game/Game.h"), "overwritten"));
+    assert!(change.apply(&root, &SilentReporter).is_err());
+    let change = Change::CopyFile(CopyFile::new(root.join("Source/Game/Game.cpp"), root.join("Source/Game/Game.h")));
+    assert!(change.apply(&root, &SilentReporter).is_err());
+    assert_eq!(fs::read_to_string(root.join("Source/Game/Game.h")).unwrap(), "#pragma once");
+}
+
+#[test]
+fn delete_file_should_be_restored_on_revert() {
+    let root = stage("delete");
+    let backup_dir = root.join("backup");
+    fs::create_dir_all(&backup_dir).unwrap();
+
+    let mut engine = Engine::with_reporter(Rc::new(SilentReporter));
+    engine
+        .execute(vec![Change::DeleteFile(DeleteFile::new(root.join("Source/Game/Game.cpp")))], &backup_dir)
+        .unwrap();
+    assert!(!root.join("Source/Game/Game.cpp").exists());
+
+    engine.revert().unwrap();
+    assert_eq!(fs::read_to_string(root.join("Source/Game/Game.cpp")).unwrap(), "// Game");
+}
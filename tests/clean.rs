@@ -0,0 +1,142 @@
+use std::{fs, path::PathBuf, rc::Rc};
+
+use renom::{
+    artifacts::{detect_artifacts, detect_stale_artifacts, generate_clean_changeset},
+    backup::{self, BackupOptions, Manifest},
+    engine::Engine,
+    presentation::reporter::SilentReporter,
+    workflows::{clean, clean::Params},
+};
+
+fn stage_project(name: &str) -> PathBuf {
+    let staging_dir = PathBuf::from("tests/temp/clean").join(name);
+    if staging_dir.is_dir() {
+        fs::remove_dir_all(&staging_dir).unwrap();
+    }
+    fs::create_dir_all(staging_dir.join("Binaries/Win64")).unwrap();
+    fs::create_dir_all(staging_dir.join("Intermediate/Build")).unwrap();
+    fs::create_dir_all(staging_dir.join("Plugins/Tools/Intermediate")).unwrap();
+    fs::create_dir_all(staging_dir.join("Source")).unwrap();
+    fs::write(staging_dir.join("Binaries/Win64/Project.dll"), "dll").unwrap();
+    fs::write(staging_dir.join("Intermediate/Build/Project.target"), "target").unwrap();
+    fs::write(staging_dir.join("Plugins/Tools/Tools.uplugin"), "{}").unwrap();
+    fs::write(staging_dir.join("Plugins/Tools/Intermediate/Tools.json"), "{}").unwrap();
+    fs::write(staging_dir.join("Project.sln"), "sln").unwrap();
+    fs::write(staging_dir.join("Project.uproject"), "{}").unwrap();
+    staging_dir
+}
+
+#[test]
+fn detect_artifacts_should_find_generated_files_of_project_and_plugins() {
+    let project_root = stage_project("detect_artifacts");
+
+    let artifacts = detect_artifacts(&project_root);
+    assert_eq!(
+        artifacts,
+        vec![
+            project_root.join("Binaries"),
+            project_root.join("Intermediate"),
+            project_root.join("Plugins/Tools/Intermediate"),
+            project_root.join("Project.sln"),
+        ]
+    );
+}
+
+#[test]
+fn clean_changeset_should_move_artifacts_to_backup_and_revert() {
+    let project_root = stage_project("move_to_backup");
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+
+    let mut engine = Engine::with_reporter(Rc::new(SilentReporter));
+    engine.execute(generate_clean_changeset(&project_root), &backup_dir).unwrap();
+    assert!(!project_root.join("Binaries").exists());
+    assert!(!project_root.join("Intermediate").exists());
+    assert!(!project_root.join("Plugins/Tools/Intermediate").exists());
+    assert!(!project_root.join("Project.sln").exists());
+    assert!(project_root.join("Project.uproject").is_file());
+
+    let manifest = Manifest::load(&backup_dir).unwrap();
+    assert_eq!(manifest.moved.len(), 4);
+    for entry in &manifest.moved {
+        assert!(backup_dir.join(&entry.blob).exists());
+    }
+
+    engine.revert().unwrap();
+    assert_eq!(fs::read_to_string(project_root.join("Binaries/Win64/Project.dll")).unwrap(), "dll");
+    assert!(project_root.join("Intermediate/Build/Project.target").is_file());
+    assert!(project_root.join("Plugins/Tools/Intermediate/Tools.json").is_file());
+    assert!(project_root.join("Project.sln").is_file());
+}
+
+#[test]
+fn restore_run_should_restore_moved_artifacts() {
+    let project_root = stage_project("restore_moved");
+    let options = BackupOptions::default();
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &options,
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+
+    let mut engine = Engine::with_reporter(Rc::new(SilentReporter));
+    engine.execute(generate_clean_changeset(&project_root), &backup_dir).unwrap();
+    assert!(!project_root.join("Intermediate").exists());
+
+    let id = Manifest::load(&backup_dir).unwrap().id;
+    let run = backup::find_run(&project_root, &options, &id).unwrap();
+    backup::restore_run(&project_root, &options, &run, &SilentReporter).unwrap();
+    assert!(project_root.join("Intermediate/Build/Project.target").is_file());
+    assert!(project_root.join("Binaries/Win64/Project.dll").is_file());
+}
+
+#[test]
+fn detect_stale_artifacts_should_only_find_artifacts_named_after_old_name() {
+    let project_root = stage_project("detect_stale_artifacts");
+    fs::create_dir_all(project_root.join("Intermediate/Build/Win64/ProjectEditor")).unwrap();
+    fs::create_dir_all(project_root.join("Intermediate/ProjectFiles")).unwrap();
+    fs::write(project_root.join("Binaries/Win64/UnrealEditor-Project.dll"), "dll").unwrap();
+    fs::write(project_root.join("Binaries/Win64/UnrealEditor-Projectile.dll"), "dll").unwrap();
+    fs::write(project_root.join("Intermediate/ProjectFiles/ProjectEditor.vcxproj"), "").unwrap();
+    fs::write(project_root.join("Intermediate/ProjectFiles/Other.vcxproj"), "").unwrap();
+    fs::write(project_root.join("Other.sln"), "sln").unwrap();
+
+    let artifacts = detect_stale_artifacts(&project_root, "Project");
+    assert_eq!(
+        artifacts,
+        vec![
+            project_root.join("Binaries/Win64/Project.dll"),
+            project_root.join("Binaries/Win64/UnrealEditor-Project.dll"),
+            project_root.join("Intermediate/Build/Project.target"),
+            project_root.join("Intermediate/Build/Win64/ProjectEditor"),
+            project_root.join("Intermediate/ProjectFiles/ProjectEditor.vcxproj"),
+            project_root.join("Project.sln"),
+        ]
+    );
+}
+
+#[test]
+fn clean_should_run_hooks_around_moving_artifacts() {
+    let project_root = stage_project("hooks");
+    fs::create_dir_all(project_root.join(".renom")).unwrap();
+    fs::write(
+        project_root.join(".renom/config.toml"),
+        "[hooks]\npre_apply = [\"exit 1\"]\n",
+    )
+    .unwrap();
+
+    let params = Params {
+        project_root: project_root.clone(),
+        backup: BackupOptions::default(),
+    };
+    assert!(clean(params, Rc::new(SilentReporter)).is_err());
+    assert!(project_root.join("Binaries/Win64/Project.dll").is_file());
+    assert!(project_root.join("Project.sln").is_file());
+}
@@ -0,0 +1,34 @@
+// Each test crate includes this module but uses only some of its helpers.
+#![allow(dead_code)]
+
+use std::{fs, path::PathBuf};
+
+use walkdir::WalkDir;
+
+/// Create an empty staging directory `tests/temp/<group>/<name>`, removing
+/// what a previous run left behind, and return its path.
+pub fn fresh_dir(group: &str, name: &str) -> PathBuf {
+    let dir = PathBuf::from("tests/temp").join(group).join(name);
+    if dir.is_dir() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Copy the project in `tests/resources/<project>` to a fresh staging
+/// directory `tests/temp/<project>/<name>` and return its path.
+pub fn stage_project(project: &str, name: &str) -> PathBuf {
+    let original_root = PathBuf::from("tests/resources").join(project);
+    let project_root = fresh_dir(project, name);
+    for entry in WalkDir::new(&original_root).into_iter().map(Result::unwrap) {
+        let path = project_root.join(entry.path().strip_prefix(&original_root).unwrap());
+        match entry.file_type().is_dir() {
+            true => fs::create_dir_all(path).unwrap(),
+            false => {
+                fs::copy(entry.path(), path).unwrap();
+            }
+        }
+    }
+    project_root
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use renom::{
+    changes::{Change, RenameFile, ReplaceInFile},
+    config::{Config, VcsMode},
+    presentation::{log::OutputFormat, reporter::SilentReporter},
+};
+
+use common::fresh_dir;
+
+fn stage_project(name: &str, config: &str) -> PathBuf {
+    let staging_dir = fresh_dir("config", name);
+    fs::create_dir_all(staging_dir.join(".renom")).unwrap();
+    fs::create_dir_all(staging_dir.join("Build/Generated")).unwrap();
+    fs::write(staging_dir.join(".renom/config.toml"), config).unwrap();
+    fs::write(staging_dir.join("Build/Graph.xml"), "Old").unwrap();
+    fs::write(staging_dir.join("Build/Generated/Graph.xml"), "Old").unwrap();
+    staging_dir
+}
+
+#[test]
+fn missing_config_should_load_as_default() {
+    let project_root = fresh_dir("config", "missing");
+    assert_eq!(Config::load(&project_root).unwrap(), Config::default());
+}
+
+#[test]
+fn config_should_load_all_sections() {
+    let project_root = stage_project(
+        "all_sections",
+        r#"
+        output = "json"
+        ignore = ["Build/Generated/**"]
+
+        [backup]
+        compress = true
+
+        [vcs]
+        mode = "git"
+
+        [validation]
+        max_path_length = 200
+        allow_reserved_names = true
+
+        [workflows.rename_module]
+        files = ["Build/**/*.xml"]
+        "#,
+    );
+
+    let config = Config::load(&project_root).unwrap();
+    assert_eq!(config.output, Some(OutputFormat::Json));
+    assert!(config.backup.compress);
+    assert_eq!(config.vcs.mode, VcsMode::Git);
+    assert_eq!(config.validation.max_path_length, Some(200));
+    assert!(config.validation.allow_reserved_names);
+    assert_eq!(
+        config
+            .extra_files(&project_root, &config.workflows.rename_module)
+            .unwrap(),
+        vec![project_root.join("Build/Graph.xml")]
+    );
+    assert!(config
+        .extra_files(&project_root, &config.workflows.rename_plugin)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn filter_changeset_should_drop_edits_of_ignored_files_only() {
+    let project_root = stage_project("filter_changeset", r#"ignore = ["Build/Generated/**"]"#);
+    let config = Config::load(&project_root).unwrap();
+    let ignored = project_root.join("Build/Generated/Graph.xml");
+    let changeset = vec![
+        Change::ReplaceInFile(ReplaceInFile::new(&ignored, "Old", "New")),
+        Change::ReplaceInFile(ReplaceInFile::new(project_root.join("Build/Graph.xml"), "Old", "New")),
+        Change::RenameFile(RenameFile::new(&ignored, project_root.join("Build/Generated/New.xml"))),
+    ];
+
+    let filtered = config.filter_changeset(&project_root, changeset, &SilentReporter);
+    assert_eq!(filtered.len(), 2);
+    assert!(filtered.iter().all(|change| change.kind() == "rename_file"
+        || change.paths() != vec![ignored.as_path()]));
+}
+
+#[test]
+fn invalid_config_should_be_reported() {
+    let project_root = stage_project("invalid", "output = 1");
+    assert!(Config::load(&project_root).is_err());
+}
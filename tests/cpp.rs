@@ -0,0 +1,71 @@
+use renom::cpp::{ImplementMacro, ImplementMacroError, ImplementMacroKind};
+
+#[test]
+fn implement_macro_should_skip_comments_and_other_macros() {
+    let implement_macro = ImplementMacro::find(
+        r#"#include "Modules/ModuleManager.h"
+// IMPLEMENT_MODULE(FDefaultModuleImpl, Commented)
+/* IMPLEMENT_GAME_MODULE(FDefaultModuleImpl, Commented) */
+DECLARE_LOG_CATEGORY_EXTERN(LogCode, Log, All);
+const TCHAR* Name = TEXT("IMPLEMENT_MODULE(FDefaultModuleImpl, Quoted)");
+MY_IMPLEMENT_MODULE(FDefaultModuleImpl, Prefixed);
+IMPLEMENT_PRIMARY_GAME_MODULE( FDefaultGameModuleImpl, Code, "Code" );
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(implement_macro.kind, ImplementMacroKind::PrimaryGameModule);
+    assert_eq!(implement_macro.implementation(), "FDefaultGameModuleImpl");
+    assert_eq!(implement_macro.module_name(), "Code");
+    assert_eq!(
+        implement_macro.renamed("Game"),
+        r#"IMPLEMENT_PRIMARY_GAME_MODULE( FDefaultGameModuleImpl, Game, "Game" )"#
+    );
+}
+
+#[test]
+fn implement_macro_should_handle_multiline_arguments() {
+    let implement_macro = ImplementMacro::find(
+        "IMPLEMENT_MODULE(\n    FModule, // implementation\n    Code\n);\n",
+    )
+    .unwrap();
+
+    assert_eq!(implement_macro.kind, ImplementMacroKind::Module);
+    assert_eq!(implement_macro.module_name(), "Code");
+    assert_eq!(
+        implement_macro.renamed("Game"),
+        "IMPLEMENT_MODULE(\n    FModule, // implementation\n    Game\n)"
+    );
+}
+
+#[test]
+fn implement_macro_should_only_rename_matching_game_name() {
+    let implement_macro = ImplementMacro::find(
+        r#"IMPLEMENT_PRIMARY_GAME_MODULE(FDefaultGameModuleImpl, Code, "Shooter");"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        implement_macro.renamed("Game"),
+        r#"IMPLEMENT_PRIMARY_GAME_MODULE(FDefaultGameModuleImpl, Game, "Shooter")"#
+    );
+}
+
+#[test]
+fn implement_macro_should_report_missing_and_malformed_invocations() {
+    assert_eq!(
+        ImplementMacro::find("DECLARE_LOG_CATEGORY_EXTERN(LogCode, Log, All);"),
+        Err(ImplementMacroError::Missing)
+    );
+    assert_eq!(
+        ImplementMacro::find("IMPLEMENT_MODULE(FDefaultModuleImpl, Code"),
+        Err(ImplementMacroError::Unterminated)
+    );
+    assert_eq!(
+        ImplementMacro::find("IMPLEMENT_GAME_MODULE(FDefaultModuleImpl)"),
+        Err(ImplementMacroError::ArgumentCount {
+            expected: 2,
+            found: 1
+        })
+    );
+}
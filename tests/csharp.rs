@@ -0,0 +1,125 @@
+use renom::csharp::{
+    assigned_value, remove_from_lists, replace_property_references, replace_references, tokenize,
+    TokenKind,
+};
+
+const BUILD_FILE: &str = r#"using UnrealBuildTool;
+
+// Game depends on GameplayTags
+public class Game : ModuleRules
+{
+	public Game(ReadOnlyTargetRules Target) : base(Target)
+	{
+		PublicDependencyModuleNames.AddRange(new string[] { "Core", "GameplayTags", "GameplayAbilities" });
+		PrivateIncludePaths.Add(@"Game\Private");
+		/* "Game" */
+	}
+}
+"#;
+
+#[test]
+fn replace_references_should_only_replace_exact_identifiers() {
+    let content = replace_references(BUILD_FILE, "Game", "Shooter", true, false);
+
+    assert!(content.contains("public class Shooter : ModuleRules"));
+    assert!(content.contains("public Shooter(ReadOnlyTargetRules Target)"));
+    assert!(content.contains(r#""GameplayTags", "GameplayAbilities""#));
+    assert!(content.contains("// Game depends on GameplayTags"));
+    assert!(content.contains(r#"/* "Game" */"#));
+}
+
+#[test]
+fn replace_references_should_only_replace_exact_strings() {
+    let content = replace_references(
+        r#"ExtraModuleNames.AddRange(new string[] { "Game", "GameplayTags", @"Game", "Game.Build" });"#,
+        "Game",
+        "Shooter",
+        false,
+        true,
+    );
+
+    assert_eq!(
+        content,
+        r#"ExtraModuleNames.AddRange(new string[] { "Shooter", "GameplayTags", @"Shooter", "Game.Build" });"#
+    );
+}
+
+#[test]
+fn tokenize_should_skip_comments_and_handle_escapes() {
+    let source = "#if WITH_EDITOR\nvar a = \"Quote \\\" Game\"; // Game\nvar b = @\"Path \"\"Game\"\"\";\n#endif";
+    let strings: Vec<&str> = tokenize(source)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::String)
+        .map(|token| &source[token.content])
+        .collect();
+    let identifiers: Vec<&str> = tokenize(source)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Identifier)
+        .map(|token| &source[token.content])
+        .collect();
+
+    assert_eq!(strings, vec![r#"Quote \" Game"#, r#"Path ""Game"""#]);
+    assert_eq!(identifiers, vec!["var", "a", "var", "b"]);
+}
+
+#[test]
+fn replace_references_should_only_replace_strings_used_with_properties() {
+    let content = replace_property_references(
+        r#"ExtraModuleNames.AddRange(new string[] { "Game" });
+LaunchModuleName = "Game";
+if (Target.Name != "Game") { PublicDefinitions.Add("Game"); }"#,
+        "Game",
+        "Shooter",
+        &["ExtraModuleNames".into(), "LaunchModuleName".into(), "Name".into()],
+    );
+
+    assert_eq!(
+        content,
+        r#"ExtraModuleNames.AddRange(new string[] { "Shooter" });
+LaunchModuleName = "Shooter";
+if (Target.Name != "Shooter") { PublicDefinitions.Add("Game"); }"#
+    );
+}
+
+#[test]
+fn remove_from_lists_should_remove_elements_and_add_calls() {
+    let content = remove_from_lists(
+        r#"PublicDependencyModuleNames.AddRange(new string[] { "Core", "Game", "Engine" });
+PrivateDependencyModuleNames.AddRange(new string[] { "Slate", "Game" });
+DynamicallyLoadedModuleNames.Add("Game");
+PublicIncludePathModuleNames.AddRange(new string[] { "Game" });
+PublicDefinitions.Add("Game");
+LaunchModuleName = "Game";
+"#,
+        "Game",
+        &[
+            "PublicDependencyModuleNames".into(),
+            "PrivateDependencyModuleNames".into(),
+            "DynamicallyLoadedModuleNames".into(),
+            "PublicIncludePathModuleNames".into(),
+            "LaunchModuleName".into(),
+        ],
+    );
+
+    assert_eq!(
+        content,
+        r#"PublicDependencyModuleNames.AddRange(new string[] { "Core", "Engine" });
+PrivateDependencyModuleNames.AddRange(new string[] { "Slate" });
+PublicIncludePathModuleNames.AddRange(new string[] { });
+PublicDefinitions.Add("Game");
+LaunchModuleName = "Game";
+"#
+    );
+}
+
+#[test]
+fn assigned_value_should_skip_comments_and_comparisons() {
+    let content = r#"// Type = TargetType.Editor;
+if (Type == TargetType.Game) {}
+Type = TargetType
+	.Editor; /* Type = TargetType.Program; */
+"#;
+
+    assert_eq!(assigned_value(content, "Type").as_deref(), Some("TargetType.Editor"));
+    assert_eq!(assigned_value(content, "LinkType"), None);
+}
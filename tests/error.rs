@@ -0,0 +1,39 @@
+use std::{path::PathBuf, rc::Rc};
+
+use renom::{
+    backup::BackupOptions,
+    error::Error,
+    presentation::reporter::SilentReporter,
+    workflows::{rename_project, rename_project::Params},
+};
+
+#[test]
+fn invalid_params_should_fail_with_validation_exit_code() {
+    let params = Params {
+        project_root: PathBuf::from("tests/temp/error/missing_project"),
+        new_name: "Game".into(),
+        clean: false,
+        backup: BackupOptions::default(),
+    };
+    let result = rename_project(params, Rc::new(SilentReporter));
+
+    let error = result.unwrap_err();
+    assert!(matches!(error, Error::Validation(_)));
+    assert_eq!(error.exit_code(), 2);
+}
+
+#[test]
+fn exit_codes_should_be_distinct_per_failure_class() {
+    let errors = [
+        Error::Other(String::new()),
+        Error::Validation(String::new()),
+        Error::Detection(String::new()),
+        Error::RolledBack(String::new()),
+        Error::RollbackFailed(String::new()),
+    ];
+    let mut codes: Vec<i32> = errors.iter().map(Error::exit_code).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), errors.len());
+    assert!(!codes.contains(&0));
+}
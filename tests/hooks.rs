@@ -0,0 +1,68 @@
+use std::{fs, path::PathBuf, rc::Rc};
+
+use renom::{
+    hooks::{Hook, HookRunner, HooksConfig},
+    presentation::reporter::{CollectingReporter, Event, SilentReporter},
+};
+
+fn runner(name: &str, hooks: HooksConfig) -> HookRunner {
+    let project_root = PathBuf::from("tests/temp/hooks").join(name);
+    fs::create_dir_all(&project_root).unwrap();
+    HookRunner {
+        hooks,
+        workflow: "rename_module".into(),
+        old_name: "Old".into(),
+        new_name: "New".into(),
+        project_root,
+        new_project_root: None,
+        reporter: Rc::new(SilentReporter),
+    }
+}
+
+#[test]
+fn failing_hook_command_should_be_reported() {
+    let runner = runner(
+        "failing",
+        HooksConfig {
+            pre_apply: vec!["exit 0".into(), "exit 3".into()],
+            ..HooksConfig::default()
+        },
+    );
+    let error = runner.run(Hook::PreApply).unwrap_err();
+    assert!(error.starts_with(r#"pre_apply hook "exit 3" failed"#));
+    assert!(runner.run(Hook::PostApply).is_ok());
+}
+
+#[test]
+fn hook_commands_should_run_in_project_root() {
+    let runner = runner(
+        "project_root",
+        HooksConfig {
+            post_apply: vec!["echo done > hook.txt".into()],
+            ..HooksConfig::default()
+        },
+    );
+    let marker = runner.project_root.join("hook.txt");
+    if marker.is_file() {
+        fs::remove_file(&marker).unwrap();
+    }
+    runner.run(Hook::PostApply).unwrap();
+    assert!(marker.is_file());
+}
+
+#[test]
+fn hook_command_output_should_be_reported() {
+    let mut runner = runner(
+        "output",
+        HooksConfig {
+            pre_apply: vec!["echo out && echo err >&2".into()],
+            ..HooksConfig::default()
+        },
+    );
+    let reporter = Rc::new(CollectingReporter::new(false));
+    runner.reporter = reporter.clone();
+    runner.run(Hook::PreApply).unwrap();
+    let events = reporter.events();
+    assert!(events.contains(&Event::Message("out".into())));
+    assert!(events.contains(&Event::Message("err".into())));
+}
@@ -1,10 +1,18 @@
+mod common;
+
 use std::{
     fs::{self, File},
     io::{BufRead, BufReader},
     path::PathBuf,
 };
 
-use renom::changes::{AppendIniEntry, Change, SetIniEntry};
+use ini::Ini;
+use renom::{
+    changes::{AppendIniEntry, Change, RemoveIniEntry, ReplaceIniValue, SetIniEntry},
+    presentation::reporter::SilentReporter,
+};
+
+use common::fresh_dir;
 
 #[test]
 fn ini_append_should_not_strip_quotes() {
@@ -21,7 +29,7 @@ fn ini_append_should_not_strip_quotes() {
 
     let append_ini_entry = AppendIniEntry::new(&result_config, "test", "test", "test");
     let append_change = Change::AppendIniEntry(append_ini_entry);
-    let _revert = append_change.apply(&staging_dir).unwrap();
+    let _revert = append_change.apply(&staging_dir, &SilentReporter).unwrap();
 
     let actual = BufReader::new(File::open(result_config).unwrap())
         .lines()
@@ -47,7 +55,7 @@ fn ini_set_should_not_strip_quotes() {
 
     let set_ini_entry = SetIniEntry::new(&result_config, "test", "test", "test");
     let set_change = Change::SetIniEntry(set_ini_entry);
-    let _revert = set_change.apply(&staging_dir).unwrap();
+    let _revert = set_change.apply(&staging_dir, &SilentReporter).unwrap();
 
     let actual = BufReader::new(File::open(result_config).unwrap())
         .lines()
@@ -57,3 +65,91 @@ fn ini_set_should_not_strip_quotes() {
     let expected = r#"key="value""#;
     assert_eq!(actual, expected);
 }
+
+fn stage_redirects(name: &str) -> PathBuf {
+    let staging_dir = fresh_dir("ini", name);
+    let config = staging_dir.join("DefaultEngine.ini");
+    fs::write(
+        &config,
+        r#"[CoreRedirects]
++PackageRedirects=(OldName="/Script/First",NewName="/Script/Old")
++PackageRedirects=(OldName="/Script/Other",NewName="/Script/Another")
+-PackageRedirects=(OldName="/Script/Second",NewName="/Script/Old")
++ClassRedirects=(OldName="/Script/Old.A",NewName="/Script/Old.B")
+"#,
+    )
+    .unwrap();
+    config
+}
+
+#[test]
+fn ini_remove_should_match_array_prefixes_and_value_pattern() {
+    let config = stage_redirects("remove_should_match_array_prefixes");
+    let staging_dir = config.parent().unwrap();
+
+    let change = Change::RemoveIniEntry(RemoveIniEntry::new(
+        &config,
+        "CoreRedirects",
+        "PackageRedirects",
+        Some(r#"NewName="/Script/Old""#.into()),
+    ));
+    let revert = change.apply(staging_dir, &SilentReporter).unwrap();
+
+    let ini = Ini::load_from_file_noescape(&config).unwrap();
+    let section = ini.section(Some("CoreRedirects")).unwrap();
+    assert_eq!(section.get_all("+PackageRedirects").count(), 1);
+    assert_eq!(section.get_all("-PackageRedirects").count(), 0);
+    assert_eq!(section.get_all("+ClassRedirects").count(), 1);
+
+    revert().unwrap();
+    let ini = Ini::load_from_file_noescape(&config).unwrap();
+    let section = ini.section(Some("CoreRedirects")).unwrap();
+    assert_eq!(section.get_all("-PackageRedirects").count(), 1);
+}
+
+#[test]
+fn ini_remove_with_prefixed_key_should_only_match_that_prefix() {
+    let config = stage_redirects("remove_with_prefixed_key");
+    let staging_dir = config.parent().unwrap();
+
+    let change = Change::RemoveIniEntry(RemoveIniEntry::new(&config, "CoreRedirects", "-PackageRedirects", None));
+    let _revert = change.apply(staging_dir, &SilentReporter).unwrap();
+
+    let ini = Ini::load_from_file_noescape(&config).unwrap();
+    let section = ini.section(Some("CoreRedirects")).unwrap();
+    assert_eq!(section.get_all("+PackageRedirects").count(), 2);
+    assert_eq!(section.get_all("-PackageRedirects").count(), 0);
+}
+
+#[test]
+fn ini_replace_value_should_only_edit_matching_entries() {
+    let config = stage_redirects("replace_value");
+    let staging_dir = config.parent().unwrap();
+
+    let change = Change::ReplaceIniValue(ReplaceIniValue::new(
+        &config,
+        "CoreRedirects",
+        "PackageRedirects",
+        r#"NewName="/Script/Old""#,
+        r#"NewName="/Script/New""#,
+    ));
+    let _revert = change.apply(staging_dir, &SilentReporter).unwrap();
+
+    let ini = Ini::load_from_file_noescape(&config).unwrap();
+    let section = ini.section(Some("CoreRedirects")).unwrap();
+    assert_eq!(
+        section.get_all("+PackageRedirects").collect::<Vec<_>>(),
+        vec![
+            r#"(OldName="/Script/First",NewName="/Script/New")"#,
+            r#"(OldName="/Script/Other",NewName="/Script/Another")"#,
+        ]
+    );
+    assert_eq!(
+        section.get("-PackageRedirects"),
+        Some(r#"(OldName="/Script/Second",NewName="/Script/New")"#)
+    );
+    assert_eq!(
+        section.get("+ClassRedirects"),
+        Some(r#"(OldName="/Script/Old.A",NewName="/Script/Old.B")"#)
+    );
+}
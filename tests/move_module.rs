@@ -0,0 +1,101 @@
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use renom::{
+    backup::BackupOptions,
+    descriptor::listed_entry,
+    error::Error,
+    presentation::reporter::SilentReporter,
+    workflows::{move_module, move_module::Params},
+};
+
+use common::stage_project;
+
+fn params(project_root: &Path, to_plugin: Option<&str>) -> Params {
+    module_params(project_root, "Extra", to_plugin)
+}
+
+fn module_params(project_root: &Path, module: &str, to_plugin: Option<&str>) -> Params {
+    Params {
+        project_root: project_root.to_owned(),
+        module: module.into(),
+        to_plugin: to_plugin.map(str::to_owned),
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn move_module_should_move_module_into_plugin() {
+    let project_root = stage_project("move_module", "into_plugin");
+
+    move_module(params(&project_root, Some("Tools")), Rc::new(SilentReporter)).unwrap();
+
+    assert!(!project_root.join("Source/Extra").exists());
+    assert!(project_root.join("Plugins/Tools/Source/Extra/Extra.Build.cs").is_file());
+    let project = fs::read_to_string(project_root.join("Project.uproject")).unwrap();
+    assert!(listed_entry(&project, "Modules", "Extra").is_none());
+    assert!(project.contains("\t\"Plugins\": [\n\t\t{\n\t\t\t\"Name\": \"Tools\",\n\t\t\t\"Enabled\": true\n\t\t}\n\t]"));
+    let plugin = fs::read_to_string(project_root.join("Plugins/Tools/Tools.uplugin")).unwrap();
+    assert_eq!(
+        listed_entry(&plugin, "Modules", "Extra").unwrap(),
+        "{\n\t\"Name\": \"Extra\",\n\t\"Type\": \"Editor\",\n\t\"LoadingPhase\": \"PostEngineInit\"\n}"
+    );
+    // Extra depends on a module of the Other plugin.
+    assert!(plugin.contains("\"Plugins\": [\n\t\t{\n\t\t\t\"Name\": \"Other\""));
+    assert!(fs::read_to_string(project_root.join("Source/Code.Target.cs"))
+        .unwrap()
+        .contains(r#"new string[] { "Code" }"#));
+}
+
+#[test]
+fn move_module_should_move_module_back_into_project() {
+    let project_root = stage_project("move_module", "into_project");
+
+    move_module(params(&project_root, Some("Tools")), Rc::new(SilentReporter)).unwrap();
+    move_module(params(&project_root, None), Rc::new(SilentReporter)).unwrap();
+
+    assert!(project_root.join("Source/Extra/Extra.Build.cs").is_file());
+    assert!(!project_root.join("Plugins/Tools/Source/Extra").exists());
+    let project = fs::read_to_string(project_root.join("Project.uproject")).unwrap();
+    assert!(listed_entry(&project, "Modules", "Extra").is_some());
+    let plugin = fs::read_to_string(project_root.join("Plugins/Tools/Tools.uplugin")).unwrap();
+    assert!(listed_entry(&plugin, "Modules", "Extra").is_none());
+}
+
+#[test]
+fn move_module_should_reject_moving_module_in_place() {
+    let project_root = stage_project("move_module", "in_place");
+
+    assert!(move_module(params(&project_root, None), Rc::new(SilentReporter)).is_err());
+}
+
+#[test]
+fn move_module_should_reject_moving_primary_game_module_into_plugin() {
+    let project_root = stage_project("move_module", "primary_game_module");
+
+    let result = move_module(
+        module_params(&project_root, "Code", Some("Tools")),
+        Rc::new(SilentReporter),
+    );
+
+    assert!(matches!(result, Err(Error::Validation(_))));
+    assert!(project_root.join("Source/Code/Code.Build.cs").is_file());
+}
+
+#[test]
+fn move_module_should_reject_moving_module_depending_on_project_modules_into_plugin() {
+    let project_root = stage_project("move_module", "project_dependencies");
+
+    let result = move_module(
+        module_params(&project_root, "Shared", Some("Tools")),
+        Rc::new(SilentReporter),
+    );
+
+    assert!(matches!(result, Err(Error::Validation(_))));
+    assert!(project_root.join("Source/Shared/Shared.Build.cs").is_file());
+}
@@ -0,0 +1,95 @@
+use ini::Ini;
+use renom::redirects::{compact, to_core_redirect, Compaction};
+
+fn compact_str(content: &str) -> (Compaction, Ini) {
+    let mut ini = Ini::load_from_str_noescape(content).unwrap();
+    let compaction = compact(&mut ini);
+    (compaction, ini)
+}
+
+fn values<'a>(ini: &'a Ini, section: &str, key: &str) -> Vec<&'a str> {
+    ini.section(Some(section)).unwrap().get_all(key).collect()
+}
+
+#[test]
+fn compact_should_collapse_chains_and_remove_self_redirects_and_duplicates() {
+    let (compaction, ini) = compact_str(
+        r#"[CoreRedirects]
++PackageRedirects=(OldName="/Script/A",NewName="/Script/B")
++PackageRedirects=(OldName="/Script/B",NewName="/Script/C")
++PackageRedirects=(OldName="/Script/A",NewName="/Script/C")
++PackageRedirects=(OldName="/Script/D",NewName="/Script/D")
++PackageRedirects=(OldName="/A/",NewName="/B/",MatchSubstring=true)
+"#,
+    );
+
+    assert_eq!(
+        values(&ini, "CoreRedirects", "+PackageRedirects"),
+        vec![
+            r#"(OldName="/Script/A",NewName="/Script/C")"#,
+            r#"(OldName="/Script/B",NewName="/Script/C")"#,
+            r#"(OldName="/A/",NewName="/B/",MatchSubstring=true)"#,
+        ]
+    );
+    assert_eq!(compaction.collapsed, 1);
+    assert_eq!(compaction.removed, 2);
+    assert!(compaction.cycles.is_empty());
+}
+
+#[test]
+fn compact_should_collapse_legacy_redirects() {
+    let (compaction, ini) = compact_str(
+        r#"[/Script/Engine.Engine]
++ActiveGameNameRedirects=(OldGameName="/Script/A", NewGameName="/Script/B")
++ActiveGameNameRedirects=(OldGameName="/Script/B", NewGameName="/Script/C")
++ActiveClassRedirects=(OldClassName="AGameMode",NewClassName="AGameMode")
+"#,
+    );
+
+    assert_eq!(
+        values(&ini, "/Script/Engine.Engine", "+ActiveGameNameRedirects"),
+        vec![
+            r#"(OldGameName="/Script/A", NewGameName="/Script/C")"#,
+            r#"(OldGameName="/Script/B", NewGameName="/Script/C")"#,
+        ]
+    );
+    assert!(ini.section(Some("/Script/Engine.Engine")).unwrap().get("+ActiveClassRedirects").is_none());
+    assert_eq!(compaction.collapsed, 1);
+    assert_eq!(compaction.removed, 1);
+}
+
+#[test]
+fn compact_should_report_cycles_and_leave_them_unchanged() {
+    let content = r#"[CoreRedirects]
++PackageRedirects=(OldName="/Script/A",NewName="/Script/B")
++PackageRedirects=(OldName="/Script/B",NewName="/Script/A")
+-PackageRedirects=(OldName="/Script/C",NewName="/Script/C")
+"#;
+    let (compaction, ini) = compact_str(content);
+
+    assert_eq!(compaction.cycles, vec![vec!["/Script/A".to_owned(), "/Script/B".into(), "/Script/A".into()]]);
+    assert_eq!(values(&ini, "CoreRedirects", "+PackageRedirects").len(), 2);
+    assert_eq!(values(&ini, "CoreRedirects", "-PackageRedirects").len(), 1);
+}
+
+#[test]
+fn legacy_redirects_should_convert_to_core_redirects() {
+    assert_eq!(
+        to_core_redirect("+ActiveGameNameRedirects", r#"(OldGameName="TP_Blank", NewGameName="/Script/Code")"#),
+        Some((
+            "+PackageRedirects".into(),
+            r#"(OldName="/Script/TP_Blank",NewName="/Script/Code")"#.into()
+        ))
+    );
+    assert_eq!(
+        to_core_redirect("+ActiveClassRedirects", r#"(OldClassName="TP_BlankGameModeBase",NewClassName="CodeGameModeBase")"#),
+        Some((
+            "+ClassRedirects".into(),
+            r#"(OldName="TP_BlankGameModeBase",NewName="CodeGameModeBase")"#.into()
+        ))
+    );
+    assert_eq!(
+        to_core_redirect("+ActiveClassRedirects", r#"(OldClassName="A",NewClassName="B",OldSubobjName="C",NewSubobjName="D")"#),
+        None
+    );
+}
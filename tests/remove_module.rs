@@ -0,0 +1,93 @@
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use renom::{
+    backup::BackupOptions,
+    descriptor::remove_entry,
+    presentation::reporter::{CollectingReporter, Event, SilentReporter},
+    workflows::{remove_module, remove_module::Params},
+};
+
+use common::stage_project;
+
+fn params(project_root: &Path, force: bool) -> Params {
+    Params {
+        project_root: project_root.to_owned(),
+        module: "Extra".into(),
+        force,
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn remove_module_should_refuse_to_remove_dependencies() {
+    let project_root = stage_project("remove_module", "refuse");
+
+    assert!(remove_module(params(&project_root, false), Rc::new(SilentReporter)).is_err());
+    assert!(project_root.join("Source/Extra/Extra.Build.cs").is_file());
+}
+
+#[test]
+fn remove_module_should_remove_references_when_forced() {
+    let project_root = stage_project("remove_module", "force");
+
+    remove_module(params(&project_root, true), Rc::new(SilentReporter)).unwrap();
+
+    assert!(!project_root.join("Source/Extra").exists());
+    assert_eq!(
+        fs::read_to_string(project_root.join("Project.uproject")).unwrap(),
+        "{\n\t\"Modules\": [\n\t\t{\n\t\t\t\"Name\": \"Code\",\n\t\t\t\"Type\": \"Runtime\"\n\t\t}\n\t]\n}\n"
+    );
+    assert!(fs::read_to_string(project_root.join("Source/Code/Code.Build.cs"))
+        .unwrap()
+        .contains(r#"PublicDependencyModuleNames.AddRange(new string[] { "Core" });"#));
+    assert!(fs::read_to_string(project_root.join("Source/Code.Target.cs"))
+        .unwrap()
+        .contains("\t\tExtraModuleNames.Add(\"Code\");\n\t}"));
+}
+
+#[test]
+fn remove_module_should_report_includes_of_its_headers_when_forced() {
+    let project_root = stage_project("remove_module", "includes");
+    fs::create_dir_all(project_root.join("Source/Extra/Public")).unwrap();
+    fs::write(project_root.join("Source/Extra/Public/ExtraTypes.h"), "#pragma once\n").unwrap();
+    fs::write(
+        project_root.join("Source/Code/Code.cpp"),
+        "#include \"ExtraTypes.h\"\n#include \"CoreMinimal.h\"\n",
+    )
+    .unwrap();
+    let reporter = Rc::new(CollectingReporter::new(false));
+
+    remove_module(params(&project_root, true), reporter.clone()).unwrap();
+
+    let manual_steps: Vec<String> = reporter
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Step { process, message, .. } if process == "manual" => Some(message),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(manual_steps.len(), 1);
+    assert!(manual_steps[0].contains(r#"includes "ExtraTypes.h" of removed module Extra"#));
+}
+
+#[test]
+fn descriptor_remove_entry_should_keep_other_entries() {
+    let descriptor = r#"{ "Plugins": [ { "Name": "A" }, { "Name": "B" }, { "Name": "C" } ] }"#;
+
+    assert_eq!(
+        remove_entry(descriptor, "Plugins", "B").unwrap(),
+        r#"{ "Plugins": [ { "Name": "A" }, { "Name": "C" } ] }"#
+    );
+    assert_eq!(
+        remove_entry(descriptor, "Plugins", "C").unwrap(),
+        r#"{ "Plugins": [ { "Name": "A" }, { "Name": "B" } ] }"#
+    );
+    assert!(remove_entry(descriptor, "Plugins", "D").is_err());
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use std::{fs, path::PathBuf, rc::Rc};
+
+use renom::{
+    backup::BackupOptions,
+    presentation::reporter::SilentReporter,
+    workflows::{rename_module, rename_module::Params},
+};
+
+use common::stage_project;
+
+fn params(project_root: PathBuf, rename_sources: bool) -> Params {
+    Params {
+        project_root,
+        module: "Code".into(),
+        new_name: "Game".into(),
+        rename_sources,
+        clean: false,
+        backup: BackupOptions::default(),
+    }
+}
+
+#[test]
+fn rename_module_should_rename_sources_named_after_module() {
+    let project_root = stage_project("rename_module", "rename_sources");
+
+    rename_module(params(project_root.clone(), true), Rc::new(SilentReporter)).unwrap();
+
+    let module_root = project_root.join("Source/Game");
+    assert!(module_root.join("Public/Game.h").is_file());
+    assert!(module_root.join("Private/Game.cpp").is_file());
+    assert_eq!(
+        fs::read_to_string(module_root.join("Public/Game.h")).unwrap(),
+        "#pragma once\n\nDECLARE_LOG_CATEGORY_EXTERN(LogGame, Log, All);\n\nclass FGameModule : public IModuleInterface {};\n"
+    );
+    assert_eq!(
+        fs::read_to_string(module_root.join("Private/Game.cpp")).unwrap(),
+        "#include \"Game.h\"\n\nDEFINE_LOG_CATEGORY(LogGame);\n\nIMPLEMENT_MODULE(FGameModule, Game)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/Other/Other.cpp")).unwrap(),
+        "#include \"Game.h\"\n#include \"CodeGameMode.h\"\n\nvoid Load() { UE_LOG(LogGame, Log, TEXT(\"LogCodeExtra\")); FGameModule::Get(); }\n"
+    );
+}
+
+#[test]
+fn rename_module_should_keep_sources_unless_requested() {
+    let project_root = stage_project("rename_module", "keep_sources");
+
+    rename_module(params(project_root.clone(), false), Rc::new(SilentReporter)).unwrap();
+
+    let module_root = project_root.join("Source/Game");
+    assert!(module_root.join("Public/Code.h").is_file());
+    assert_eq!(
+        fs::read_to_string(module_root.join("Private/Code.cpp")).unwrap(),
+        "#include \"Code.h\"\n\nDEFINE_LOG_CATEGORY(LogCode);\n\nIMPLEMENT_MODULE(FCodeModule, Game)\n"
+    );
+}
+
+#[test]
+fn rename_module_should_update_include_paths_through_module_folder() {
+    let project_root = stage_project("rename_module", "include_paths");
+    let module_root = project_root.join("Source/Code");
+    fs::write(module_root.join("Public/Widget.h"), "#pragma once\n").unwrap();
+    fs::create_dir_all(project_root.join("Source/Other/Vendor/Code/Public")).unwrap();
+    fs::write(project_root.join("Source/Other/Vendor/Code/Public/Widget.h"), "#pragma once\n").unwrap();
+    fs::write(
+        project_root.join("Source/Other/Widgets.cpp"),
+        "#include \"Code/Public/Code.h\"\n#include <Code/Public/Widget.h>\n#include \"Code/Private/Missing.h\"\n",
+    )
+    .unwrap();
+    fs::write(
+        project_root.join("Source/Other/Other.Build.cs"),
+        r#"PublicDependencyModuleNames.Add("Code");
+PublicIncludePaths.AddRange(new string[] { "Code/Public", "Code\\Private" });
+PrivateIncludePaths.Add("ThirdParty/Code/Include");"#,
+    )
+    .unwrap();
+
+    rename_module(params(project_root.clone(), false), Rc::new(SilentReporter)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/Other/Widgets.cpp")).unwrap(),
+        "#include \"Game/Public/Code.h\"\n#include <Code/Public/Widget.h>\n#include \"Code/Private/Missing.h\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/Other/Other.Build.cs")).unwrap(),
+        r#"PublicDependencyModuleNames.Add("Game");
+PublicIncludePaths.AddRange(new string[] { "Code/Public", "Game\\Private" });
+PrivateIncludePaths.Add("ThirdParty/Code/Include");"#
+    );
+}
+
+#[test]
+fn rename_module_should_update_existing_redirects_of_every_kind() {
+    let project_root = stage_project("rename_module", "redirects");
+    fs::write(
+        project_root.join("Config/DefaultEngine.ini"),
+        r#"[CoreRedirects]
++ClassRedirects=(OldName="/Script/Legacy.Actor",NewName="/Script/Code.Actor")
++StructRedirects=(OldName="Item",NewName="/Script/Code.Item")
++FunctionRedirects=(OldName="/Script/Legacy.Actor.Fire",NewName="/Script/CodeTools.Actor.Fire")
+
+[/Script/Engine.Engine]
++ActiveClassRedirects=(OldClassName="/Script/Legacy.Pawn",NewClassName="/Script/Code.Pawn")
+"#,
+    )
+    .unwrap();
+
+    rename_module(params(project_root.clone(), false), Rc::new(SilentReporter)).unwrap();
+
+    let config = fs::read_to_string(project_root.join("Config/DefaultEngine.ini")).unwrap();
+    assert!(config.contains(r#"+ClassRedirects=(OldName="/Script/Legacy.Actor",NewName="/Script/Game.Actor")"#));
+    assert!(config.contains(r#"+StructRedirects=(OldName="Item",NewName="/Script/Game.Item")"#));
+    assert!(config.contains(r#"NewName="/Script/CodeTools.Actor.Fire""#));
+    assert!(config.contains(r#"+ActiveClassRedirects=(OldClassName="/Script/Legacy.Pawn",NewClassName="/Script/Game.Pawn")"#));
+}
+
+#[test]
+fn rename_module_should_only_update_includes_of_module_main_header() {
+    let project_root = stage_project("rename_module", "main_header_includes");
+    fs::create_dir_all(project_root.join("Source/Other/Vendor")).unwrap();
+    fs::write(project_root.join("Source/Other/Vendor/Code.h"), "#pragma once\n").unwrap();
+    fs::write(
+        project_root.join("Source/Other/Other.cpp"),
+        "#include \"Public/Code.h\"\n#include \"Vendor/Code.h\"\n",
+    )
+    .unwrap();
+
+    rename_module(params(project_root.clone(), true), Rc::new(SilentReporter)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/Other/Other.cpp")).unwrap(),
+        "#include \"Public/Game.h\"\n#include \"Vendor/Code.h\"\n"
+    );
+}
+
+#[test]
+fn rename_module_should_only_update_implement_macro_invocation() {
+    let project_root = stage_project("rename_module", "implement_macro_invocation");
+    fs::write(
+        project_root.join("Source/Code/Private/Code.cpp"),
+        "#include \"Code.h\"\n\n// IMPLEMENT_MODULE(FCodeModule, Code)\nIMPLEMENT_MODULE(FCodeModule, Code)\n",
+    )
+    .unwrap();
+
+    rename_module(params(project_root.clone(), false), Rc::new(SilentReporter)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(project_root.join("Source/Game/Private/Code.cpp")).unwrap(),
+        "#include \"Code.h\"\n\n// IMPLEMENT_MODULE(FCodeModule, Code)\nIMPLEMENT_MODULE(FCodeModule, Game)\n"
+    );
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::{fs, rc::Rc};
+
+use renom::{
+    backup::BackupOptions,
+    error::Error,
+    presentation::reporter::SilentReporter,
+    workflows::{rename_target, rename_target::Params},
+};
+
+use common::stage_project;
+
+#[test]
+fn rename_target_should_update_target_name_references() {
+    let project_root = stage_project("rename_target", "references");
+
+    let params = Params {
+        project_root: project_root.clone(),
+        target: "Code".into(),
+        new_name: "Game".into(),
+        clean: false,
+        backup: BackupOptions::default(),
+    };
+    rename_target(params, Rc::new(SilentReporter)).unwrap();
+
+    assert!(!project_root.join("Source/Code.Target.cs").exists());
+    let target = fs::read_to_string(project_root.join("Source/Game.Target.cs")).unwrap();
+    assert!(target.contains("public class GameTarget : TargetRules"));
+    assert!(target.contains("public GameTarget(TargetInfo Target)"));
+    // Module names are kept, since the Code module is not renamed.
+    assert!(target.contains(r#"new string[] { "Code", "CodeTools" }"#));
+    assert!(target.contains(r#"LaunchModuleName = "Code";"#));
+    let derived = fs::read_to_string(project_root.join("Source/CodeEditor.Target.cs")).unwrap();
+    assert!(derived.contains("public class CodeEditorTarget : GameTarget"));
+    let build_file = fs::read_to_string(project_root.join("Source/Code/Code.Build.cs")).unwrap();
+    assert!(build_file.contains(r#"if (Target.Name == "Game") { PublicDefinitions.Add("Code"); }"#));
+    let config = fs::read_to_string(project_root.join("Config/DefaultGame.ini")).unwrap();
+    assert!(config.contains("BuildTarget=Game"));
+    assert!(config.contains("LaunchOnTarget=Game"));
+}
+
+#[test]
+fn rename_target_should_update_descriptor_target_lists() {
+    let project_root = stage_project("rename_target", "descriptor");
+
+    let params = Params {
+        project_root: project_root.clone(),
+        target: "Code".into(),
+        new_name: "Game".into(),
+        clean: false,
+        backup: BackupOptions::default(),
+    };
+    rename_target(params, Rc::new(SilentReporter)).unwrap();
+
+    let project = fs::read_to_string(project_root.join("Project.uproject")).unwrap();
+    assert!(project.contains("\"TargetDenyList\": [ \"Game\" ]"));
+    assert!(project.contains("\"TargetAllowList\": [\n\t\t\t\t\"CodeEditor\",\n\t\t\t\t\"Game\"\n\t\t\t]"));
+    // Module names are kept, since the Code module is not renamed.
+    assert!(project.contains("\"Name\": \"Code\""));
+}
+
+#[test]
+fn rename_target_should_keep_build_environment() {
+    let project_root = stage_project("rename_target", "build_environment");
+
+    let params = Params {
+        project_root: project_root.clone(),
+        target: "Code".into(),
+        new_name: "Game".into(),
+        clean: false,
+        backup: BackupOptions::default(),
+    };
+    rename_target(params, Rc::new(SilentReporter)).unwrap();
+
+    // BuildEnvironment only holds TargetBuildEnvironment values, never a
+    // target name.
+    let target = fs::read_to_string(project_root.join("Source/Game.Target.cs")).unwrap();
+    assert!(target.contains("BuildEnvironment = TargetBuildEnvironment.Unique;"));
+}
+
+#[test]
+fn failing_post_apply_hook_should_revert_rename() {
+    let project_root = stage_project("rename_target", "post_apply_failure");
+    fs::create_dir_all(project_root.join(".renom")).unwrap();
+    fs::write(
+        project_root.join(".renom/config.toml"),
+        "[hooks]\npost_apply = [\"exit 1\"]\nrevert_on_post_apply_failure = true\n",
+    )
+    .unwrap();
+
+    let params = Params {
+        project_root: project_root.clone(),
+        target: "Code".into(),
+        new_name: "Game".into(),
+        clean: false,
+        backup: BackupOptions {
+            snapshot: true,
+            ..BackupOptions::default()
+        },
+    };
+    let error = rename_target(params, Rc::new(SilentReporter)).unwrap_err();
+
+    assert!(matches!(error, Error::RolledBack(_)));
+    assert!(project_root.join("Source/Code.Target.cs").is_file());
+    assert!(!project_root.join("Source/Game.Target.cs").exists());
+}
@@ -0,0 +1,68 @@
+use std::{fs, path::PathBuf, rc::Rc};
+
+use renom::{
+    changes::{Change, ReplaceInFile},
+    engine::Engine,
+    presentation::{
+        log,
+        reporter::{to_json, CollectingReporter, Event, SilentReporter},
+    },
+};
+
+#[test]
+fn engine_should_report_changes_to_its_reporter() {
+    let staging_dir = PathBuf::from("tests/temp/reporter/engine");
+    if staging_dir.is_dir() {
+        fs::remove_dir_all(&staging_dir).unwrap();
+    }
+    fs::create_dir_all(&staging_dir).unwrap();
+    let file = staging_dir.join("File.txt");
+    fs::write(&file, "Old").unwrap();
+
+    let reporter = Rc::new(CollectingReporter::new(false));
+    let mut engine = Engine::with_reporter(reporter.clone());
+    let changeset = vec![Change::ReplaceInFile(ReplaceInFile::new(&file, "Old", "New"))];
+    engine.execute(changeset, &staging_dir).unwrap();
+    engine.revert().unwrap();
+
+    let processes: Vec<(String, Option<&str>, Vec<PathBuf>)> = reporter
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Step {
+                process,
+                change,
+                paths,
+                ..
+            } => Some((process, change, paths)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        processes,
+        vec![
+            ("apply".into(), Some("replace_in_file"), vec![file.clone()]),
+            ("revert".into(), Some("replace_in_file"), vec![file.clone()]),
+        ]
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), "Old");
+}
+
+#[test]
+fn log_functions_should_report_to_the_given_reporter() {
+    let reporter = CollectingReporter::new(true);
+    let silent = SilentReporter;
+    log::set_workflow("test");
+    log::verbose_with_category(&reporter, "category", "detail");
+    log::error(&reporter, "failure");
+    log::basic(&silent, "not collected");
+
+    let records = reporter.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].workflow.as_deref(), Some("test"));
+    assert_eq!(
+        records[1].event,
+        Event::Error("failure".into()),
+    );
+    assert!(to_json(&records[0]).contains(r#""category":"category""#));
+}
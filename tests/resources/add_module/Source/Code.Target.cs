@@ -0,0 +1,7 @@
+public class CodeTarget : TargetRules
+{
+	public CodeTarget(TargetInfo Target) : base(Target)
+	{
+		ExtraModuleNames.AddRange(new string[] { "Code" });
+	}
+}
\ No newline at end of file
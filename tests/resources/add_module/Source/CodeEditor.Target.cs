@@ -0,0 +1,8 @@
+public class CodeEditorTarget : TargetRules
+{
+	public CodeEditorTarget(TargetInfo Target) : base(Target)
+	{
+		Type = TargetType.Editor;
+		ExtraModuleNames.Add("Code");
+	}
+}
\ No newline at end of file
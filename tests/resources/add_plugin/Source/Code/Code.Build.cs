@@ -0,0 +1 @@
+public class Code : ModuleRules {}
\ No newline at end of file
@@ -0,0 +1,8 @@
+public class CodeTarget : TargetRules
+{
+	public CodeTarget(TargetInfo Target) : base(Target)
+	{
+		Type = TargetType.Game;
+		ExtraModuleNames.Add("Code");
+	}
+}
\ No newline at end of file
@@ -0,0 +1 @@
+public class Other : ModuleRules {}
\ No newline at end of file
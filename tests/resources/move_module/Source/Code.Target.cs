@@ -0,0 +1 @@
+public class CodeTarget : TargetRules { ExtraModuleNames.AddRange(new string[] { "Code", "Extra" }); }
\ No newline at end of file
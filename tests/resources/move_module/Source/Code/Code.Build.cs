@@ -0,0 +1 @@
+public class Code : ModuleRules { PrivateDependencyModuleNames.Add("Extra"); }
\ No newline at end of file
@@ -0,0 +1 @@
+public class Extra : ModuleRules { PublicDependencyModuleNames.AddRange(new string[] { "Core", "Other" }); }
\ No newline at end of file
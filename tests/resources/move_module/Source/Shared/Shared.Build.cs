@@ -0,0 +1 @@
+public class Shared : ModuleRules { PrivateDependencyModuleNames.Add("Extra"); }
\ No newline at end of file
@@ -0,0 +1,8 @@
+public class CodeTarget : TargetRules
+{
+	public CodeTarget(TargetInfo Target) : base(Target)
+	{
+		ExtraModuleNames.Add("Code");
+		ExtraModuleNames.Add("Extra");
+	}
+}
\ No newline at end of file
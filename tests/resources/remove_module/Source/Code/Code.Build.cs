@@ -0,0 +1,7 @@
+public class Code : ModuleRules
+{
+	public Code(ReadOnlyTargetRules Target) : base(Target)
+	{
+		PublicDependencyModuleNames.AddRange(new string[] { "Core", "Extra" });
+	}
+}
\ No newline at end of file
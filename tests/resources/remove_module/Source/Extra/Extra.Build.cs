@@ -0,0 +1 @@
+public class Extra : ModuleRules {}
\ No newline at end of file
@@ -0,0 +1 @@
+public class Code : ModuleRules { public Code(ReadOnlyTargetRules Target) : base(Target) {} }
\ No newline at end of file
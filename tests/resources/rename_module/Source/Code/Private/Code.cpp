@@ -0,0 +1,5 @@
+#include "Code.h"
+
+DEFINE_LOG_CATEGORY(LogCode);
+
+IMPLEMENT_MODULE(FCodeModule, Code)
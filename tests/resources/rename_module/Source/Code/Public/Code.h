@@ -0,0 +1,5 @@
+#pragma once
+
+DECLARE_LOG_CATEGORY_EXTERN(LogCode, Log, All);
+
+class FCodeModule : public IModuleInterface {};
@@ -0,0 +1 @@
+PublicDependencyModuleNames.Add("Code");
\ No newline at end of file
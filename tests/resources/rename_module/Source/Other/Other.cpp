@@ -0,0 +1,4 @@
+#include "Code.h"
+#include "CodeGameMode.h"
+
+void Load() { UE_LOG(LogCode, Log, TEXT("LogCodeExtra")); FCodeModule::Get(); }
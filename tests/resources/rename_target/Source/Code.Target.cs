@@ -0,0 +1,9 @@
+public class CodeTarget : TargetRules
+{
+	public CodeTarget(TargetInfo Target) : base(Target)
+	{
+		ExtraModuleNames.AddRange(new string[] { "Code", "CodeTools" });
+		LaunchModuleName = "Code";
+		BuildEnvironment = TargetBuildEnvironment.Unique;
+	}
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+public class Code : ModuleRules
+{
+	public Code(ReadOnlyTargetRules Target) : base(Target)
+	{
+		if (Target.Name == "Code") { PublicDefinitions.Add("Code"); }
+	}
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+public class CodeEditorTarget : CodeTarget
+{
+	public CodeEditorTarget(TargetInfo Target) : base(Target)
+	{
+		Type = TargetType.Editor;
+	}
+}
\ No newline at end of file
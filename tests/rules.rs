@@ -0,0 +1,66 @@
+use std::{fs, path::PathBuf};
+
+use renom::{
+    changes::{Change, ReplaceInFile},
+    config::Config,
+    rules::{expand_template, ReplacementRule},
+};
+
+#[test]
+fn placeholders_should_expand_to_name_variants() {
+    assert_eq!(
+        expand_template("{old}/{new}/{old_lower}/{new_lower}/{OLD_UPPER}/{NEW_UPPER}", "OldName", "NewName"),
+        "OldName/NewName/oldname/newname/OLDNAME/NEWNAME"
+    );
+}
+
+#[test]
+fn rule_pattern_should_match_names_literally() {
+    let rule = ReplacementRule {
+        files: vec![],
+        pattern: r#"module: "{old_lower}""#.into(),
+        replacement: r#"module: "{new_lower}""#.into(),
+    };
+    assert!(rule.validate().is_ok());
+    assert_eq!(rule.expand_pattern("A.B", "C"), r#"module: "a\.b""#);
+
+    let invalid = ReplacementRule {
+        pattern: "({old}".into(),
+        ..rule
+    };
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn workflow_rules_should_expand_into_replace_in_file_changes() {
+    let project_root = PathBuf::from("tests/temp/rules/expand");
+    if project_root.is_dir() {
+        fs::remove_dir_all(&project_root).unwrap();
+    }
+    fs::create_dir_all(project_root.join(".renom")).unwrap();
+    fs::create_dir_all(project_root.join("CI")).unwrap();
+    fs::write(project_root.join("CI/build.yml"), "MODULE: GAME").unwrap();
+    fs::write(
+        project_root.join(".renom/config.toml"),
+        r#"
+        [[workflows.rename_module.rules]]
+        files = ["CI/*.yml"]
+        pattern = "MODULE: {OLD_UPPER}"
+        replacement = "MODULE: {NEW_UPPER}"
+        "#,
+    )
+    .unwrap();
+
+    let config = Config::load(&project_root).unwrap();
+    let changes = config
+        .extra_changes(&project_root, &config.workflows.rename_module, "Game", "Shooter")
+        .unwrap();
+    assert_eq!(
+        changes,
+        vec![Change::ReplaceInFile(ReplaceInFile::new(
+            project_root.join("CI/build.yml"),
+            "MODULE: GAME",
+            "MODULE: SHOOTER"
+        ))]
+    );
+}
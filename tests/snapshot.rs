@@ -0,0 +1,141 @@
+use std::{fs, path::PathBuf};
+
+use renom::{
+    backup::{self, BackupOptions, Manifest},
+    changes::{Change, RenameFile, ReplaceInFile},
+    presentation::reporter::SilentReporter,
+    snapshot::{create_snapshot, restore_snapshot},
+};
+
+#[test]
+fn restore_snapshot_should_undo_renames_and_edits() {
+    let project_root = PathBuf::from("tests/temp/snapshot/undo_renames_and_edits");
+    if project_root.is_dir() {
+        fs::remove_dir_all(&project_root).unwrap();
+    }
+    let module_root = project_root.join("Source/Old");
+    fs::create_dir_all(&module_root).unwrap();
+    fs::write(module_root.join("Old.Build.cs"), "class Old").unwrap();
+    fs::write(module_root.join("Untouched.h"), "untouched").unwrap();
+
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+    let new_root = project_root.join("Source/New");
+    let changeset = vec![
+        Change::RenameFile(RenameFile::new(&module_root, &new_root)),
+        Change::ReplaceInFile(ReplaceInFile::new(new_root.join("Old.Build.cs"), "Old", "New")),
+    ];
+    let snapshot = create_snapshot(
+        &project_root,
+        &[],
+        &changeset,
+        &backup_dir,
+        &SilentReporter,
+    ).unwrap();
+    assert_eq!(snapshot.paths, vec![PathBuf::from("Source/Old")]);
+    assert_eq!(snapshot.created, vec![PathBuf::from("Source/New")]);
+    assert!(Manifest::load(&backup_dir).unwrap().snapshot.is_some());
+
+    for change in &changeset {
+        let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+    }
+    assert_eq!(
+        fs::read_to_string(new_root.join("Old.Build.cs")).unwrap(),
+        "class New"
+    );
+
+    restore_snapshot(&project_root, &backup_dir, &SilentReporter).unwrap();
+    assert!(!new_root.exists());
+    assert_eq!(
+        fs::read_to_string(module_root.join("Old.Build.cs")).unwrap(),
+        "class Old"
+    );
+    assert_eq!(
+        fs::read_to_string(module_root.join("Untouched.h")).unwrap(),
+        "untouched"
+    );
+}
+
+#[test]
+fn snapshot_should_not_share_untouched_files_with_project() {
+    let project_root = PathBuf::from("tests/temp/snapshot/not_share_untouched_files");
+    if project_root.is_dir() {
+        fs::remove_dir_all(&project_root).unwrap();
+    }
+    let module_root = project_root.join("Source/Old");
+    fs::create_dir_all(&module_root).unwrap();
+    fs::write(module_root.join("Untouched.h"), "untouched").unwrap();
+
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+    let changeset = vec![Change::RenameFile(RenameFile::new(
+        &module_root,
+        project_root.join("Source/New"),
+    ))];
+    create_snapshot(&project_root, &[], &changeset, &backup_dir, &SilentReporter).unwrap();
+    fs::write(module_root.join("Untouched.h"), "edited elsewhere").unwrap();
+
+    assert_eq!(
+        fs::read_to_string(backup_dir.join("snapshot/Source/Old/Untouched.h")).unwrap(),
+        "untouched"
+    );
+}
+
+#[test]
+fn restore_snapshot_should_rename_project_root_back() {
+    let staging_dir = PathBuf::from("tests/temp/snapshot/rename_project_root_back");
+    if staging_dir.is_dir() {
+        fs::remove_dir_all(&staging_dir).unwrap();
+    }
+    let project_root = staging_dir.join("Old");
+    let new_project_root = staging_dir.join("New");
+    fs::create_dir_all(project_root.join("Config")).unwrap();
+    fs::write(project_root.join("Config/DefaultGame.ini"), "Name=Old\n").unwrap();
+
+    let backup_dir = backup::create_backup_dir(
+        &project_root,
+        &BackupOptions::default(),
+        "test",
+        "test run",
+        &SilentReporter,
+    ).unwrap();
+    let changeset = vec![
+        Change::ReplaceInFile(ReplaceInFile::new(
+            project_root.join("Config/DefaultGame.ini"),
+            "Old",
+            "New",
+        )),
+        Change::RenameFile(RenameFile::new(&project_root, &new_project_root)),
+    ];
+    let snapshot = create_snapshot(
+        &project_root,
+        &[],
+        &changeset,
+        &backup_dir,
+        &SilentReporter,
+    ).unwrap();
+    assert_eq!(snapshot.renamed_root, Some(PathBuf::from("New")));
+
+    for change in &changeset {
+        let _revert = change.apply(&backup_dir, &SilentReporter).unwrap();
+    }
+    let relocated_backup_dir =
+        new_project_root.join(backup_dir.strip_prefix(&project_root).unwrap());
+
+    restore_snapshot(&project_root, &relocated_backup_dir, &SilentReporter).unwrap();
+    assert!(!new_project_root.exists());
+    assert_eq!(
+        fs::read_to_string(project_root.join("Config/DefaultGame.ini")).unwrap(),
+        "Name=Old\n"
+    );
+}
@@ -0,0 +1,101 @@
+use std::{fs, path::PathBuf};
+
+use renom::validation::{
+    validate_name, validate_name_with, validate_path_lengths, validate_path_lengths_with, NameError,
+    NameKind, ValidationOverrides,
+};
+
+#[test]
+fn valid_identifier_should_be_accepted() {
+    assert_eq!(validate_name("My_Game2", NameKind::Project), Ok(()));
+    assert_eq!(validate_name("_Internal", NameKind::Module), Ok(()));
+}
+
+#[test]
+fn invalid_identifiers_should_be_rejected() {
+    assert_eq!(
+        validate_name("2Fast", NameKind::Module),
+        Err(NameError::LeadingDigit)
+    );
+    assert_eq!(
+        validate_name("Café", NameKind::Plugin),
+        Err(NameError::NonAscii)
+    );
+    assert_eq!(
+        validate_name("My-Game", NameKind::Project),
+        Err(NameError::InvalidCharacter('-'))
+    );
+    assert_eq!(
+        validate_name("class", NameKind::Module),
+        Err(NameError::CppKeyword)
+    );
+    assert_eq!(validate_name("  ", NameKind::Target), Err(NameError::Empty));
+}
+
+#[test]
+fn length_cap_should_depend_on_kind() {
+    let name = "A".repeat(25);
+    assert_eq!(
+        validate_name(&name, NameKind::Project),
+        Err(NameError::TooLong { max_len: 20 })
+    );
+    assert_eq!(validate_name(&name, NameKind::Module), Ok(()));
+}
+
+#[test]
+fn reserved_names_should_be_rejected_regardless_of_case() {
+    assert_eq!(
+        validate_name("unrealed", NameKind::Module),
+        Err(NameError::ReservedEngineName("UnrealEd".into()))
+    );
+    assert_eq!(
+        validate_name("Aux", NameKind::Plugin),
+        Err(NameError::ReservedDeviceName("AUX".into()))
+    );
+}
+
+#[test]
+fn long_resulting_paths_should_be_rejected() {
+    let staging_dir = PathBuf::from("tests/temp/validation/long_resulting_paths");
+    if staging_dir.is_dir() {
+        fs::remove_dir_all(&staging_dir).unwrap();
+    }
+    let module_root = staging_dir.join("Old");
+    fs::create_dir_all(module_root.join("Private")).unwrap();
+    fs::write(module_root.join("Old.Build.cs"), "").unwrap();
+
+    assert_eq!(validate_path_lengths(&module_root, "Old", "New"), Ok(()));
+    let result = validate_path_lengths(&module_root, "Old", &"N".repeat(300));
+    assert!(matches!(result, Err(NameError::PathTooLong { .. })));
+}
+
+#[test]
+fn overrides_should_relax_name_rules() {
+    let overrides = ValidationOverrides {
+        max_name_length: Some(40),
+        allow_reserved_names: true,
+        ..ValidationOverrides::default()
+    };
+    assert!(validate_name("Engine", NameKind::Module).is_err());
+    assert!(validate_name_with("Engine", NameKind::Module, &overrides).is_ok());
+    let long_name = "A".repeat(35);
+    assert!(validate_name(&long_name, NameKind::Module).is_err());
+    assert!(validate_name_with(&long_name, NameKind::Module, &overrides).is_ok());
+}
+
+#[test]
+fn zero_max_path_length_should_reject_all_paths() {
+    let staging_dir = PathBuf::from("tests/temp/validation/zero_max_path_length");
+    if staging_dir.is_dir() {
+        fs::remove_dir_all(&staging_dir).unwrap();
+    }
+    let module_root = staging_dir.join("Old");
+    fs::create_dir_all(&module_root).unwrap();
+
+    let overrides = ValidationOverrides {
+        max_path_length: Some(0),
+        ..ValidationOverrides::default()
+    };
+    let result = validate_path_lengths_with(&module_root, "Old", "New", &overrides);
+    assert!(matches!(result, Err(NameError::PathTooLong { max_len: 0, .. })));
+}